@@ -23,7 +23,8 @@ impl Render for ElementPlayerExample {
             .child(
                 video(self.video.clone())
                     .id("main-video")
-                    .buffer_capacity(30),
+                    .buffer_capacity(30)
+                    .state_badges(true),
             )
     }
 }
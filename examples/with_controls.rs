@@ -2,7 +2,7 @@ use gpui::{
     App, Application, Context, CursorStyle, Render, Window, WindowOptions, div, prelude::*,
 };
 use gpui_component::button::Button;
-use gpui_video_player::{Video, video};
+use gpui_video_player::{Video, time_label, video};
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 use url::Url;
@@ -120,7 +120,8 @@ impl Render for WithControlsExample {
                                     .gap_3()
                                     .child(back_5s)
                                     .child(play_pause)
-                                    .child(forward_5s),
+                                    .child(forward_5s)
+                                    .child(time_label(self.video.clone()).id("time")),
                             ),
                     ),
             )
@@ -0,0 +1,375 @@
+//! Animated media playbin does not play as video: numbered image sequences and
+//! animated GIF and PNG images.
+//!
+//! Both are built into pipelines ending in the same appsink as regular videos, so
+//! [`Video`](crate::Video) and its element play them like any other media.
+
+use crate::Error;
+use crate::pipeline::{VIDEO_SINK_NAME, validate_uri};
+use crate::runtime;
+use crate::video::{VideoOptions, sink_caps};
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use image::AnimationDecoder;
+use parking_lot::Mutex;
+use std::io::Cursor;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Browsers show GIF frames with shorter delays than this for 100 ms, since such
+/// delays were written by tools that left the delay unset; so does this crate.
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+/// Animated images are read whole and every frame is kept decoded, so larger ones
+/// are refused; they are better encoded as video. 512 MiB is about 60 frames of
+/// 1080p.
+const MAX_IMAGE_BYTES: usize = 64 << 20;
+const MAX_DECODED_BYTES: usize = 512 << 20;
+
+/// How long opening a remote animated image may spend downloading it.
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A source pipeline with the appsink `Video` pulls frames from, and the media's
+/// duration, which the sources cannot report themselves.
+pub(crate) struct AnimatedSource {
+    pub(crate) pipeline: gst::Pipeline,
+    pub(crate) video_sink: gst_app::AppSink,
+    pub(crate) duration: Duration,
+}
+
+fn appsink(options: &VideoOptions) -> gst_app::AppSink {
+    gst_app::AppSink::builder()
+        .name(VIDEO_SINK_NAME)
+        .drop(true)
+        .max_buffers(200)
+        .enable_last_sample(false)
+        .caps(&sink_caps(options))
+        .build()
+}
+
+/// The image format of a sequence, from the pattern's extension.
+fn sequence_format(pattern: &str) -> Option<(&'static str, &'static str)> {
+    let extension = Path::new(pattern)
+        .extension()?
+        .to_str()?
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some(("image/png", "pngdec")),
+        "jpg" | "jpeg" => Some(("image/jpeg", "jpegdec")),
+        "webp" => Some(("image/webp", "webpdec")),
+        _ => None,
+    }
+}
+
+/// Fill the printf-style index in `pattern` (`%d`, `%4d`, or `%04d`), as
+/// multifilesrc does. None if the pattern has no index.
+fn sequence_path(pattern: &str, index: u32) -> Option<String> {
+    let start = pattern.find('%')?;
+    let spec = &pattern[start + 1..];
+    let end = spec.find(|c: char| !c.is_ascii_digit())?;
+    if !spec[end..].starts_with('d') {
+        return None;
+    }
+    let digits = &spec[..end];
+    let number = match digits.parse::<usize>() {
+        Ok(width) if digits.starts_with('0') => format!("{index:0width$}"),
+        Ok(width) => format!("{index:width$}"),
+        Err(_) => index.to_string(),
+    };
+    Some(format!("{}{number}{}", &pattern[..start], &spec[end + 1..]))
+}
+
+/// Build a pipeline showing the images matching `pattern` at `fps`, numbered from
+/// 0 or 1 up to the first missing index.
+pub(crate) fn image_sequence(
+    pattern: &Path,
+    fps: f64,
+    options: &VideoOptions,
+) -> Result<AnimatedSource, Error> {
+    runtime::ensure_init()?;
+
+    let pattern = pattern.to_string_lossy();
+    let invalid = || Error::ImageSequence(pattern.to_string());
+    let (media_type, decoder) = sequence_format(&pattern).ok_or_else(invalid)?;
+    let path = |index| sequence_path(&pattern, index).ok_or_else(invalid);
+    let framerate = gst::Fraction::approximate_f64(fps)
+        .filter(|rate| rate.numer() > 0 && rate.denom() > 0)
+        .ok_or(Error::Framerate(fps))?;
+
+    let first = if Path::new(&path(0)?).exists() { 0 } else { 1 };
+    let mut count = 0;
+    while Path::new(&path(first + count)?).exists() {
+        count += 1;
+    }
+    if count == 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("no image matches {pattern}"),
+        )
+        .into());
+    }
+
+    let pipeline = gst::Pipeline::new();
+    let caps = gst::Caps::builder(media_type)
+        .field("framerate", framerate)
+        .build();
+    // With a framerate in its caps, multifilesrc timestamps the images and seeks
+    // by index.
+    let source = gst::ElementFactory::make("multifilesrc")
+        .property("location", &*pattern)
+        .property("index", first as i32)
+        .property("start-index", first as i32)
+        .property("stop-index", (first + count - 1) as i32)
+        .property("caps", &caps)
+        .build()?;
+    let decoder = gst::ElementFactory::make(decoder).build()?;
+    let convert = gst::ElementFactory::make("videoconvert").build()?;
+    let scale = gst::ElementFactory::make("videoscale").build()?;
+    let video_sink = appsink(options);
+
+    pipeline.add_many([&source, &decoder, &convert, &scale, video_sink.upcast_ref()])?;
+    gst::Element::link_many([&source, &decoder, &convert, &scale, video_sink.upcast_ref()])?;
+
+    Ok(AnimatedSource {
+        pipeline,
+        video_sink,
+        duration: Duration::from_secs_f64(count as f64 / fps),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImageKind {
+    Gif,
+    /// Animated PNG; plain PNGs are left to playbin.
+    Png,
+}
+
+fn image_kind(uri: &url::Url) -> Option<ImageKind> {
+    let extension = Path::new(uri.path())
+        .extension()?
+        .to_str()?
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "gif" => Some(ImageKind::Gif),
+        "png" | "apng" => Some(ImageKind::Png),
+        _ => None,
+    }
+}
+
+/// A decoded frame, on the timeline of the animation.
+struct ImageFrame {
+    pixels: Arc<[u8]>,
+    pts: gst::ClockTime,
+    duration: gst::ClockTime,
+}
+
+/// Build a pipeline playing the animated GIF or PNG at `uri`, or None if `uri`
+/// is not one.
+///
+/// GStreamer only decodes the first frame of these, so the image is decoded up
+/// front and its frames are pushed through an appsrc, timed by their delays.
+pub(crate) fn animated_image(
+    uri: &url::Url,
+    options: &VideoOptions,
+) -> Result<Option<AnimatedSource>, Error> {
+    let Some(kind) = image_kind(uri) else {
+        return Ok(None);
+    };
+    validate_uri(uri)?;
+
+    let bytes = read_uri(uri)?;
+    let frames = match kind {
+        ImageKind::Gif => image::codecs::gif::GifDecoder::new(Cursor::new(&bytes))?.into_frames(),
+        ImageKind::Png => {
+            let decoder = image::codecs::png::PngDecoder::new(Cursor::new(&bytes))?;
+            if !decoder.is_apng()? {
+                return Ok(None);
+            }
+            decoder.apng()?.into_frames()
+        }
+    };
+    let mut decoded = 0;
+    let frames = frames
+        .map(|frame| {
+            let frame = frame?;
+            decoded += frame.buffer().as_raw().len();
+            if decoded > MAX_DECODED_BYTES {
+                return Err(Error::ImageTooLarge(MAX_DECODED_BYTES));
+            }
+            Ok(frame)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let Some(first) = frames.first() else {
+        return Ok(None);
+    };
+    let (width, height) = first.buffer().dimensions();
+
+    let mut pts = gst::ClockTime::ZERO;
+    let frames: Arc<[ImageFrame]> = frames
+        .into_iter()
+        .map(|frame| {
+            let delay = Duration::from(frame.delay());
+            let delay = if delay < MIN_FRAME_DELAY {
+                DEFAULT_FRAME_DELAY
+            } else {
+                delay
+            };
+            let duration = gst::ClockTime::from_nseconds(delay.as_nanos() as u64);
+            let image = ImageFrame {
+                pixels: frame.into_buffer().into_raw().into(),
+                pts,
+                duration,
+            };
+            pts += duration;
+            image
+        })
+        .collect();
+    let total = pts;
+
+    // The delays vary, so the caps carry the average rate; frames keep their own.
+    let fps = frames.len() as f64 / total.seconds_f64();
+    let caps = gst::Caps::builder("video/x-raw")
+        .field("format", "RGBA")
+        .field("width", width as i32)
+        .field("height", height as i32)
+        .field(
+            "framerate",
+            gst::Fraction::approximate_f64(fps).unwrap_or(gst::Fraction::new(10, 1)),
+        )
+        .build();
+
+    let source = gst_app::AppSrc::builder()
+        .caps(&caps)
+        .format(gst::Format::Time)
+        .stream_type(gst_app::AppStreamType::Seekable)
+        .build();
+    source.set_duration(total);
+    let next = Arc::new(Mutex::new(0));
+    source.set_callbacks(
+        gst_app::AppSrcCallbacks::builder()
+            .need_data({
+                let frames = Arc::clone(&frames);
+                let next = Arc::clone(&next);
+                move |source, _| {
+                    let frame = {
+                        let mut next = next.lock();
+                        let frame = frames.get(*next);
+                        *next += 1;
+                        frame
+                    };
+                    let Some(frame) = frame else {
+                        if let Err(err) = source.end_of_stream() {
+                            log::debug!("failed to end animation stream: {err}");
+                        }
+                        return;
+                    };
+                    let mut buffer = gst::Buffer::from_slice(Arc::clone(&frame.pixels));
+                    if let Some(buffer) = buffer.get_mut() {
+                        buffer.set_pts(frame.pts);
+                        buffer.set_duration(frame.duration);
+                    }
+                    if let Err(err) = source.push_buffer(buffer) {
+                        log::debug!("failed to push animation frame: {err}");
+                    }
+                }
+            })
+            .seek_data({
+                let frames = Arc::clone(&frames);
+                move |_, position| {
+                    // Start from the frame showing at the position.
+                    *next.lock() = frames.partition_point(|frame| {
+                        (frame.pts + frame.duration).nseconds() <= position
+                    });
+                    true
+                }
+            })
+            .build(),
+    );
+
+    let pipeline = gst::Pipeline::new();
+    let convert = gst::ElementFactory::make("videoconvert").build()?;
+    let scale = gst::ElementFactory::make("videoscale").build()?;
+    let video_sink = appsink(options);
+    pipeline.add_many([
+        source.upcast_ref(),
+        &convert,
+        &scale,
+        video_sink.upcast_ref(),
+    ])?;
+    gst::Element::link_many([
+        source.upcast_ref(),
+        &convert,
+        &scale,
+        video_sink.upcast_ref(),
+    ])?;
+
+    Ok(Some(AnimatedSource {
+        pipeline,
+        video_sink,
+        duration: Duration::from_nanos(total.nseconds()),
+    }))
+}
+
+/// Read all of `uri`, through whichever GStreamer source handles its scheme.
+///
+/// This runs while the video opens, so remote images are given
+/// [`DOWNLOAD_TIMEOUT`] and both kinds are refused past [`MAX_IMAGE_BYTES`].
+fn read_uri(uri: &url::Url) -> Result<Vec<u8>, Error> {
+    if let Ok(path) = uri.to_file_path() {
+        if std::fs::metadata(&path)?.len() > MAX_IMAGE_BYTES as u64 {
+            return Err(Error::ImageTooLarge(MAX_IMAGE_BYTES));
+        }
+        return Ok(std::fs::read(path)?);
+    }
+    let pipeline = gst::Pipeline::new();
+    let source = gst::Element::make_from_uri(gst::URIType::Src, uri.as_str(), None)?;
+    let sink = gst_app::AppSink::builder().sync(false).build();
+    pipeline.add_many([&source, sink.upcast_ref()])?;
+    source.link(&sink)?;
+
+    let result = (|| -> Result<Vec<u8>, Error> {
+        let bus = pipeline.bus().ok_or(Error::Bus)?;
+        pipeline.set_state(gst::State::Playing)?;
+        let deadline = Instant::now() + DOWNLOAD_TIMEOUT;
+        let mut bytes = Vec::new();
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let timeout = gst::ClockTime::from_nseconds(remaining.as_nanos() as u64);
+            let Some(sample) = sink.try_pull_sample(timeout) else {
+                // A failed source stops pushing without an EOS, so check the bus
+                // before calling it a timeout.
+                if let Some(msg) = bus.pop_filtered(&[gst::MessageType::Error])
+                    && let gst::MessageView::Error(err) = msg.view()
+                {
+                    return Err(Error::from_bus(err, Some(uri.as_str())));
+                }
+                if sink.is_eos() {
+                    return Ok(bytes);
+                }
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    format!(
+                        "downloading {uri} took over {}s",
+                        DOWNLOAD_TIMEOUT.as_secs()
+                    ),
+                )
+                .into());
+            };
+            if let Some(buffer) = sample.buffer()
+                && let Ok(map) = buffer.map_readable()
+            {
+                bytes.extend_from_slice(&map);
+            }
+            if bytes.len() > MAX_IMAGE_BYTES {
+                return Err(Error::ImageTooLarge(MAX_IMAGE_BYTES));
+            }
+        }
+    })();
+    if let Err(err) = pipeline.set_state(gst::State::Null) {
+        log::debug!("failed to stop the download pipeline: {err}");
+    }
+    result
+}
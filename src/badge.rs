@@ -0,0 +1,159 @@
+use crate::locale::{StatusText, localizer};
+use crate::theme::PlayerTheme;
+use crate::video::Video;
+use gpui::{Bounds, Corners, Hsla, Pixels, Window};
+use std::f32::consts::TAU;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Dots around the buffering spinner.
+const SPINNER_DOTS: usize = 8;
+/// Time for the spinner's highlight to move one dot, in milliseconds.
+const SPINNER_STEP_MS: u128 = 100;
+
+/// Which state affordances a [`VideoElement`](crate::VideoElement) draws over the video.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct StateBadges {
+    pub(crate) paused_veil: bool,
+    pub(crate) buffering_spinner: bool,
+    pub(crate) error_badge: bool,
+}
+
+impl StateBadges {
+    pub(crate) fn any(self) -> bool {
+        self.paused_veil || self.buffering_spinner || self.error_badge
+    }
+}
+
+/// Paint the affordance for `video`'s current state over `bounds`: the error badge
+/// if playback failed, else the spinner while network buffering holds playback,
+/// else the veil while paused or ended. Only enabled affordances are drawn.
+pub(crate) fn paint_state_badges(
+    window: &mut Window,
+    cx: &mut gpui::App,
+    bounds: Bounds<Pixels>,
+    corner_radii: Corners<Pixels>,
+    video: &Video,
+    badges: StateBadges,
+    theme: &PlayerTheme,
+) {
+    // Scaled with the element, so thumbnails get small glyphs and full screen large ones.
+    let glyph =
+        (bounds.size.width.min(bounds.size.height) * 0.18).clamp(gpui::px(24.0), gpui::px(88.0));
+    if video.error().is_some() {
+        if badges.error_badge {
+            paint_error_badge(window, cx, bounds, theme);
+        }
+    } else if video.buffering_percent() < 100 {
+        if badges.buffering_spinner {
+            paint_spinner(window, bounds, glyph, theme);
+            // The spinner turns with time, not with frames.
+            window.request_animation_frame();
+        }
+    } else if (video.paused() || video.eos()) && badges.paused_veil {
+        window
+            .paint_quad(gpui::fill(bounds, theme.overlay.opacity(0.6)).corner_radii(corner_radii));
+        paint_play_glyph(window, bounds, glyph, theme);
+    }
+}
+
+/// A round button with a play triangle, centered in `bounds`.
+fn paint_play_glyph(
+    window: &mut Window,
+    bounds: Bounds<Pixels>,
+    size: Pixels,
+    theme: &PlayerTheme,
+) {
+    let center = bounds.center();
+    let circle = Bounds::centered_at(center, gpui::size(size, size));
+    window.paint_quad(gpui::fill(circle, theme.overlay).corner_radii(size / 2.0));
+
+    // Shifted right so the triangle looks centered rather than its bounding box.
+    let half = size * 0.22;
+    let mut triangle = gpui::PathBuilder::fill();
+    triangle.move_to(gpui::point(center.x - half * 0.8, center.y - half));
+    triangle.line_to(gpui::point(center.x + half * 1.2, center.y));
+    triangle.line_to(gpui::point(center.x - half * 0.8, center.y + half));
+    triangle.close();
+    match triangle.build() {
+        Ok(path) => window.paint_path(path, theme.foreground),
+        Err(err) => log::debug!("failed to build play glyph: {err}"),
+    }
+}
+
+/// A ring of dots with a highlight running around it, centered in `bounds`.
+fn paint_spinner(window: &mut Window, bounds: Bounds<Pixels>, size: Pixels, theme: &PlayerTheme) {
+    let center = bounds.center();
+    let radius = size * 0.4;
+    let dot = size * 0.12;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let head = (millis / SPINNER_STEP_MS) as usize % SPINNER_DOTS;
+    for index in 0..SPINNER_DOTS {
+        let angle = TAU * index as f32 / SPINNER_DOTS as f32;
+        let position = gpui::point(
+            center.x + radius * angle.sin(),
+            center.y - radius * angle.cos(),
+        );
+        // Dots fade out behind the highlight.
+        let age = (head + SPINNER_DOTS - index) % SPINNER_DOTS;
+        let opacity = 1.0 - age as f32 / SPINNER_DOTS as f32;
+        let bounds = Bounds::centered_at(position, gpui::size(dot, dot));
+        window.paint_quad(
+            gpui::fill(bounds, theme.foreground.opacity(opacity)).corner_radii(dot / 2.0),
+        );
+    }
+}
+
+/// A pill with a red dot and the localized error status, centered in `bounds`.
+fn paint_error_badge(
+    window: &mut Window,
+    cx: &mut gpui::App,
+    bounds: Bounds<Pixels>,
+    theme: &PlayerTheme,
+) {
+    let text = localizer(cx).status(StatusText::Error);
+    let run = gpui::TextRun {
+        len: text.len(),
+        font: window.text_style().font(),
+        color: theme.foreground,
+        background_color: None,
+        underline: None,
+        strikethrough: None,
+    };
+    let line = window
+        .text_system()
+        .shape_line(text, theme.font_size, &[run], None);
+
+    let padding = theme.font_size * 0.75;
+    let line_height = theme.font_size * 1.4;
+    let dot = theme.font_size * 0.6;
+    let size = gpui::size(
+        padding * 2.0 + dot + padding * 0.5 + line.width,
+        line_height + padding,
+    );
+    if size.width > bounds.size.width {
+        return;
+    }
+    let pill = Bounds::centered_at(bounds.center(), size);
+    window.paint_quad(gpui::fill(pill, theme.overlay).corner_radii(size.height / 2.0));
+
+    let dot_bounds = Bounds::centered_at(
+        gpui::point(pill.origin.x + padding + dot / 2.0, pill.center().y),
+        gpui::size(dot, dot),
+    );
+    window.paint_quad(gpui::fill(dot_bounds, error_color()).corner_radii(dot / 2.0));
+
+    let origin = gpui::point(
+        dot_bounds.right() + padding * 0.5,
+        pill.center().y - line_height / 2.0,
+    );
+    if let Err(err) = line.paint(origin, line_height, window, cx) {
+        log::debug!("failed to paint error badge: {err}");
+    }
+}
+
+fn error_color() -> Hsla {
+    gpui::red()
+}
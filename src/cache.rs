@@ -0,0 +1,81 @@
+use crate::Error;
+use parking_lot::Mutex;
+use std::path::PathBuf;
+
+static CACHE_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Store cached media artifacts (such as poster frames) under `dir` instead of the
+/// platform cache directory, or restore the default with `None`.
+pub fn set_cache_dir(dir: Option<PathBuf>) {
+    *CACHE_DIR.lock() = dir;
+}
+
+/// Directory holding cached media artifacts: the one set with [`set_cache_dir`],
+/// otherwise `gpui-video-player` inside the platform cache directory.
+pub fn cache_dir() -> PathBuf {
+    if let Some(dir) = CACHE_DIR.lock().clone() {
+        return dir;
+    }
+    platform_cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("gpui-video-player")
+}
+
+fn platform_cache_dir() -> Option<PathBuf> {
+    let env = |name: &str| std::env::var_os(name).map(PathBuf::from);
+    if cfg!(target_os = "windows") {
+        env("LOCALAPPDATA")
+    } else if cfg!(target_os = "macos") {
+        env("HOME").map(|home| home.join("Library/Caches"))
+    } else {
+        env("XDG_CACHE_HOME").or_else(|| env("HOME").map(|home| home.join(".cache")))
+    }
+}
+
+/// File name stem for artifacts of `uri`.
+///
+/// FNV-1a rather than `DefaultHasher`, whose output may change between Rust
+/// releases and would orphan every cached file.
+pub(crate) fn key(uri: &url::Url) -> String {
+    let hash = uri
+        .as_str()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    format!("{hash:016x}")
+}
+
+fn poster_path(uri: &url::Url) -> PathBuf {
+    cache_dir()
+        .join("posters")
+        .join(format!("{}.png", key(uri)))
+}
+
+/// Persist the chosen poster frame of `uri`.
+pub(crate) fn save_poster(uri: &url::Url, image: &image::RgbaImage) -> Result<(), Error> {
+    let path = poster_path(uri);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    image.save_with_format(&path, image::ImageFormat::Png)?;
+    Ok(())
+}
+
+/// The poster frame chosen for `uri` with
+/// [`Video::set_poster_time`](crate::Video::set_poster_time), if one was saved.
+///
+/// Reads only the cache, so galleries can show posters without opening the media.
+pub fn cached_poster(uri: &url::Url) -> Option<image::RgbaImage> {
+    let path = poster_path(uri);
+    if !path.exists() {
+        return None;
+    }
+    match image::open(&path) {
+        Ok(image) => Some(image.to_rgba8()),
+        Err(err) => {
+            log::warn!("failed to read cached poster {}: {}", path.display(), err);
+            None
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Runtime detection of optional GStreamer plugins, and diagnostics for
+//! incomplete installations.
+
+use crate::Error;
+use crate::runtime;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::fmt;
+
+/// Which optional GStreamer elements are installed, so apps can adapt their UI
+/// (e.g. hide HLS options or warn about missing hardware decoding) and attach
+/// the report to bug diagnostics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityReport {
+    /// Runtime GStreamer version, e.g. `GStreamer 1.24.2`.
+    pub gstreamer_version: String,
+    /// The newer `playbin3` element.
+    pub playbin3: bool,
+    /// VideoToolbox hardware decoding (macOS).
+    pub vtdec: bool,
+    /// VA-API hardware decoding (Linux), via either the `va` or legacy `vaapi` plugin.
+    pub vaapi: bool,
+    /// HTTP Live Streaming.
+    pub hlsdemux: bool,
+    /// SRT sources.
+    pub srt: bool,
+    /// WebRTC.
+    pub webrtc: bool,
+}
+
+impl CapabilityReport {
+    /// Whether any hardware video decoder was found.
+    pub fn hardware_decoding(&self) -> bool {
+        self.vtdec || self.vaapi
+    }
+}
+
+impl fmt::Display for CapabilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = |available: bool| if available { "yes" } else { "no" };
+        writeln!(f, "{}", self.gstreamer_version)?;
+        writeln!(f, "playbin3: {}", mark(self.playbin3))?;
+        writeln!(f, "vtdec: {}", mark(self.vtdec))?;
+        writeln!(f, "vaapi: {}", mark(self.vaapi))?;
+        writeln!(f, "hlsdemux: {}", mark(self.hlsdemux))?;
+        writeln!(f, "srt: {}", mark(self.srt))?;
+        write!(f, "webrtc: {}", mark(self.webrtc))
+    }
+}
+
+fn has_element(name: &str) -> bool {
+    gst::ElementFactory::find(name).is_some()
+}
+
+/// Whether the factory's klass metadata marks it as a hardware element.
+pub(crate) fn is_hardware(factory: &gst::ElementFactory) -> bool {
+    factory
+        .metadata(gst::ELEMENT_METADATA_KLASS)
+        .is_some_and(|klass| klass.contains("Hardware"))
+}
+
+/// Probe the GStreamer registry for optional elements.
+pub fn report() -> Result<CapabilityReport, Error> {
+    runtime::ensure_init()?;
+    Ok(CapabilityReport {
+        gstreamer_version: gst::version_string().to_string(),
+        playbin3: has_element("playbin3"),
+        vtdec: has_element("vtdec") || has_element("vtdec_hw"),
+        vaapi: has_element("vah264dec") || has_element("vaapidecodebin"),
+        hlsdemux: has_element("hlsdemux") || has_element("hlsdemux2"),
+        srt: has_element("srtsrc"),
+        webrtc: has_element("webrtcbin"),
+    })
+}
+
+/// Elements every video needs; playback fails without them.
+const REQUIRED_ELEMENTS: [&str; 8] = [
+    "playbin",
+    "decodebin",
+    "typefind",
+    "appsink",
+    "videoconvert",
+    "videoscale",
+    "audioconvert",
+    "autoaudiosink",
+];
+
+/// Codecs common enough that a missing decoder is worth flagging, with their caps.
+const COMMON_CODECS: [(&str, &str); 9] = [
+    ("H.264", "video/x-h264"),
+    ("H.265", "video/x-h265"),
+    ("VP8", "video/x-vp8"),
+    ("VP9", "video/x-vp9"),
+    ("AV1", "video/x-av1"),
+    ("AAC", "audio/mpeg, mpegversion=(int)4"),
+    ("MP3", "audio/mpeg, mpegversion=(int)1, layer=(int)3"),
+    ("Opus", "audio/x-opus"),
+    ("Vorbis", "audio/x-vorbis"),
+];
+
+/// The state of the GStreamer installation, for support requests and first-run
+/// checks. Made by [`diagnose`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnosis {
+    /// Plugins in the registry, loaded or not.
+    pub plugin_count: usize,
+    /// Required elements that are not installed.
+    pub missing_elements: Vec<&'static str>,
+    /// Plugins GStreamer found but could not load, e.g. because a library they
+    /// link against is missing. Deleting the registry cache retries them.
+    pub blacklisted_plugins: Vec<String>,
+    /// Common codecs and the decoder that would play them, or None if none is
+    /// installed.
+    pub decoders: Vec<(&'static str, Option<String>)>,
+    pub capabilities: CapabilityReport,
+}
+
+impl Diagnosis {
+    /// Whether every required element is installed.
+    pub fn is_ok(&self) -> bool {
+        self.missing_elements.is_empty()
+    }
+}
+
+impl fmt::Display for Diagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.capabilities)?;
+        writeln!(f, "plugins: {}", self.plugin_count)?;
+        if !self.missing_elements.is_empty() {
+            writeln!(f, "missing elements: {}", self.missing_elements.join(", "))?;
+        }
+        if !self.blacklisted_plugins.is_empty() {
+            writeln!(
+                f,
+                "blacklisted plugins: {}",
+                self.blacklisted_plugins.join(", ")
+            )?;
+        }
+        let decoders: Vec<String> = self
+            .decoders
+            .iter()
+            .map(|(codec, decoder)| format!("{codec}: {}", decoder.as_deref().unwrap_or("none")))
+            .collect();
+        write!(f, "decoders: {}", decoders.join(", "))
+    }
+}
+
+/// Check the GStreamer installation: required elements, plugins that failed to
+/// load, and decoders for common codecs. Slower than [`report`], since it walks
+/// the whole registry.
+pub fn diagnose() -> Result<Diagnosis, Error> {
+    let capabilities = report()?;
+    let registry = gst::Registry::get();
+    let plugins = registry.plugins();
+    let blacklisted_plugins = plugins
+        .iter()
+        .filter(|plugin| {
+            plugin
+                .plugin_flags()
+                .contains(gst::PluginFlags::BLACKLISTED)
+        })
+        .map(|plugin| plugin.plugin_name().to_string())
+        .collect();
+
+    let mut factories = gst::ElementFactory::factories_with_type(
+        gst::ElementFactoryType::DECODER,
+        gst::Rank::MARGINAL,
+    );
+    // Highest rank first, as decodebin would pick them.
+    factories.sort_by_key(|factory| std::cmp::Reverse(factory.rank()));
+    let decoders = COMMON_CODECS
+        .iter()
+        .map(|&(codec, caps)| {
+            let decoder = caps.parse::<gst::Caps>().ok().and_then(|caps| {
+                factories
+                    .iter()
+                    .find(|factory| factory.can_sink_any_caps(&caps))
+                    .map(|factory| factory.name().to_string())
+            });
+            (codec, decoder)
+        })
+        .collect();
+
+    Ok(Diagnosis {
+        plugin_count: plugins.len(),
+        missing_elements: REQUIRED_ELEMENTS
+            .into_iter()
+            .filter(|name| !has_element(name))
+            .collect(),
+        blacklisted_plugins,
+        decoders,
+        capabilities,
+    })
+}
+
+/// What a `missing-plugin` element message asks for, e.g. `H.265 (Main Profile)
+/// decoder`. decodebin posts one for each stream it has no decoder for.
+pub(crate) fn missing_plugin(s: &gst::StructureRef) -> Option<String> {
+    if !s.has_name("missing-plugin") {
+        return None;
+    }
+    // The poster's description, when it had one, already names the kind.
+    if let Ok(name) = s.get::<String>("name") {
+        return Some(name);
+    }
+    let kind = match s.get::<&str>("type").ok()? {
+        "decoder" => "decoder",
+        "encoder" => "encoder",
+        "urisource" => "URI source",
+        "urisink" => "URI sink",
+        _ => "element",
+    };
+    let detail = s
+        .get::<gst::Caps>("detail")
+        .map(|caps| caps.to_string())
+        .or_else(|_| s.get::<String>("detail"))
+        .ok()?;
+    Some(format!("{detail} {kind}"))
+}
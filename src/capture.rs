@@ -0,0 +1,225 @@
+//! Cameras and other capture devices as video sources.
+
+use crate::Error;
+use crate::pipeline::VIDEO_SINK_NAME;
+use crate::runtime;
+use crate::video::{VideoOptions, sink_caps};
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+
+/// Device properties that identify a device across runs, most specific first.
+/// Providers set different ones: v4l2 and Media Foundation a device path, AVFoundation
+/// a unique id, PipeWire an object path.
+const ID_PROPERTIES: [&str; 5] = [
+    "device.path",
+    "api.v4l2.path",
+    "avf.unique_id",
+    "object.path",
+    "device.strid",
+];
+
+/// Identifies a capture device found by [`list_video_devices`].
+///
+/// Built from the platform's device path or unique id where it reports one, else
+/// the device name, so it can be saved in settings to reopen the same camera.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DeviceId(String);
+
+impl DeviceId {
+    /// Recreate an id from [`DeviceId::as_str`], e.g. read from saved settings.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for DeviceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A resolution and frame rate a device captures at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaptureMode {
+    pub width: u32,
+    pub height: u32,
+    pub fps: f64,
+}
+
+/// A camera or other video capture device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoDevice {
+    pub id: DeviceId,
+    /// Human-readable name, e.g. "FaceTime HD Camera".
+    pub name: String,
+    /// Modes the device lists with fixed values; ones given as ranges are left out.
+    pub modes: Vec<CaptureMode>,
+}
+
+/// Options for [`Video::from_capture_device`](crate::Video::from_capture_device).
+#[derive(Debug, Clone, Default)]
+pub struct CaptureOptions {
+    /// Capture width in pixels. Defaults to None (the device's choice).
+    pub width: Option<u32>,
+    /// Capture height in pixels. Defaults to None (the device's choice).
+    pub height: Option<u32>,
+    /// Capture frame rate. Defaults to None (the device's choice).
+    pub fps: Option<f64>,
+    /// Flip the picture horizontally, as users expect of a preview of themselves.
+    /// Defaults to false.
+    pub mirror: bool,
+    /// Options for the video itself. Looping, seeking, and start or stop positions
+    /// do not apply to a live source.
+    pub video: VideoOptions,
+}
+
+fn device_id(device: &gst::Device) -> DeviceId {
+    let properties = device.properties();
+    let id = ID_PROPERTIES.iter().find_map(|name| {
+        properties
+            .as_ref()?
+            .get::<String>(*name)
+            .ok()
+            .filter(|id| !id.is_empty())
+    });
+    DeviceId(id.unwrap_or_else(|| device.display_name().to_string()))
+}
+
+fn capture_modes(device: &gst::Device) -> Vec<CaptureMode> {
+    let Some(caps) = device.caps() else {
+        return Vec::new();
+    };
+    let mut modes = Vec::new();
+    for s in caps.iter() {
+        let (Ok(width), Ok(height), Ok(fps)) = (
+            s.get::<i32>("width"),
+            s.get::<i32>("height"),
+            s.get::<gst::Fraction>("framerate"),
+        ) else {
+            continue;
+        };
+        let mode = CaptureMode {
+            width: width as u32,
+            height: height as u32,
+            fps: fps.numer() as f64 / fps.denom() as f64,
+        };
+        // Devices list each mode once per pixel format.
+        if !modes.contains(&mode) {
+            modes.push(mode);
+        }
+    }
+    modes
+}
+
+fn video_devices() -> Result<Vec<gst::Device>, Error> {
+    runtime::ensure_init()?;
+    let monitor = gst::DeviceMonitor::new();
+    monitor.add_filter(Some("Video/Source"), None);
+    monitor.start()?;
+    let devices = monitor.devices().into_iter().collect();
+    monitor.stop();
+    Ok(devices)
+}
+
+/// List the cameras and other video capture devices connected now, through
+/// GStreamer's device providers (AVFoundation on macOS, Video4Linux and PipeWire
+/// on Linux, Kernel Streaming and Media Foundation on Windows).
+///
+/// On macOS the app needs camera permission, and the first call may prompt for it.
+pub fn list_video_devices() -> Result<Vec<VideoDevice>, Error> {
+    Ok(video_devices()?
+        .iter()
+        .map(|device| VideoDevice {
+            id: device_id(device),
+            name: device.display_name().to_string(),
+            modes: capture_modes(device),
+        })
+        .collect())
+}
+
+/// Build a live pipeline capturing from the device `id`.
+pub(crate) fn capture_pipeline(
+    id: &DeviceId,
+    options: &CaptureOptions,
+) -> Result<(gst::Pipeline, gst_app::AppSink), Error> {
+    let device = video_devices()?
+        .into_iter()
+        .find(|device| device_id(device) == *id)
+        .ok_or_else(|| Error::CaptureDevice(id.to_string()))?;
+    // The device's provider configures its own source element (avfvideosrc,
+    // v4l2src, ksvideosrc, ...) for it.
+    let source = device.create_element(None)?;
+
+    // Cameras often only reach their larger modes as MJPEG, so accept that as well
+    // and let decodebin decode it.
+    let mut caps = gst::Caps::new_empty();
+    for media_type in ["video/x-raw", "image/jpeg"] {
+        let mut s = gst::Structure::new_empty(media_type);
+        if let Some(width) = options.width {
+            s.set("width", width as i32);
+        }
+        if let Some(height) = options.height {
+            s.set("height", height as i32);
+        }
+        if let Some(fps) = options.fps {
+            let fps = gst::Fraction::approximate_f64(fps).ok_or(Error::Framerate(fps))?;
+            s.set("framerate", fps);
+        }
+        caps.make_mut().append_structure(s);
+    }
+    let filter = gst::ElementFactory::make("capsfilter")
+        .property("caps", &caps)
+        .build()?;
+    let decode = gst::ElementFactory::make("decodebin").build()?;
+    let flip = gst::ElementFactory::make("videoflip")
+        .property_from_str(
+            "video-direction",
+            if options.mirror { "horiz" } else { "identity" },
+        )
+        .build()?;
+    let convert = gst::ElementFactory::make("videoconvert").build()?;
+    let scale = gst::ElementFactory::make("videoscale").build()?;
+    // Queued frames only add delay on a live source.
+    let video_sink = gst_app::AppSink::builder()
+        .name(VIDEO_SINK_NAME)
+        .drop(true)
+        .max_buffers(2)
+        .enable_last_sample(false)
+        .caps(&sink_caps(&options.video))
+        .build();
+
+    let pipeline = gst::Pipeline::new();
+    pipeline.add_many([
+        &source,
+        &filter,
+        &decode,
+        &flip,
+        &convert,
+        &scale,
+        video_sink.upcast_ref(),
+    ])?;
+    gst::Element::link_many([&source, &filter, &decode])?;
+    gst::Element::link_many([&flip, &convert, &scale, video_sink.upcast_ref()])?;
+
+    let flip_weak = flip.downgrade();
+    decode.connect_pad_added(move |_, pad| {
+        let Some(flip) = flip_weak.upgrade() else {
+            return;
+        };
+        let Some(sink_pad) = flip.static_pad("sink") else {
+            return;
+        };
+        if !sink_pad.is_linked()
+            && let Err(err) = pad.link(&sink_pad)
+        {
+            log::error!("failed to link capture stream: {err}");
+        }
+    });
+
+    Ok((pipeline, video_sink))
+}
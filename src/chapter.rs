@@ -0,0 +1,58 @@
+use gstreamer as gst;
+use std::time::Duration;
+
+/// A chapter read from the media's table of contents (e.g. MKV or MP4 chapters).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Chapter {
+    /// Chapter name, if the container provides one.
+    pub title: Option<String>,
+    pub start: Duration,
+    pub end: Duration,
+}
+
+/// Flatten the chapter entries of a TOC, sorted by start time.
+///
+/// Containers often leave the stop time unset, so missing ends are filled
+/// from the next chapter's start, or `duration` for the last one.
+pub(crate) fn chapters_from_toc(toc: &gst::TocRef, duration: Duration) -> Vec<Chapter> {
+    let mut entries = Vec::new();
+    for entry in toc.entries() {
+        collect_chapters(&entry, &mut entries);
+    }
+    entries.sort_by_key(|(start, _, _)| *start);
+
+    let mut chapters = Vec::with_capacity(entries.len());
+    for (i, (start, end, title)) in entries.iter().enumerate() {
+        let end = end
+            .or_else(|| entries.get(i + 1).map(|(next, _, _)| *next))
+            .unwrap_or(duration)
+            .max(*start);
+        chapters.push(Chapter {
+            title: title.clone(),
+            start: *start,
+            end,
+        });
+    }
+    chapters
+}
+
+/// Chapters may be nested under editions (MKV) or other chapters, so walk the whole tree.
+fn collect_chapters(
+    entry: &gst::TocEntryRef,
+    out: &mut Vec<(Duration, Option<Duration>, Option<String>)>,
+) {
+    if entry.entry_type() == gst::TocEntryType::Chapter
+        && let Some((start, stop)) = entry.start_stop_times()
+        && start >= 0
+    {
+        let title = entry.tags().and_then(|tags| {
+            tags.get::<gst::tags::Title>()
+                .map(|title| title.get().to_string())
+        });
+        let end = (stop >= 0).then(|| Duration::from_nanos(stop as u64));
+        out.push((Duration::from_nanos(start as u64), end, title));
+    }
+    for sub_entry in entry.sub_entries() {
+        collect_chapters(&sub_entry, out);
+    }
+}
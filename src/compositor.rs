@@ -0,0 +1,135 @@
+use crate::element::video;
+use crate::video::Video;
+use gpui::{
+    Bounds, ElementId, InteractiveElement, IntoElement, ParentElement, Pixels, RenderOnce, Styled,
+    Window, div,
+};
+
+/// A video drawn over the main one in a [`VideoCompositor`].
+#[derive(Debug, Clone)]
+pub struct Overlay {
+    video: Video,
+    rect: Bounds<f32>,
+    z_index: i32,
+    opacity: f32,
+}
+
+impl Overlay {
+    /// Draw `video` in `rect`, given as fractions (0.0..=1.0) of the compositor's
+    /// width and height, e.g. `Bounds::new(point(0.7, 0.7), size(0.25, 0.25))` for
+    /// a bottom-right inset. The video is letterboxed inside it.
+    pub fn new(video: Video, rect: Bounds<f32>) -> Self {
+        Self {
+            video,
+            rect,
+            z_index: 0,
+            opacity: 1.0,
+        }
+    }
+
+    /// Stacking order among overlays; higher draws on top, and equal ones keep the
+    /// order they were added in. Every overlay draws over the main video.
+    pub fn z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    /// Opacity from 0.0 (invisible) to 1.0. Defaults to 1.0.
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// Draws a main [`Video`] with other videos over it, for picture-in-picture or
+/// multi-camera layouts.
+///
+/// Each source is decoded and paced by its own pipeline. An overlay stays hidden
+/// until its first frame is decoded, so a camera that is slow to connect does not
+/// show as an empty box over the main video.
+#[derive(IntoElement)]
+pub struct VideoCompositor {
+    main: Video,
+    overlays: Vec<Overlay>,
+    size: Option<(Pixels, Pixels)>,
+    element_id: Option<ElementId>,
+}
+
+impl VideoCompositor {
+    pub fn new(main: Video) -> Self {
+        Self {
+            main,
+            overlays: Vec::new(),
+            size: None,
+            element_id: None,
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<ElementId>) -> Self {
+        self.element_id = Some(id.into());
+        self
+    }
+
+    /// Size of the whole composition. Defaults to the main video's display size.
+    pub fn size(mut self, width: Pixels, height: Pixels) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    pub fn overlay(mut self, overlay: Overlay) -> Self {
+        self.overlays.push(overlay);
+        self
+    }
+
+    pub fn overlays(mut self, overlays: impl IntoIterator<Item = Overlay>) -> Self {
+        self.overlays.extend(overlays);
+        self
+    }
+}
+
+impl RenderOnce for VideoCompositor {
+    fn render(self, _window: &mut Window, _cx: &mut gpui::App) -> impl IntoElement {
+        let (width, height) = self.size.unwrap_or_else(|| {
+            let (width, height) = self.main.display_size();
+            (gpui::px(width as f32), gpui::px(height as f32))
+        });
+        let mut root = div()
+            .relative()
+            .w(width)
+            .h(height)
+            .child(video(self.main).id("compositor-main").size(width, height));
+
+        // Ids follow the order overlays were added in, so a source keeps its element
+        // state when its z-index changes. The sort is stable, so overlays with equal
+        // z-index also draw in that order.
+        let mut overlays: Vec<_> = self.overlays.into_iter().enumerate().collect();
+        overlays.sort_by_key(|(_, overlay)| overlay.z_index);
+        for (index, overlay) in overlays {
+            // A source without a frame yet is still laid out, invisibly, so its
+            // element repaints the window once the first frame arrives.
+            let ready = overlay.video.frame_generation() > 0;
+            let element = video(overlay.video).id(("compositor-overlay", index)).size(
+                width * overlay.rect.size.width,
+                height * overlay.rect.size.height,
+            );
+            root = root.child(
+                div()
+                    .absolute()
+                    .left(width * overlay.rect.origin.x)
+                    .top(height * overlay.rect.origin.y)
+                    .opacity(if ready { overlay.opacity } else { 0.0 })
+                    .child(element),
+            );
+        }
+        // An id scopes the sources' element ids, so compositors can sit side by side.
+        match self.element_id {
+            Some(id) => div().id(id).child(root).into_any_element(),
+            None => root.into_any_element(),
+        }
+    }
+}
+
+/// Helper function to create a video compositor
+pub fn video_compositor(main: Video) -> VideoCompositor {
+    VideoCompositor::new(main)
+}
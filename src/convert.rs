@@ -0,0 +1,600 @@
+use gstreamer_video as gst_video;
+use rayon::prelude::*;
+use std::sync::OnceLock;
+use yuv::{
+    YuvBiPlanarImage, YuvConversionMode, YuvPlanarImage, YuvRange, YuvStandardMatrix,
+    yuv_nv12_to_bgra, yuv_nv12_to_rgba, yuv420_to_bgra, yuv420_to_rgba,
+};
+
+/// Byte order of converted 8-bit, 4-channel pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PixelOrder {
+    /// What GPUI's sprite atlas expects.
+    Bgra,
+    /// What the `image` crate expects.
+    Rgba,
+}
+
+/// Transfer characteristics of the decoded signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Transfer {
+    Sdr,
+    /// SMPTE ST 2084 (HDR10).
+    Pq,
+    /// ARIB STD-B67 hybrid log-gamma.
+    Hlg,
+}
+
+/// YUV range, matrix, and transfer used to convert decoded frames to RGB.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ColorSpace {
+    pub(crate) range: YuvRange,
+    pub(crate) matrix: YuvStandardMatrix,
+    pub(crate) transfer: Transfer,
+}
+
+impl ColorSpace {
+    /// Read the colorimetry negotiated in the caps. Unknown values fall back to
+    /// what players conventionally assume: limited range, BT.709 for HD and
+    /// BT.601 for SD content.
+    pub(crate) fn from_video_info(info: &gst_video::VideoInfo) -> Self {
+        let colorimetry = info.colorimetry();
+        let range = match colorimetry.range() {
+            gst_video::VideoColorRange::Range0_255 => YuvRange::Full,
+            _ => YuvRange::Limited,
+        };
+        let matrix = match colorimetry.matrix() {
+            gst_video::VideoColorMatrix::Bt709 => YuvStandardMatrix::Bt709,
+            gst_video::VideoColorMatrix::Bt601 => YuvStandardMatrix::Bt601,
+            gst_video::VideoColorMatrix::Bt2020 => YuvStandardMatrix::Bt2020,
+            gst_video::VideoColorMatrix::Smpte240m => YuvStandardMatrix::Smpte240,
+            gst_video::VideoColorMatrix::Fcc => YuvStandardMatrix::Fcc,
+            _ if info.height() >= 720 => YuvStandardMatrix::Bt709,
+            _ => YuvStandardMatrix::Bt601,
+        };
+        let transfer = match colorimetry.transfer() {
+            gst_video::VideoTransferFunction::Smpte2084 => Transfer::Pq,
+            gst_video::VideoTransferFunction::AribStdB67 => Transfer::Hlg,
+            _ => Transfer::Sdr,
+        };
+        Self {
+            range,
+            matrix,
+            transfer,
+        }
+    }
+
+    pub(crate) fn is_hdr(&self) -> bool {
+        self.transfer != Transfer::Sdr
+    }
+
+    /// Luma coefficients `(Kr, Kb)` of the matrix.
+    fn luma_coefficients(&self) -> (f32, f32) {
+        match self.matrix {
+            YuvStandardMatrix::Bt601 => (0.299, 0.114),
+            YuvStandardMatrix::Bt2020 => (0.2627, 0.0593),
+            YuvStandardMatrix::Smpte240 => (0.212, 0.087),
+            YuvStandardMatrix::Fcc => (0.30, 0.11),
+            _ => (0.2126, 0.0722),
+        }
+    }
+}
+
+impl Default for ColorSpace {
+    fn default() -> Self {
+        Self {
+            range: YuvRange::Limited,
+            matrix: YuvStandardMatrix::Bt709,
+            transfer: Transfer::Sdr,
+        }
+    }
+}
+
+/// Range of YUV sample values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorRange {
+    /// Luma 16-235, as nearly all video is encoded.
+    Limited,
+    /// Luma 0-255.
+    Full,
+}
+
+/// Matrix converting YUV to RGB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorMatrix {
+    /// SD video.
+    Bt601,
+    /// HD video.
+    Bt709,
+    /// UHD and HDR video.
+    Bt2020,
+    Smpte240m,
+    Fcc,
+}
+
+/// Range and matrix forced by the user for files whose colorimetry is flagged wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub(crate) struct ColorOverrides {
+    pub(crate) range: Option<ColorRange>,
+    pub(crate) matrix: Option<ColorMatrix>,
+}
+
+impl ColorOverrides {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.range.is_none() && self.matrix.is_none()
+    }
+
+    pub(crate) fn apply(&self, mut color: ColorSpace) -> ColorSpace {
+        if let Some(range) = self.range {
+            color.range = match range {
+                ColorRange::Limited => YuvRange::Limited,
+                ColorRange::Full => YuvRange::Full,
+            };
+        }
+        if let Some(matrix) = self.matrix {
+            color.matrix = match matrix {
+                ColorMatrix::Bt601 => YuvStandardMatrix::Bt601,
+                ColorMatrix::Bt709 => YuvStandardMatrix::Bt709,
+                ColorMatrix::Bt2020 => YuvStandardMatrix::Bt2020,
+                ColorMatrix::Smpte240m => YuvStandardMatrix::Smpte240,
+                ColorMatrix::Fcc => YuvStandardMatrix::Fcc,
+            };
+        }
+        color
+    }
+}
+
+/// Pixel format requested from the video sink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PixelFormat {
+    /// 8-bit 4:2:0 with interleaved chroma. Rendered natively on macOS.
+    Nv12,
+    /// 8-bit 4:2:0 with separate U and V planes.
+    I420,
+    Rgba,
+    Bgra,
+    /// 10-bit 4:2:0 in 16-bit little-endian words, used for HDR sources.
+    P010,
+}
+
+impl PixelFormat {
+    pub(crate) fn caps_name(self) -> &'static str {
+        match self {
+            PixelFormat::Nv12 => "NV12",
+            PixelFormat::I420 => "I420",
+            PixelFormat::Rgba => "RGBA",
+            PixelFormat::Bgra => "BGRA",
+            PixelFormat::P010 => "P010_10LE",
+        }
+    }
+
+    pub(crate) fn from_video_format(format: gst_video::VideoFormat) -> Option<Self> {
+        match format {
+            gst_video::VideoFormat::Nv12 => Some(PixelFormat::Nv12),
+            gst_video::VideoFormat::I420 => Some(PixelFormat::I420),
+            gst_video::VideoFormat::Rgba => Some(PixelFormat::Rgba),
+            gst_video::VideoFormat::Bgra => Some(PixelFormat::Bgra),
+            gst_video::VideoFormat::P01010le => Some(PixelFormat::P010),
+            _ => None,
+        }
+    }
+}
+
+/// Memory layout of the raw frames delivered by the video sink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FrameLayout {
+    pub(crate) format: Option<PixelFormat>,
+    /// Bytes per row of each plane.
+    pub(crate) stride: [usize; 3],
+    /// Byte offset of each plane from the start of the buffer.
+    pub(crate) offset: [usize; 3],
+}
+
+impl FrameLayout {
+    pub(crate) fn from_video_info(info: &gst_video::VideoInfo) -> Self {
+        let mut stride = [0; 3];
+        let mut offset = [0; 3];
+        for (plane, value) in info.stride().iter().take(3).enumerate() {
+            stride[plane] = (*value).max(0) as usize;
+        }
+        for (plane, value) in info.offset().iter().take(3).enumerate() {
+            offset[plane] = *value;
+        }
+        Self {
+            format: PixelFormat::from_video_format(info.format()),
+            stride,
+            offset,
+        }
+    }
+
+    /// Whether this is NV12 without row padding, as required by the CVPixelBuffer upload.
+    pub(crate) fn is_tight_nv12(&self, width: u32, height: u32) -> bool {
+        let width = width as usize;
+        self.format == Some(PixelFormat::Nv12)
+            && self.stride[0] == width
+            && self.stride[1] == width
+            && self.offset[0] == 0
+            && self.offset[1] == width * height as usize
+    }
+
+    /// Layout of the rows from `first_row` (even) down, as if they were a frame.
+    fn band(&self, first_row: u32) -> Self {
+        let mut band = *self;
+        for plane in 0..3 {
+            // Chroma planes of the 4:2:0 formats have one row per two luma rows.
+            let subsampled = plane > 0
+                && matches!(
+                    self.format,
+                    Some(PixelFormat::Nv12 | PixelFormat::I420 | PixelFormat::P010)
+                );
+            let rows = if subsampled { first_row / 2 } else { first_row };
+            band.offset[plane] += rows as usize * self.stride[plane];
+        }
+        band
+    }
+
+    /// Slice `rows` rows of `plane` out of `data`.
+    fn plane<'a>(&self, data: &'a [u8], plane: usize, rows: usize) -> Option<&'a [u8]> {
+        let start = self.offset[plane];
+        data.get(start..start + self.stride[plane] * rows)
+    }
+}
+
+/// Frames with at least this many pixels are converted on several threads; for
+/// smaller ones, handing out the bands costs more than it saves.
+const PARALLEL_MIN_PIXELS: usize = 1920 * 1080;
+
+/// Threads shared by every video's conversions, one per core. Kept apart from
+/// rayon's global pool so an app's own parallel work cannot hold up frames.
+fn conversion_pool() -> Option<&'static rayon::ThreadPool> {
+    static POOL: OnceLock<Option<rayon::ThreadPool>> = OnceLock::new();
+    POOL.get_or_init(|| {
+        rayon::ThreadPoolBuilder::new()
+            .thread_name(|index| format!("gpui-video-convert-{index}"))
+            .build()
+            .inspect_err(|err| log::warn!("failed to start frame conversion threads: {err}"))
+            .ok()
+    })
+    .as_ref()
+}
+
+/// Convert a decoded frame in any supported sink format to 4-channel pixels.
+/// Returns a black frame if the input is too short or conversion fails.
+///
+/// Large frames are split into bands of rows converted in parallel, since one
+/// core cannot convert 4K at 60 fps.
+pub(crate) fn frame_to(
+    order: PixelOrder,
+    layout: &FrameLayout,
+    color: ColorSpace,
+    data: &[u8],
+    width: u32,
+    height: u32,
+) -> Vec<u8> {
+    let mut out = vec![0u8; width as usize * height as usize * 4];
+    frame_into(order, layout, color, data, width, height, &mut out);
+    out
+}
+
+/// Like [`frame_to`], converting into `out`, which holds exactly `width * height`
+/// 4-byte pixels, so callers can reuse their buffers.
+pub(crate) fn frame_into(
+    order: PixelOrder,
+    layout: &FrameLayout,
+    color: ColorSpace,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    out: &mut [u8],
+) {
+    let pool = (width as usize * height as usize >= PARALLEL_MIN_PIXELS)
+        .then(conversion_pool)
+        .flatten()
+        .filter(|pool| pool.current_num_threads() > 1);
+    let converted = match pool {
+        Some(pool) => {
+            // Bands start on even rows, so they never split a row of 4:2:0 chroma.
+            let threads = pool.current_num_threads() as u32;
+            let band_rows = height.div_ceil(threads).next_multiple_of(2);
+            let row_bytes = width as usize * 4;
+            pool.install(|| {
+                out.par_chunks_mut(band_rows as usize * row_bytes)
+                    .enumerate()
+                    .all(|(band, out)| {
+                        let first_row = band as u32 * band_rows;
+                        let rows = (out.len() / row_bytes) as u32;
+                        let layout = layout.band(first_row);
+                        convert_into(order, &layout, color, data, width, rows, out).is_some()
+                    })
+            })
+        }
+        None => convert_into(order, layout, color, data, width, height, out).is_some(),
+    };
+    if !converted {
+        log::debug!("failed to convert {:?} frame", layout.format);
+        // Fall back to a black frame on conversion error
+        out.fill(0);
+    }
+}
+
+/// Convert `height` rows into `out`, which holds exactly that many tight rows.
+fn convert_into(
+    order: PixelOrder,
+    layout: &FrameLayout,
+    color: ColorSpace,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    out: &mut [u8],
+) -> Option<()> {
+    match layout.format {
+        Some(PixelFormat::Nv12) => nv12_to(order, layout, color, data, width, height, out),
+        Some(PixelFormat::I420) => i420_to(order, layout, color, data, width, height, out),
+        Some(PixelFormat::P010) => p010_to(order, layout, color, data, width, height, out),
+        Some(format @ (PixelFormat::Rgba | PixelFormat::Bgra)) => {
+            packed_to(order, format, layout, data, width, height, out)
+        }
+        None => None,
+    }
+}
+
+/// Convert NV12 YUV data using optimized yuvutils-rs.
+fn nv12_to(
+    order: PixelOrder,
+    layout: &FrameLayout,
+    color: ColorSpace,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    out: &mut [u8],
+) -> Option<()> {
+    let chroma_rows = height.div_ceil(2) as usize;
+    let yuv_bi_planar = YuvBiPlanarImage {
+        y_plane: layout.plane(data, 0, height as usize)?,
+        y_stride: layout.stride[0] as u32,
+        uv_plane: layout.plane(data, 1, chroma_rows)?,
+        uv_stride: layout.stride[1] as u32,
+        width,
+        height,
+    };
+
+    let out_stride = width * 4;
+    match order {
+        PixelOrder::Bgra => yuv_nv12_to_bgra(
+            &yuv_bi_planar,
+            out,
+            out_stride,
+            color.range,
+            color.matrix,
+            YuvConversionMode::Balanced,
+        ),
+        PixelOrder::Rgba => yuv_nv12_to_rgba(
+            &yuv_bi_planar,
+            out,
+            out_stride,
+            color.range,
+            color.matrix,
+            YuvConversionMode::Balanced,
+        ),
+    }
+    .ok()
+}
+
+/// Convert I420 YUV data using optimized yuvutils-rs.
+fn i420_to(
+    order: PixelOrder,
+    layout: &FrameLayout,
+    color: ColorSpace,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    out: &mut [u8],
+) -> Option<()> {
+    let chroma_rows = height.div_ceil(2) as usize;
+    let planar = YuvPlanarImage {
+        y_plane: layout.plane(data, 0, height as usize)?,
+        y_stride: layout.stride[0] as u32,
+        u_plane: layout.plane(data, 1, chroma_rows)?,
+        u_stride: layout.stride[1] as u32,
+        v_plane: layout.plane(data, 2, chroma_rows)?,
+        v_stride: layout.stride[2] as u32,
+        width,
+        height,
+    };
+
+    let out_stride = width * 4;
+    match order {
+        PixelOrder::Bgra => yuv420_to_bgra(&planar, out, out_stride, color.range, color.matrix),
+        PixelOrder::Rgba => yuv420_to_rgba(&planar, out, out_stride, color.range, color.matrix),
+    }
+    .ok()
+}
+
+/// Repack RGBA/BGRA rows into a tight buffer, swapping channels if needed.
+fn packed_to(
+    order: PixelOrder,
+    format: PixelFormat,
+    layout: &FrameLayout,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    out: &mut [u8],
+) -> Option<()> {
+    let row_len = width as usize * 4;
+    let plane = layout.plane(data, 0, height as usize)?;
+    let swap = matches!(
+        (format, order),
+        (PixelFormat::Rgba, PixelOrder::Bgra) | (PixelFormat::Bgra, PixelOrder::Rgba)
+    );
+
+    for (row, out) in plane
+        .chunks(layout.stride[0])
+        .zip(out.chunks_exact_mut(row_len))
+        .take(height as usize)
+    {
+        let row = row.get(..row_len)?;
+        if swap {
+            for (px, out) in row.chunks_exact(4).zip(out.chunks_exact_mut(4)) {
+                out.copy_from_slice(&[px[2], px[1], px[0], px[3]]);
+            }
+        } else {
+            out.copy_from_slice(row);
+        }
+    }
+    Some(())
+}
+
+/// SDR reference white in nits; HDR signals are normalized so this maps to 1.0.
+const SDR_WHITE_NITS: f32 = 203.0;
+/// Assumed mastering peak for tone mapping, relative to SDR white.
+const HDR_PEAK: f32 = 1000.0 / SDR_WHITE_NITS;
+
+/// Convert P010 (10-bit NV12 layout, little endian) to 8-bit pixels, tone-mapping
+/// PQ/HLG content to SDR with BT.709 primaries.
+fn p010_to(
+    order: PixelOrder,
+    layout: &FrameLayout,
+    color: ColorSpace,
+    data: &[u8],
+    width: u32,
+    height: u32,
+    out: &mut [u8],
+) -> Option<()> {
+    let width = width as usize;
+    let height = height as usize;
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let y_plane = layout.plane(data, 0, height)?;
+    let uv_plane = layout.plane(data, 1, height.div_ceil(2))?;
+    if layout.stride[0] < width * 2 || layout.stride[1] < width.div_ceil(2) * 4 {
+        return None;
+    }
+
+    let sample = |plane: &[u8], byte: usize| -> f32 {
+        let bytes = [plane[byte], plane[byte + 1]];
+        (u16::from_le_bytes(bytes) >> 6) as f32
+    };
+    let (y_offset, y_scale, c_scale) = match color.range {
+        YuvRange::Full => (0.0, 1023.0, 1023.0),
+        YuvRange::Limited => (64.0, 876.0, 896.0),
+    };
+    let (kr, kb) = color.luma_coefficients();
+    let kg = 1.0 - kr - kb;
+    let eotf = linearize_lut(color.transfer);
+    let oetf = encode_lut();
+
+    if out.len() < width * height * 4 {
+        return None;
+    }
+    for row in 0..height {
+        let y_row = row * layout.stride[0];
+        let uv_row = (row / 2) * layout.stride[1];
+        for col in 0..width {
+            let luma = (sample(y_plane, y_row + col * 2) - y_offset) / y_scale;
+            let uv = uv_row + (col / 2) * 4;
+            let cb = (sample(uv_plane, uv) - 512.0) / c_scale;
+            let cr = (sample(uv_plane, uv + 2) - 512.0) / c_scale;
+
+            let r = luma + 2.0 * (1.0 - kr) * cr;
+            let b = luma + 2.0 * (1.0 - kb) * cb;
+            let g = (luma - kr * r - kb * b) / kg;
+            let mut rgb = [r, g, b];
+
+            if color.transfer != Transfer::Sdr {
+                rgb = rgb.map(|c| lookup(eotf, c));
+                rgb = tone_map(rgb);
+                rgb = bt2020_to_bt709(rgb);
+                rgb = rgb.map(|c| lookup(oetf, c));
+            }
+
+            let [r, g, b] = rgb.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+            let px = (row * width + col) * 4;
+            let pixel = match order {
+                PixelOrder::Bgra => [b, g, r, 255],
+                PixelOrder::Rgba => [r, g, b, 255],
+            };
+            out[px..px + 4].copy_from_slice(&pixel);
+        }
+    }
+    Some(())
+}
+
+fn lookup(table: &[f32], value: f32) -> f32 {
+    let last = table.len() - 1;
+    let index = (value.clamp(0.0, 1.0) * last as f32).round() as usize;
+    table[index.min(last)]
+}
+
+/// Table mapping a non-linear HDR signal in `0.0..=1.0` to linear light, where
+/// 1.0 is SDR reference white.
+fn linearize_lut(transfer: Transfer) -> &'static [f32] {
+    static PQ: OnceLock<Vec<f32>> = OnceLock::new();
+    static HLG: OnceLock<Vec<f32>> = OnceLock::new();
+    let build = |f: fn(f32) -> f32| (0..1024).map(|i| f(i as f32 / 1023.0)).collect::<Vec<_>>();
+    match transfer {
+        Transfer::Hlg => HLG.get_or_init(|| build(hlg_to_linear)),
+        _ => PQ.get_or_init(|| build(pq_to_linear)),
+    }
+}
+
+/// Table mapping linear light in `0.0..=1.0` to the sRGB-encoded signal.
+fn encode_lut() -> &'static [f32] {
+    static SRGB: OnceLock<Vec<f32>> = OnceLock::new();
+    SRGB.get_or_init(|| {
+        (0..4096)
+            .map(|i| {
+                let c = i as f32 / 4095.0;
+                if c <= 0.003_130_8 {
+                    c * 12.92
+                } else {
+                    1.055 * c.powf(1.0 / 2.4) - 0.055
+                }
+            })
+            .collect()
+    })
+}
+
+fn pq_to_linear(e: f32) -> f32 {
+    const M1: f32 = 0.159_301_76;
+    const M2: f32 = 78.843_75;
+    const C1: f32 = 0.835_937_5;
+    const C2: f32 = 18.851_563;
+    const C3: f32 = 18.6875;
+    let p = e.powf(1.0 / M2);
+    let nits = ((p - C1).max(0.0) / (C2 - C3 * p)).powf(1.0 / M1) * 10_000.0;
+    nits / SDR_WHITE_NITS
+}
+
+fn hlg_to_linear(e: f32) -> f32 {
+    const A: f32 = 0.178_832_77;
+    const B: f32 = 0.284_668_92;
+    const C: f32 = 0.559_910_7;
+    // HLG reference white sits at 75% signal.
+    const REFERENCE_WHITE: f32 = 0.264_962_4;
+    let scene = if e <= 0.5 {
+        e * e / 3.0
+    } else {
+        (((e - C) / A).exp() + B) / 12.0
+    };
+    scene / REFERENCE_WHITE
+}
+
+/// Extended Reinhard on luminance, compressing `HDR_PEAK` to 1.0.
+fn tone_map([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let luminance = 0.2627 * r + 0.6780 * g + 0.0593 * b;
+    if luminance <= 0.0 {
+        return [0.0; 3];
+    }
+    let mapped = luminance * (1.0 + luminance / (HDR_PEAK * HDR_PEAK)) / (1.0 + luminance);
+    let scale = mapped / luminance;
+    [r * scale, g * scale, b * scale]
+}
+
+fn bt2020_to_bt709([r, g, b]: [f32; 3]) -> [f32; 3] {
+    [
+        1.6605 * r - 0.5876 * g - 0.0728 * b,
+        -0.1246 * r + 1.1329 * g - 0.0083 * b,
+        -0.0182 * r - 0.1006 * g + 1.1187 * b,
+    ]
+}
@@ -0,0 +1,110 @@
+//! Diagnostics for verifying that videos release their GStreamer resources.
+
+use crate::Video;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static LIVE_PIPELINES: AtomicUsize = AtomicUsize::new(0);
+static LIVE_WORKERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts of resources currently held by videos in this process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceCounts {
+    /// Pipelines that have not been shut down to `Null`.
+    pub pipelines: usize,
+    /// Frame worker threads that have not exited.
+    pub workers: usize,
+}
+
+/// Result of [`open_close_cycles`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleReport {
+    pub cycles: usize,
+    /// Cycles whose video failed to open. Their error paths are checked for leaks
+    /// like the rest.
+    pub failed: usize,
+    pub before: ResourceCounts,
+    pub after: ResourceCounts,
+}
+
+impl CycleReport {
+    /// Whether any pipeline or worker thread outlived its video.
+    pub fn leaked(&self) -> bool {
+        self.after.pipelines > self.before.pipelines || self.after.workers > self.before.workers
+    }
+}
+
+/// Resources currently held by all videos.
+pub fn live_resources() -> ResourceCounts {
+    ResourceCounts {
+        pipelines: LIVE_PIPELINES.load(Ordering::SeqCst),
+        workers: LIVE_WORKERS.load(Ordering::SeqCst),
+    }
+}
+
+/// Open and drop a video `n` times, reporting resources still held afterwards.
+///
+/// Intended for stress tests of apps that open many clips per session; a
+/// [`CycleReport::leaked`] result means teardown did not reclaim everything.
+/// Failed opens are counted rather than returned, since a failing open can leak too.
+pub fn open_close_cycles(uri: &url::Url, n: usize) -> CycleReport {
+    let before = live_resources();
+    let mut failed = 0;
+    for _ in 0..n {
+        match Video::new(uri) {
+            Ok(video) => drop(video),
+            Err(err) => {
+                log::debug!("open/close cycle failed to open {uri}: {err}");
+                failed += 1;
+            }
+        }
+    }
+    let after = live_resources();
+    if after != before {
+        log::warn!("resources leaked after {n} open/close cycles: {before:?} -> {after:?}");
+    }
+    CycleReport {
+        cycles: n,
+        failed,
+        before,
+        after,
+    }
+}
+
+/// Marks a pipeline as live until [`PipelineGuard::release`]. Deliberately not
+/// released on drop, so a video dropped without shutting down its pipeline is
+/// reported as a leak.
+#[derive(Debug)]
+pub(crate) struct PipelineGuard {
+    released: bool,
+}
+
+impl PipelineGuard {
+    pub(crate) fn new() -> Self {
+        LIVE_PIPELINES.fetch_add(1, Ordering::SeqCst);
+        Self { released: false }
+    }
+
+    /// Record that the pipeline has been shut down.
+    pub(crate) fn release(&mut self) {
+        if !std::mem::replace(&mut self.released, true) {
+            LIVE_PIPELINES.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Marks a frame worker thread as live for as long as it is held.
+#[derive(Debug)]
+pub(crate) struct WorkerGuard;
+
+impl WorkerGuard {
+    pub(crate) fn new() -> Self {
+        LIVE_WORKERS.fetch_add(1, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for WorkerGuard {
+    fn drop(&mut self) {
+        LIVE_WORKERS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
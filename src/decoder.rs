@@ -0,0 +1,148 @@
+use crate::capabilities::is_hardware;
+use crate::stats::PlaybackStats;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// `GstAutoplugSelectResult` values: try this factory, or skip to the next one.
+const AUTOPLUG_TRY: i32 = 0;
+const AUTOPLUG_SKIP: i32 = 2;
+
+/// Whether a [`Video`](crate::Video) decodes with hardware decoders, set with
+/// [`VideoOptions::hardware_decoding`](crate::VideoOptions::hardware_decoding).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HwPreference {
+    /// Let GStreamer's decoder ranking choose, which favors hardware decoders on
+    /// most platforms.
+    #[default]
+    Auto,
+    /// Only use hardware decoders. Creating the video fails when none is installed,
+    /// and a codec none of them handle fails with a missing decoder error.
+    Require,
+    /// Only use software decoders, e.g. to rule out a driver bug.
+    Disable,
+}
+
+/// The video decoder a [`Video`](crate::Video) is using, from
+/// [`Video::decoder_info`](crate::Video::decoder_info).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecoderInfo {
+    /// Element factory name, e.g. `vtdec_hw`, `vah264dec`, `d3d11h264dec`,
+    /// `nvh264dec`, or `avdec_h264`.
+    pub name: String,
+    pub hardware: bool,
+}
+
+fn is_video_decoder(factory: &gst::ElementFactory) -> bool {
+    factory
+        .metadata(gst::ELEMENT_METADATA_KLASS)
+        .is_some_and(|klass| klass.contains("Decoder") && klass.contains("Video"))
+}
+
+/// Whether any hardware video decoder is installed.
+pub(crate) fn hardware_decoder_installed() -> bool {
+    gst::ElementFactory::factories_with_type(
+        gst::ElementFactoryType::DECODER | gst::ElementFactoryType::MEDIA_VIDEO,
+        gst::Rank::NONE,
+    )
+    .iter()
+    .any(is_hardware)
+}
+
+/// Make every decodebin in `pipeline` skip the video decoders `preference` rules
+/// out. Decodebins are created when the pipeline starts, so this watches for them.
+pub(crate) fn restrict_decoders(pipeline: &gst::Pipeline, preference: HwPreference) {
+    if preference == HwPreference::Auto {
+        return;
+    }
+    pipeline.connect_deep_element_added(move |_, _, element| {
+        let is_decodebin = element
+            .factory()
+            .is_some_and(|factory| factory.name() == "decodebin");
+        if !is_decodebin {
+            return;
+        }
+        element.connect("autoplug-select", false, move |args| {
+            let factory = args
+                .get(3)
+                .and_then(|arg| arg.get::<gst::ElementFactory>().ok());
+            let allowed = factory.as_ref().is_none_or(|factory| {
+                !is_video_decoder(factory)
+                    || match preference {
+                        HwPreference::Auto => true,
+                        HwPreference::Require => is_hardware(factory),
+                        HwPreference::Disable => !is_hardware(factory),
+                    }
+            });
+            if let Some(factory) = factory.filter(|_| !allowed) {
+                log::debug!("skipping decoder {} ({preference:?})", factory.name());
+            }
+            // The signal returns an enum type, so a plain integer would be rejected.
+            glib::Type::from_name("GstAutoplugSelectResult")
+                .and_then(glib::EnumClass::with_type)
+                .and_then(|class| {
+                    class.to_value(if allowed { AUTOPLUG_TRY } else { AUTOPLUG_SKIP })
+                })
+        });
+    });
+}
+
+/// Time every video decoder added to `pipeline` and measure its input bitrate,
+/// recording both in `stats`.
+pub(crate) fn time_decoders(pipeline: &gst::Pipeline, stats: &Arc<Mutex<PlaybackStats>>) {
+    let stats = Arc::downgrade(stats);
+    pipeline.connect_deep_element_added(move |_, _, element| {
+        if !element
+            .factory()
+            .is_some_and(|factory| is_video_decoder(&factory))
+        {
+            return;
+        }
+        let (Some(sink), Some(src)) = (element.static_pad("sink"), element.static_pad("src"))
+        else {
+            return;
+        };
+        let input = stats.clone();
+        sink.add_probe(
+            gst::PadProbeType::BUFFER | gst::PadProbeType::EVENT_FLUSH,
+            move |_, info| {
+                if let Some(stats) = input.upgrade() {
+                    if let Some(buffer) = info.buffer()
+                        && let Some(pts) = buffer.pts()
+                    {
+                        stats
+                            .lock()
+                            .record_decoder_input(pts.nseconds(), buffer.size());
+                    } else if info.event().is_some() {
+                        stats.lock().clear_decoding();
+                    }
+                }
+                gst::PadProbeReturn::Ok
+            },
+        );
+        let output = stats.clone();
+        src.add_probe(gst::PadProbeType::BUFFER, move |_, info| {
+            if let Some(stats) = output.upgrade()
+                && let Some(pts) = info.buffer().and_then(|buffer| buffer.pts())
+            {
+                stats.lock().record_decoder_output(pts.nseconds());
+            }
+            gst::PadProbeReturn::Ok
+        });
+    });
+}
+
+/// The first video decoder found in `pipeline`.
+pub(crate) fn video_decoder(pipeline: &gst::Pipeline) -> Option<DecoderInfo> {
+    pipeline
+        .iterate_recurse()
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter_map(|element| element.factory())
+        .find(is_video_decoder)
+        .map(|factory| DecoderInfo {
+            name: factory.name().to_string(),
+            hardware: is_hardware(&factory),
+        })
+}
@@ -0,0 +1,42 @@
+//! DMA-BUF frames from VA-API decoders on Linux.
+//!
+//! With a VA decoder, system-memory caps make GStreamer download every frame from
+//! the GPU surface and then copy it again in `videoconvert`. Negotiating DMA-BUF
+//! instead exports the decoded surface as-is, and the one copy left is the upload
+//! into gpui's sprite atlas: gpui 0.2 cannot import external textures on Linux yet.
+
+use crate::Error;
+use gstreamer as gst;
+use gstreamer_video as gst_video;
+
+const DMABUF_FEATURE: &str = "memory:DMABuf";
+
+/// Whether DMA-BUF frames can be negotiated: the `va` plugin is installed and
+/// GStreamer is new enough to describe DMA-BUF layouts (1.24).
+pub(crate) fn available() -> bool {
+    let (major, minor, _, _) = gst::version();
+    (major, minor) >= (1, 24) && gst::ElementFactory::find("vapostproc").is_some()
+}
+
+/// Caps for linear NV12 DMA-BUF frames, offered ahead of system memory so any
+/// other decoder falls back to the regular path.
+pub(crate) fn caps() -> gst::Caps {
+    gst::Caps::builder("video/x-raw")
+        .features([DMABUF_FEATURE])
+        .field("format", "DMA_DRM")
+        // A DRM fourcc without modifier means a linear layout, which can be mapped.
+        .field("drm-format", "NV12")
+        .build()
+}
+
+pub(crate) fn is_dmabuf(caps: &gst::CapsRef) -> bool {
+    caps.features(0)
+        .is_some_and(|features| features.contains(DMABUF_FEATURE))
+}
+
+/// Plane layout of negotiated DMA-BUF caps, as for system memory.
+pub(crate) fn video_info(caps: &gst::CapsRef) -> Result<gst_video::VideoInfo, Error> {
+    gst_video::VideoInfoDmaDrm::from_caps(caps)
+        .and_then(|info| info.to_video_info())
+        .map_err(|_| Error::Caps)
+}
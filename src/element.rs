@@ -1,3 +1,10 @@
+use crate::badge::{self, StateBadges};
+use crate::convert::{self, ColorOverrides, PixelOrder};
+use crate::hud;
+use crate::metadata::Orientation;
+use crate::repaint;
+use crate::theme::player_theme;
+use crate::thread;
 use crate::video::Video;
 #[cfg(target_os = "macos")]
 use core_foundation::{
@@ -11,26 +18,142 @@ use core_video::pixel_buffer::{CVPixelBuffer, kCVPixelFormatType_420YpCbCr8BiPla
 #[cfg(target_os = "macos")]
 use core_video::r#return::kCVReturnSuccess;
 use gpui::{
-    Element, ElementId, GlobalElementId, InspectorElementId, IntoElement, LayoutId, Window,
+    Element, ElementId, GlobalElementId, InspectorElementId, IntoElement, LayoutId, ParentElement,
+    Styled, Window, div,
 };
+use gstreamer as gst;
 use std::sync::Arc;
-use yuv::{YuvBiPlanarImage, YuvConversionMode, YuvRange, YuvStandardMatrix, yuv_nv12_to_bgra};
+use std::time::Instant;
+
+/// Identifies the image converted from a frame. Repaints of an unchanged frame, as
+/// on a window resize or overlay hover, reuse the image instead of converting again.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RenderKey {
+    video_id: u64,
+    pts: gst::ClockTime,
+    orientation: Orientation,
+    crop: Option<gpui::Bounds<f32>>,
+    color_overrides: ColorOverrides,
+}
+
+/// The last image an element uploaded, and the frame it was converted from.
+type RenderCache = Option<(Option<RenderKey>, Arc<gpui::RenderImage>)>;
+
+/// Map limited-range samples (`black` up to `black + span`) onto full range around
+/// `center`, rounding and clamping.
+#[cfg(target_os = "macos")]
+const fn expand_range(black: i32, span: i32, center: i32) -> [u8; 256] {
+    let mut lut = [0; 256];
+    let mut value = 0;
+    while value < 256 {
+        let full = center + ((value - black) * 255 + span / 2).div_euclid(span);
+        lut[value as usize] = if full < 0 {
+            0
+        } else if full > 255 {
+            255
+        } else {
+            full as u8
+        };
+        value += 1;
+    }
+    lut
+}
+
+#[cfg(target_os = "macos")]
+const LIMITED_LUMA: [u8; 256] = expand_range(16, 219, 0);
+#[cfg(target_os = "macos")]
+const LIMITED_CHROMA: [u8; 256] = expand_range(128, 224, 128);
+
+/// Copy a row of NV12 samples, mapped through `lut` if given.
+#[cfg(target_os = "macos")]
+fn copy_row(src: &[u8], dst: &mut [u8], lut: Option<&[u8; 256]>) {
+    match lut {
+        Some(lut) => {
+            for (dst, src) in dst.iter_mut().zip(src) {
+                *dst = lut[*src as usize];
+            }
+        }
+        None => dst.copy_from_slice(src),
+    }
+}
 
 /// A video element that implements Element trait similar to GPUI's img element
+#[derive(Clone)]
 pub struct VideoElement {
     video: Video,
     display_width: Option<gpui::Pixels>,
     display_height: Option<gpui::Pixels>,
     element_id: Option<ElementId>,
+    poster: Option<Arc<gpui::RenderImage>>,
+    error_placeholder: Option<Arc<gpui::RenderImage>>,
+    corner_radii: gpui::Corners<gpui::Pixels>,
+    border: Option<(gpui::Pixels, gpui::Hsla)>,
+    shadows: Vec<gpui::BoxShadow>,
+    crop: Option<gpui::Bounds<f32>>,
+    opacity: f32,
+    grayscale: bool,
+    tint: Option<gpui::Hsla>,
+    state_badges: StateBadges,
+    debug_overlay: bool,
+    stats_overlay: bool,
+    source_location: &'static core::panic::Location<'static>,
+}
+
+/// Diagnostics shown for a [`VideoElement`] in gpui's inspector; see
+/// [`register_video_inspector`].
+#[cfg(any(feature = "inspector", debug_assertions))]
+#[derive(Debug, Clone, Default)]
+pub struct VideoInspectorState {
+    pub frame_size: (i32, i32),
+    pub framerate: f64,
+    pub buffered_frames: usize,
+    pub position: std::time::Duration,
+    pub paused: bool,
+}
+
+/// Show [`VideoInspectorState`] when a video element is picked in gpui's inspector.
+#[cfg(any(feature = "inspector", debug_assertions))]
+pub fn register_video_inspector(cx: &mut gpui::App) {
+    use gpui::{ParentElement, Styled};
+
+    cx.register_inspector_element(|_id, state: &VideoInspectorState, _window, _cx| {
+        gpui::div()
+            .flex()
+            .flex_col()
+            .child(format!(
+                "Frame: {}x{} @ {:.2} fps",
+                state.frame_size.0, state.frame_size.1, state.framerate
+            ))
+            .child(format!("Buffered frames: {}", state.buffered_frames))
+            .child(format!(
+                "Position: {:.3}s ({})",
+                state.position.as_secs_f64(),
+                if state.paused { "paused" } else { "playing" }
+            ))
+    });
 }
 
 impl VideoElement {
+    #[track_caller]
     pub fn new(video: Video) -> Self {
         Self {
             video,
             display_width: None,
             display_height: None,
             element_id: None,
+            poster: None,
+            error_placeholder: None,
+            corner_radii: gpui::Corners::default(),
+            border: None,
+            shadows: Vec::new(),
+            crop: None,
+            opacity: 1.0,
+            grayscale: false,
+            tint: None,
+            state_badges: StateBadges::default(),
+            debug_overlay: false,
+            stats_overlay: false,
+            source_location: core::panic::Location::caller(),
         }
     }
 
@@ -59,6 +182,128 @@ impl VideoElement {
         self
     }
 
+    /// Image painted until the first frame has been decoded. Defaults to the frame
+    /// chosen with [`Video::set_poster_time`], if any.
+    pub fn poster(mut self, image: Arc<gpui::RenderImage>) -> Self {
+        self.poster = Some(image);
+        self
+    }
+
+    /// Image painted instead of the video after a fatal pipeline error.
+    pub fn error_placeholder(mut self, image: Arc<gpui::RenderImage>) -> Self {
+        self.error_placeholder = Some(image);
+        self
+    }
+
+    /// Round the corners of the painted video, like `img().rounded(..)`. Rounding
+    /// applies to the letterboxed frame, not the whole element.
+    pub fn rounded(mut self, corner_radii: impl Into<gpui::Corners<gpui::Pixels>>) -> Self {
+        self.corner_radii = corner_radii.into();
+        self
+    }
+
+    /// Draw a border of `width` around the painted video, following its rounded corners.
+    pub fn border(mut self, width: gpui::Pixels, color: impl Into<gpui::Hsla>) -> Self {
+        self.border = Some((width, color.into()));
+        self
+    }
+
+    /// Cast shadows behind the painted video.
+    pub fn shadow(mut self, shadows: Vec<gpui::BoxShadow>) -> Self {
+        self.shadows = shadows;
+        self
+    }
+
+    /// Show only a region of the frame, given as fractions (0.0..=1.0) of the upright
+    /// frame's width and height, e.g. for zooming or picture-in-picture framing.
+    /// The region is letterboxed like a full frame.
+    pub fn crop(mut self, region: gpui::Bounds<f32>) -> Self {
+        self.crop = Some(region);
+        self
+    }
+
+    /// Draw the element, including its shadows, border, and overlays, at `opacity`
+    /// from 0.0 (invisible) to 1.0, e.g. animated to fade videos in and out.
+    /// Defaults to 1.0.
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Draw the picture in grayscale, e.g. for videos in the background. The
+    /// conversion happens on the GPU, so toggling it costs nothing.
+    pub fn grayscale(mut self, grayscale: bool) -> Self {
+        self.grayscale = grayscale;
+        self
+    }
+
+    /// Lay `color` over the picture; its alpha sets the strength, e.g.
+    /// `gpui::black().opacity(0.4)` to dim a video behind a dialog.
+    pub fn tint(mut self, color: gpui::Hsla) -> Self {
+        self.tint = Some(color);
+        self
+    }
+
+    /// Dim the video and draw a play button over it while paused or ended, styled
+    /// by the [`PlayerTheme`](crate::PlayerTheme). Off by default.
+    pub fn paused_veil(mut self, enabled: bool) -> Self {
+        self.state_badges.paused_veil = enabled;
+        self
+    }
+
+    /// Draw a spinner over the video while network buffering holds playback; see
+    /// [`Video::buffering_percent`]. Off by default.
+    pub fn buffering_spinner(mut self, enabled: bool) -> Self {
+        self.state_badges.buffering_spinner = enabled;
+        self
+    }
+
+    /// Draw a badge with the localized error status over the video once playback
+    /// failed; see [`Video::error`]. Off by default.
+    pub fn error_badge(mut self, enabled: bool) -> Self {
+        self.state_badges.error_badge = enabled;
+        self
+    }
+
+    /// Turn the paused veil, buffering spinner, and error badge on or off together,
+    /// so a simple player needs no state UI of its own.
+    pub fn state_badges(self, enabled: bool) -> Self {
+        self.paused_veil(enabled)
+            .buffering_spinner(enabled)
+            .error_badge(enabled)
+    }
+
+    /// Draw a frame pacing HUD over the video: recent frame intervals, conversion
+    /// times, and dropped frames from [`Video::playback_stats`]. Useful when
+    /// chasing stutter on a particular machine; bind it to a debug toggle.
+    pub fn debug_overlay(mut self, enabled: bool) -> Self {
+        self.debug_overlay = enabled;
+        self
+    }
+
+    /// Show the numbers from [`Video::stats`] in a panel over the video: decoded
+    /// and dropped frames, decode time, frame rate, queue depth, and bitrate.
+    /// Combines with [`debug_overlay`](Self::debug_overlay), which it sits beside.
+    pub fn stats_overlay(mut self, enabled: bool) -> Self {
+        self.stats_overlay = enabled;
+        self
+    }
+
+    /// Cut the crop region out of an upright frame, or return it unchanged.
+    fn apply_crop(&self, image: image::RgbaImage) -> image::RgbaImage {
+        let Some(region) = self.crop else {
+            return image;
+        };
+        let (width, height) = image.dimensions();
+        let x = (region.origin.x.clamp(0.0, 1.0) * width as f32) as u32;
+        let y = (region.origin.y.clamp(0.0, 1.0) * height as f32) as u32;
+        let crop_width = ((region.size.width.clamp(0.0, 1.0) * width as f32) as u32)
+            .clamp(1, width.saturating_sub(x).max(1));
+        let crop_height = ((region.size.height.clamp(0.0, 1.0) * height as f32) as u32)
+            .clamp(1, height.saturating_sub(y).max(1));
+        image::imageops::crop_imm(&image, x, y, crop_width, crop_height).to_image()
+    }
+
     /// Configure how many frames to buffer inside the underlying `Video`.
     /// 0 disables buffering and behaves like immediate rendering.
     pub fn buffer_capacity(self, capacity: usize) -> Self {
@@ -66,6 +311,14 @@ impl VideoElement {
         self
     }
 
+    /// Delay (positive) or advance (negative) frame presentation by `ms` milliseconds
+    /// to line video up with an audio clock the app drives itself. Like
+    /// [`buffer_capacity`](Self::buffer_capacity), this configures the underlying `Video`.
+    pub fn presentation_offset(self, ms: i64) -> Self {
+        self.video.set_presentation_offset(ms);
+        self
+    }
+
     /// Get the current display dimensions, falling back to video's effective display size.
     fn get_display_size(&self) -> (gpui::Pixels, gpui::Pixels) {
         match (self.display_width, self.display_height) {
@@ -109,12 +362,43 @@ impl VideoElement {
         )
     }
 
+    /// Paint `content` within `dest_bounds` between its shadows and border.
+    fn paint_decorated(
+        &self,
+        window: &mut Window,
+        dest_bounds: gpui::Bounds<gpui::Pixels>,
+        content: impl FnOnce(&mut Window),
+    ) {
+        if !self.shadows.is_empty() {
+            window.paint_shadows(dest_bounds, self.corner_radii, &self.shadows);
+        }
+        content(window);
+        if let Some(tint) = self.tint {
+            window.paint_quad(gpui::fill(dest_bounds, tint).corner_radii(self.corner_radii));
+        }
+        if let Some((width, color)) = self.border {
+            window.paint_quad(gpui::quad(
+                dest_bounds,
+                self.corner_radii,
+                gpui::transparent_black(),
+                width,
+                color,
+                gpui::BorderStyle::default(),
+            ));
+        }
+    }
+
     /// Paint using GPUI sprite atlas with a BGRA buffer, while evicting the previous frame's texture.
+    /// The frame is rotated/flipped upright first if the stream carries an orientation tag,
+    /// then cropped.
+    #[allow(clippy::too_many_arguments)]
     fn paint_render_image(
         &mut self,
         window: &mut Window,
         cx: &mut gpui::App,
         bounds: gpui::Bounds<gpui::Pixels>,
+        cache: &gpui::Entity<RenderCache>,
+        key: Option<RenderKey>,
         rgb_data: Vec<u8>,
         frame_width: u32,
         frame_height: u32,
@@ -125,29 +409,21 @@ impl VideoElement {
         if let Some(image_buffer) =
             ImageBuffer::<Rgba<u8>, _>::from_raw(frame_width, frame_height, rgb_data)
         {
-            let last_render_image: gpui::Entity<Option<Arc<gpui::RenderImage>>> =
-                window.use_state(cx, |_, _| None);
+            let image_buffer = self.apply_crop(self.video.orientation().apply(image_buffer));
+            let (frame_width, frame_height) = image_buffer.dimensions();
 
             let frames: SmallVec<[image::Frame; 1]> =
                 SmallVec::from_elem(image::Frame::new(image_buffer), 1);
             let render_image = Arc::new(gpui::RenderImage::new(frames));
-
-            let dest_bounds = self.fitted_bounds(bounds, frame_width, frame_height);
+            self.video
+                .set_converted_bytes(frame_width as usize * frame_height as usize * 4);
 
             // Swap and remember the previous image so we can drop it after painting
-            let prev_image: Option<Arc<gpui::RenderImage>> =
-                last_render_image.update(cx, |this, _| this.replace(render_image.clone()));
+            let prev_image = cache
+                .update(cx, |this, _| this.replace((key, render_image.clone())))
+                .map(|(_, image)| image);
 
-            // Paint the image within the fitted bounds (letterboxed/pillarboxed)
-            window
-                .paint_image(
-                    dest_bounds,
-                    gpui::Corners::default(),
-                    render_image.clone(),
-                    0,
-                    false,
-                )
-                .ok();
+            self.paint_still(window, bounds, render_image);
 
             // Drop the previously uploaded image after painting to avoid atlas growth
             if let Some(prev) = prev_image {
@@ -156,6 +432,22 @@ impl VideoElement {
         }
     }
 
+    /// Paint a user-supplied still image, letterboxed like video frames.
+    fn paint_still(
+        &self,
+        window: &mut Window,
+        bounds: gpui::Bounds<gpui::Pixels>,
+        image: Arc<gpui::RenderImage>,
+    ) {
+        let size = image.size(0);
+        let dest_bounds = self.fitted_bounds(bounds, size.width.0 as u32, size.height.0 as u32);
+        self.paint_decorated(window, dest_bounds, |window| {
+            window
+                .paint_image(dest_bounds, self.corner_radii, image, 0, self.grayscale)
+                .ok();
+        });
+    }
+
     /// macOS-only: Try to render NV12 via CVPixelBuffer and paint_surface. Returns true if painted.
     #[cfg(target_os = "macos")]
     fn try_paint_surface_macos(
@@ -222,6 +514,15 @@ impl VideoElement {
             return false;
         }
 
+        // gpui's surface shader reads full-range samples, and it asserts the full-range
+        // pixel format, so the video-range one cannot be used to say otherwise. Expand
+        // limited-range frames while copying instead.
+        let limited = matches!(self.video.color_space().range, yuv::YuvRange::Limited);
+        let (luma_lut, chroma_lut) = if limited {
+            (Some(&LIMITED_LUMA), Some(&LIMITED_CHROMA))
+        } else {
+            (None, None)
+        };
         if pixel_buffer.lock_base_address(0) != kCVReturnSuccess {
             return false;
         }
@@ -231,119 +532,223 @@ impl VideoElement {
 
             // Copy Y plane row-wise respecting stride
             for row in 0..height {
-                let src_off = row * width;
-                let dst_off = row * y_stride;
-                std::ptr::copy_nonoverlapping(
-                    yuv_data.as_ptr().add(src_off),
-                    y_dst.add(dst_off),
-                    width,
-                );
+                let dst = std::slice::from_raw_parts_mut(y_dst.add(row * y_stride), width);
+                copy_row(&yuv_data[row * width..][..width], dst, luma_lut);
             }
             // Copy UV plane
             for row in 0..(height / 2) {
-                let src_off = y_size + row * width;
-                let dst_off = row * uv_stride;
-                std::ptr::copy_nonoverlapping(
-                    yuv_data.as_ptr().add(src_off),
-                    uv_dst.add(dst_off),
-                    width,
-                );
+                let dst = std::slice::from_raw_parts_mut(uv_dst.add(row * uv_stride), width);
+                copy_row(&yuv_data[y_size + row * width..][..width], dst, chroma_lut);
             }
         }
         let _ = pixel_buffer.unlock_base_address(0);
+        self.video
+            .set_converted_bytes(y_stride * height + uv_stride * (height / 2));
 
         let dest_bounds = self.fitted_bounds(bounds, frame_width, frame_height);
-        window.paint_surface(dest_bounds, pixel_buffer);
+        self.paint_decorated(window, dest_bounds, |window| {
+            window.paint_surface(dest_bounds, pixel_buffer);
+        });
         true
     }
 
-    /// Convert NV12 YUV data to RGB using optimized yuvutils-rs
-    fn yuv_to_rgb(&self, yuv_data: &[u8], width: u32, height: u32) -> Vec<u8> {
-        let width_usize = width as usize;
-        let height_usize = height as usize;
-        let y_size = width_usize * height_usize;
-        let uv_size = (width_usize * height_usize) / 2;
-
-        if yuv_data.len() < y_size + uv_size {
-            // Not enough data, return black frame
-            return vec![0; width_usize * height_usize * 4];
+    /// Paint the current frame, or the poster or error placeholder in its place.
+    fn paint_video(
+        &mut self,
+        window: &mut Window,
+        cx: &mut gpui::App,
+        bounds: gpui::Bounds<gpui::Pixels>,
+    ) {
+        if self.video.error().is_some()
+            && let Some(placeholder) = self.error_placeholder.clone()
+        {
+            self.paint_still(window, bounds, placeholder);
+            return;
         }
 
-        // Split NV12 data into Y and UV planes
-        let y_plane = &yuv_data[..y_size];
-        let uv_plane = &yuv_data[y_size..y_size + uv_size];
+        self.video.pull_latest_frame();
 
-        // Create YuvBiPlanarImage structure for NV12 data
-        let yuv_bi_planar = YuvBiPlanarImage {
-            y_plane,
-            y_stride: width,
-            uv_plane,
-            uv_stride: width, // NV12 UV stride is same as width
-            width,
-            height,
-        };
-
-        // Prepare output RGB buffer (BGRA format)
-        let mut bgra = vec![0u8; width_usize * height_usize * 4];
-        let rgba_stride = width * 4;
-
-        // Use yuvutils-rs optimized NV12 to RGB conversion
-        // Try Bt709 first (HD standard) with full range
-        if yuv_nv12_to_bgra(
-            &yuv_bi_planar,
-            &mut bgra,
-            rgba_stride,
-            YuvRange::Full,              // Try full range first
-            YuvStandardMatrix::Bt709,    // HD standard
-            YuvConversionMode::Balanced, // Use balanced conversion mode (default)
-        )
-        .is_ok()
+        // Present the newest buffered frame whose time has come. Frames ahead of the
+        // playback clock wait for the paint at their time, so playback stays even
+        // when the display refresh rate is not a multiple of the framerate.
+        let buffered = self.video.buffered_len();
+        let due = self.video.pop_due_frame();
+        let from_buffer = due.is_some();
+        let cache: gpui::Entity<RenderCache> = window.use_state(cx, |_, _| None);
+        if due.is_none()
+            && buffered > 0
+            && let Some((_, image)) = cache.read(cx).clone()
         {
-            return bgra;
+            // Nothing is due yet; keep showing the frame presented last.
+            self.paint_still(window, bounds, image);
+            return;
+        }
+        let frame = due.unwrap_or_else(|| self.video.current_frame());
+
+        // Frames without a timestamp cannot be told apart and are always converted.
+        let key = frame.pts().map(|pts| RenderKey {
+            video_id: self.video.read().id,
+            pts,
+            orientation: self.video.orientation(),
+            crop: self.crop,
+            color_overrides: self.video.color_overrides(),
+        });
+        let cached = cache.read(cx).as_ref().and_then(|(cached_key, image)| {
+            (key.is_some() && *cached_key == key).then(|| image.clone())
+        });
+        if let Some(image) = cached {
+            self.paint_still(window, bounds, image);
+            return;
         }
 
-        // Try Bt709 with limited range
-        if yuv_nv12_to_bgra(
-            &yuv_bi_planar,
-            &mut bgra,
-            rgba_stride,
-            YuvRange::Limited,           // Limited range
-            YuvStandardMatrix::Bt709,    // HD standard
-            YuvConversionMode::Balanced, // Use balanced conversion mode (default)
-        )
-        .is_ok()
-        {
-            return bgra;
+        let (width, height) = self.video.size();
+        // Large frames arrive already converted by the worker thread.
+        if let Some(rgb_data) = frame.take_converted() {
+            self.paint_render_image(
+                window,
+                cx,
+                bounds,
+                &cache,
+                key,
+                rgb_data,
+                width as u32,
+                height as u32,
+            );
+            self.video.record_paint_latency(frame.pulled_at());
+            return;
         }
 
-        // Fallback to Bt601 (SD standard)
-        match yuv_nv12_to_bgra(
-            &yuv_bi_planar,
-            &mut bgra,
-            rgba_stride,
-            YuvRange::Limited,
-            YuvStandardMatrix::Bt601,
-            YuvConversionMode::Balanced, // Use balanced conversion mode (default)
-        ) {
-            Ok(_) => bgra,
-            Err(_) => {
-                // Final fallback to black frame on conversion error
-                vec![0; width_usize * height_usize * 4]
+        let (frame_width, frame_height) = (width as u32, height as u32);
+        let conversion_start = Instant::now();
+        // Convert straight from the mapped sample, without copying it first.
+        let painted = frame.with_data(|yuv_data| {
+            if from_buffer {
+                log::debug!(
+                    "Painting frame from buffer (buffered_len before drain: {})",
+                    buffered
+                );
+            } else {
+                log::debug!("Painting the current frame");
+            }
+
+            // On macOS, upload via CVPixelBuffer + paint_surface to avoid atlas growth.
+            // Other formats (including HDR frames needing CPU tone mapping), padded
+            // NV12 layouts, rotated or cropped frames, rounded corners (surfaces are
+            // not clipped), and grayscale or faded frames take the atlas path.
+            #[cfg(target_os = "macos")]
+            if self
+                .video
+                .frame_layout()
+                .is_tight_nv12(frame_width, frame_height)
+                && self.video.orientation().is_identity()
+                && self.video.color_overrides().is_empty()
+                && self.corner_radii == gpui::Corners::default()
+                && self.crop.is_none()
+                && !self.grayscale
+                && self.opacity == 1.0
+                && self.try_paint_surface_macos(window, bounds, yuv_data, frame_width, frame_height)
+            {
+                return;
             }
+
+            let rgb_data = self.yuv_to_rgb(yuv_data, frame_width, frame_height);
+            self.paint_render_image(
+                window,
+                cx,
+                bounds,
+                &cache,
+                key,
+                rgb_data,
+                frame_width,
+                frame_height,
+            );
+        });
+
+        match painted {
+            Ok(Some(())) => {
+                self.video.record_conversion(conversion_start.elapsed());
+                self.video.record_paint_latency(frame.pulled_at());
+                return;
+            }
+            Ok(None) => {}
+            Err(err) => log::debug!("skipping unreadable frame: {err}"),
+        }
+        if let Some(poster) = self.poster.clone().or_else(|| self.video.poster()) {
+            self.paint_still(window, bounds, poster);
+        }
+    }
+
+    /// Paint the frame and everything drawn over it.
+    fn paint_contents(
+        &mut self,
+        window: &mut Window,
+        cx: &mut gpui::App,
+        bounds: gpui::Bounds<gpui::Pixels>,
+    ) {
+        self.paint_video(window, cx, bounds);
+        if self.state_badges.any() {
+            badge::paint_state_badges(
+                window,
+                cx,
+                bounds,
+                self.corner_radii,
+                &self.video,
+                self.state_badges,
+                &player_theme(cx),
+            );
         }
+        if self.debug_overlay {
+            hud::paint_debug_overlay(
+                window,
+                cx,
+                bounds,
+                &self.video.playback_stats(),
+                self.video.framerate(),
+                &player_theme(cx),
+            );
+        }
+        if self.stats_overlay {
+            hud::paint_stats_overlay(window, cx, bounds, &self.video.stats(), &player_theme(cx));
+        }
+    }
+
+    /// Convert decoded frame data to the BGRA layout expected by GPUI's sprite atlas.
+    fn yuv_to_rgb(&self, yuv_data: &[u8], width: u32, height: u32) -> Vec<u8> {
+        let mut pixels = vec![0; width as usize * height as usize * 4];
+        convert::frame_into(
+            PixelOrder::Bgra,
+            &self.video.frame_layout(),
+            self.video.color_space(),
+            yuv_data,
+            width,
+            height,
+            &mut pixels,
+        );
+        pixels
     }
 }
 
 impl Element for VideoElement {
-    type RequestLayoutState = ();
+    /// While faded, the contents are painted by a child div that applies the
+    /// opacity: gpui only lets divs set it for what they paint.
+    type RequestLayoutState = Option<gpui::AnyElement>;
     type PrepaintState = ();
 
+    /// Falls back to an id derived from the video and where the element was
+    /// created, so per-element state (such as the last painted frame) survives
+    /// re-renders without an explicit `id()`. Give each view its own id when one
+    /// video is shown more than once from the same line, e.g. in a loop.
     fn id(&self) -> Option<ElementId> {
-        self.element_id.clone()
+        Some(self.element_id.clone().unwrap_or_else(|| {
+            ElementId::NamedChild(
+                Box::new(ElementId::CodeLocation(*self.source_location)),
+                format!("video-{}", self.video.read().id).into(),
+            )
+        }))
     }
 
     fn source_location(&self) -> Option<&'static core::panic::Location<'static>> {
-        None
+        Some(self.source_location)
     }
 
     fn request_layout(
@@ -378,8 +783,23 @@ impl Element for VideoElement {
             ..Default::default()
         };
 
-        let layout_id = window.request_layout(style, [], cx);
-        (layout_id, ())
+        let mut faded = (self.opacity < 1.0).then(|| {
+            let mut contents = self.clone();
+            div()
+                .size_full()
+                .opacity(self.opacity)
+                .child(
+                    gpui::canvas(
+                        |_, _, _| {},
+                        move |bounds, _, window, cx| contents.paint_contents(window, cx, bounds),
+                    )
+                    .size_full(),
+                )
+                .into_any_element()
+        });
+        let children = faded.as_mut().map(|faded| faded.request_layout(window, cx));
+        let layout_id = window.request_layout(style, children, cx);
+        (layout_id, faded)
     }
 
     fn prepaint(
@@ -387,16 +807,33 @@ impl Element for VideoElement {
         _global_id: Option<&GlobalElementId>,
         _inspector_id: Option<&InspectorElementId>,
         _bounds: gpui::Bounds<gpui::Pixels>,
-        _request_layout_state: &mut Self::RequestLayoutState,
+        request_layout_state: &mut Self::RequestLayoutState,
         window: &mut Window,
-        _cx: &mut gpui::App,
+        cx: &mut gpui::App,
     ) -> Self::PrepaintState {
-        // Schedule repaints only when playing or when a new frame arrived.
-        let is_playing = !self.video.eos() && !self.video.paused();
-        let has_new_frame = self.video.take_frame_ready();
-        if is_playing || has_new_frame {
-            window.request_animation_frame();
+        thread::mark_ui_thread();
+        if let Some(faded) = request_layout_state {
+            faded.prepaint(window, cx);
         }
+
+        // Redraw for new frames and state changes only; the display refresh rate
+        // is usually well above the video's framerate, and paused video never changes.
+        repaint::repaint_on_change(window, cx, &self.video, None);
+
+        #[cfg(any(feature = "inspector", debug_assertions))]
+        window.with_inspector_state(
+            _inspector_id,
+            cx,
+            |state: &mut Option<VideoInspectorState>, _window| {
+                *state = Some(VideoInspectorState {
+                    frame_size: self.video.size(),
+                    framerate: self.video.framerate(),
+                    buffered_frames: self.video.buffered_len(),
+                    position: self.video.position(),
+                    paused: self.video.paused(),
+                });
+            },
+        );
     }
 
     fn paint(
@@ -404,52 +841,14 @@ impl Element for VideoElement {
         _global_id: Option<&GlobalElementId>,
         _inspector_id: Option<&InspectorElementId>,
         bounds: gpui::Bounds<gpui::Pixels>,
-        _request_layout_state: &mut Self::RequestLayoutState,
+        request_layout_state: &mut Self::RequestLayoutState,
         _prepaint_state: &mut Self::PrepaintState,
         window: &mut Window,
         cx: &mut gpui::App,
     ) {
-        // Prefer buffered frames if available. Drain to the latest to avoid lag.
-        let buffered = self.video.buffered_len();
-        let mut frame_to_render: Option<(Vec<u8>, u32, u32)> = None;
-        let mut from_buffer = false;
-        if buffered > 0 {
-            for _ in 0..buffered {
-                if let Some(frame) = self.video.pop_buffered_frame() {
-                    frame_to_render = Some(frame);
-                }
-            }
-            from_buffer = frame_to_render.is_some();
-        } else {
-            frame_to_render = self.video.current_frame_data();
-        }
-
-        if let Some((yuv_data, frame_width, frame_height)) = frame_to_render {
-            if from_buffer {
-                log::debug!(
-                    "Painting frame from buffer (buffered_len before drain: {})",
-                    buffered
-                );
-            } else {
-                log::debug!("Painting frame from live current_frame_data()");
-            }
-
-            // On macOS, upload via CVPixelBuffer + paint_surface to avoid atlas growth
-            #[cfg(target_os = "macos")]
-            {
-                if self.try_paint_surface_macos(
-                    window,
-                    bounds,
-                    &yuv_data,
-                    frame_width,
-                    frame_height,
-                ) {
-                    return;
-                }
-            }
-
-            let rgb_data = self.yuv_to_rgb(&yuv_data, frame_width, frame_height);
-            self.paint_render_image(window, cx, bounds, rgb_data, frame_width, frame_height);
+        match request_layout_state {
+            Some(faded) => faded.paint(window, cx),
+            None => self.paint_contents(window, cx, bounds),
         }
     }
 }
@@ -463,6 +862,7 @@ impl IntoElement for VideoElement {
 }
 
 /// Helper function to create a video element
+#[track_caller]
 pub fn video(video: Video) -> VideoElement {
     VideoElement::new(video)
 }
@@ -0,0 +1,379 @@
+//! Named encoder presets for export and transcoding.
+
+use crate::Error;
+use crate::capabilities::is_hardware;
+use crate::runtime;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_pbutils as gst_pbutils;
+
+/// Container format of an encoded file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Container {
+    Mp4,
+    QuickTime,
+    Matroska,
+    WebM,
+}
+
+impl Container {
+    pub(crate) fn caps(self) -> gst::Caps {
+        match self {
+            Container::Mp4 => gst::Caps::builder("video/quicktime")
+                .field("variant", "iso")
+                .build(),
+            Container::QuickTime => gst::Caps::builder("video/quicktime").build(),
+            Container::Matroska => gst::Caps::builder("video/x-matroska").build(),
+            Container::WebM => gst::Caps::builder("video/webm").build(),
+        }
+    }
+
+    /// Muxer element, for writing streams without re-encoding.
+    pub(crate) fn muxer(self) -> &'static str {
+        match self {
+            Container::Mp4 => "mp4mux",
+            Container::QuickTime => "qtmux",
+            Container::Matroska => "matroskamux",
+            Container::WebM => "webmmux",
+        }
+    }
+
+    /// Conventional file extension, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Container::Mp4 => "mp4",
+            Container::QuickTime => "mov",
+            Container::Matroska => "mkv",
+            Container::WebM => "webm",
+        }
+    }
+}
+
+/// How the video encoder is chosen among those installed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EncoderPreference {
+    /// Use a hardware encoder (VideoToolbox, NVENC, QSV, VA-API) when available,
+    /// falling back to software.
+    #[default]
+    PreferHardware,
+    /// Use a software encoder when available, falling back to hardware.
+    PreferSoftware,
+    /// Only use software encoders, for output that is identical across machines.
+    SoftwareOnly,
+}
+
+/// An installed encoder element for a codec.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncoderInfo {
+    /// Element factory name, e.g. `x264enc`.
+    pub name: &'static str,
+    pub hardware: bool,
+}
+
+/// Video codec of an encoded file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VideoCodec {
+    H264,
+    H265,
+    Vp9,
+    Av1,
+    /// Apple ProRes 422 Proxy, for editing proxies.
+    ProResProxy,
+}
+
+impl VideoCodec {
+    pub(crate) fn caps(self) -> gst::Caps {
+        match self {
+            VideoCodec::H264 => gst::Caps::builder("video/x-h264").build(),
+            VideoCodec::H265 => gst::Caps::builder("video/x-h265").build(),
+            VideoCodec::Vp9 => gst::Caps::builder("video/x-vp9").build(),
+            VideoCodec::Av1 => gst::Caps::builder("video/x-av1").build(),
+            VideoCodec::ProResProxy => gst::Caps::builder("video/x-prores")
+                .field("variant", "proxy")
+                .build(),
+        }
+    }
+
+    /// Hardware encoders, in order of preference.
+    fn hardware_encoders(self) -> &'static [&'static str] {
+        match self {
+            VideoCodec::H264 => &[
+                "vtenc_h264_hw",
+                "nvh264enc",
+                "qsvh264enc",
+                "vah264enc",
+                "vaapih264enc",
+                "mfh264enc",
+            ],
+            VideoCodec::H265 => &[
+                "vtenc_h265_hw",
+                "nvh265enc",
+                "qsvh265enc",
+                "vah265enc",
+                "vaapih265enc",
+                "mfh265enc",
+            ],
+            VideoCodec::Vp9 => &["qsvvp9enc", "vavp9enc"],
+            VideoCodec::Av1 => &["nvav1enc", "qsvav1enc", "vaav1enc"],
+            VideoCodec::ProResProxy => &["vtenc_prores"],
+        }
+    }
+
+    /// Software encoders, in order of preference.
+    fn software_encoders(self) -> &'static [&'static str] {
+        match self {
+            VideoCodec::H264 => &["x264enc", "openh264enc"],
+            VideoCodec::H265 => &["x265enc"],
+            VideoCodec::Vp9 => &["vp9enc"],
+            VideoCodec::Av1 => &["svtav1enc", "av1enc", "rav1enc"],
+            VideoCodec::ProResProxy => &["avenc_prores_ks"],
+        }
+    }
+
+    /// Installed encoders for this codec, hardware first.
+    pub fn available_encoders(self) -> Vec<EncoderInfo> {
+        let hardware = self.hardware_encoders().iter().map(|&name| EncoderInfo {
+            name,
+            hardware: true,
+        });
+        let software = self.software_encoders().iter().map(|&name| EncoderInfo {
+            name,
+            hardware: gst::ElementFactory::find(name).is_some_and(|factory| is_hardware(&factory)),
+        });
+        hardware
+            .chain(software)
+            .filter(|encoder| is_installed(encoder.name))
+            .collect()
+    }
+}
+
+/// Audio codec of an encoded file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioCodec {
+    Aac,
+    Opus,
+    Flac,
+    /// Uncompressed 16-bit PCM.
+    Pcm,
+}
+
+impl AudioCodec {
+    pub(crate) fn caps(self) -> gst::Caps {
+        match self {
+            AudioCodec::Aac => gst::Caps::builder("audio/mpeg")
+                .field("mpegversion", 4i32)
+                .build(),
+            AudioCodec::Opus => gst::Caps::builder("audio/x-opus").build(),
+            AudioCodec::Flac => gst::Caps::builder("audio/x-flac").build(),
+            AudioCodec::Pcm => gst::Caps::builder("audio/x-raw")
+                .field("format", "S16LE")
+                .build(),
+        }
+    }
+
+    fn encoders(self) -> &'static [&'static str] {
+        match self {
+            AudioCodec::Aac => &["fdkaacenc", "avenc_aac", "voaacenc"],
+            AudioCodec::Opus => &["opusenc"],
+            AudioCodec::Flac => &["flacenc"],
+            AudioCodec::Pcm => &[],
+        }
+    }
+}
+
+/// Encoder settings for export and transcoding, mapped to an `encodebin` profile.
+///
+/// Start from a preset in [`EncodeProfile::catalog`] and override fields as needed:
+///
+/// ```no_run
+/// use gpui_video_player::EncodeProfile;
+///
+/// let profile = EncodeProfile {
+///     video_bitrate_kbps: Some(2500),
+///     ..EncodeProfile::named("web-720p").unwrap()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodeProfile {
+    pub name: String,
+    pub container: Container,
+    /// Video codec, or None to drop the video track.
+    pub video: Option<VideoCodec>,
+    /// Audio codec, or None to drop the audio track.
+    pub audio: Option<AudioCodec>,
+    /// Output size. None keeps the source size.
+    pub size: Option<(u32, u32)>,
+    /// Target video bitrate. None leaves the encoder's default.
+    pub video_bitrate_kbps: Option<u32>,
+    /// Target audio bitrate. None leaves the encoder's default.
+    pub audio_bitrate_kbps: Option<u32>,
+    /// How to pick the video encoder.
+    pub encoder: EncoderPreference,
+}
+
+impl EncodeProfile {
+    /// The built-in presets: `web-720p`, `archive-h265`, `av1-slow`, and `prores-proxy`.
+    pub fn catalog() -> Vec<EncodeProfile> {
+        vec![
+            EncodeProfile {
+                name: "web-720p".into(),
+                container: Container::Mp4,
+                video: Some(VideoCodec::H264),
+                audio: Some(AudioCodec::Aac),
+                size: Some((1280, 720)),
+                video_bitrate_kbps: Some(4000),
+                audio_bitrate_kbps: Some(128),
+                encoder: EncoderPreference::PreferHardware,
+            },
+            EncodeProfile {
+                name: "archive-h265".into(),
+                container: Container::Matroska,
+                video: Some(VideoCodec::H265),
+                audio: Some(AudioCodec::Flac),
+                size: None,
+                video_bitrate_kbps: Some(12000),
+                audio_bitrate_kbps: None,
+                // Software encoders compress better at the same bitrate.
+                encoder: EncoderPreference::PreferSoftware,
+            },
+            EncodeProfile {
+                name: "av1-slow".into(),
+                container: Container::Matroska,
+                video: Some(VideoCodec::Av1),
+                audio: Some(AudioCodec::Opus),
+                size: None,
+                video_bitrate_kbps: Some(3000),
+                audio_bitrate_kbps: Some(160),
+                encoder: EncoderPreference::PreferSoftware,
+            },
+            EncodeProfile {
+                name: "prores-proxy".into(),
+                container: Container::QuickTime,
+                video: Some(VideoCodec::ProResProxy),
+                audio: Some(AudioCodec::Pcm),
+                size: None,
+                video_bitrate_kbps: None,
+                audio_bitrate_kbps: None,
+                encoder: EncoderPreference::PreferHardware,
+            },
+        ]
+    }
+
+    /// Look up a preset from [`EncodeProfile::catalog`] by name.
+    pub fn named(name: &str) -> Option<EncodeProfile> {
+        Self::catalog()
+            .into_iter()
+            .find(|profile| profile.name == name)
+    }
+
+    /// The video encoder element that will be used, if any is installed.
+    pub fn video_encoder(&self) -> Option<EncoderInfo> {
+        let codec = self.video?;
+        let (hardware, software): (Vec<_>, Vec<_>) = codec
+            .available_encoders()
+            .into_iter()
+            .partition(|encoder| encoder.hardware);
+        match self.encoder {
+            EncoderPreference::PreferHardware => hardware.into_iter().chain(software).next(),
+            EncoderPreference::PreferSoftware => software.into_iter().chain(hardware).next(),
+            EncoderPreference::SoftwareOnly => software.into_iter().next(),
+        }
+    }
+
+    /// Build the `encodebin` profile for these settings.
+    pub fn to_encoding_profile(&self) -> Result<gst_pbutils::EncodingContainerProfile, Error> {
+        runtime::ensure_init()?;
+        let mut container =
+            gst_pbutils::EncodingContainerProfile::builder(&self.container.caps()).name(&self.name);
+
+        if let Some(codec) = self.video {
+            let encoder = self
+                .video_encoder()
+                .ok_or_else(|| Error::Encoder(format!("{codec:?}")))?
+                .name;
+            log::debug!("encoding {codec:?} with {encoder}");
+            let mut video = gst_pbutils::EncodingVideoProfile::builder(&codec.caps())
+                .preset_name(encoder)
+                .presence(0);
+            if let Some((width, height)) = self.size {
+                let restriction = gst::Caps::builder("video/x-raw")
+                    .field("width", width as i32)
+                    .field("height", height as i32)
+                    .build();
+                video = video.restriction(&restriction);
+            }
+            if let Some(kbps) = self.video_bitrate_kbps
+                && let Some(properties) = bitrate_properties(encoder, kbps)
+            {
+                video = video.element_properties(properties);
+            }
+            container = container.add_profile(video.build());
+        }
+
+        if let Some(codec) = self.audio {
+            let mut audio = gst_pbutils::EncodingAudioProfile::builder(&codec.caps()).presence(0);
+            if let Some(encoder) = codec
+                .encoders()
+                .iter()
+                .copied()
+                .find(|name| is_installed(name))
+            {
+                audio = audio.preset_name(encoder);
+                if let Some(kbps) = self.audio_bitrate_kbps
+                    && let Some(properties) = bitrate_properties(encoder, kbps)
+                {
+                    audio = audio.element_properties(properties);
+                }
+            } else if !codec.encoders().is_empty() {
+                return Err(Error::Encoder(format!("{codec:?}")));
+            }
+            container = container.add_profile(audio.build());
+        }
+
+        Ok(container.build())
+    }
+}
+
+fn is_installed(factory: &str) -> bool {
+    gst::ElementFactory::find(factory).is_some()
+}
+
+/// Bitrate property of an encoder and its scale relative to kbit/s.
+fn bitrate_property(factory: &str) -> Option<(&'static str, u64)> {
+    match factory {
+        "vp9enc" => Some(("target-bitrate", 1000)),
+        "svtav1enc" | "av1enc" => Some(("target-bitrate", 1)),
+        "rav1enc" | "openh264enc" | "opusenc" | "fdkaacenc" | "avenc_aac" | "voaacenc" => {
+            Some(("bitrate", 1000))
+        }
+        "flacenc" | "avenc_prores_ks" | "vtenc_prores" => None,
+        _ => Some(("bitrate", 1)),
+    }
+}
+
+/// Element properties setting `factory`'s bitrate, typed to match its property.
+fn bitrate_properties(factory: &str, kbps: u32) -> Option<gst_pbutils::ElementProperties> {
+    let (property, scale) = bitrate_property(factory)?;
+    let element = gst::ElementFactory::make(factory).build().ok()?;
+    let pspec = element.find_property(property)?;
+    let value = u64::from(kbps) * scale;
+    let value = match pspec.value_type() {
+        t if t == glib::Type::U32 => u32::try_from(value).ok()?.to_send_value(),
+        t if t == glib::Type::I32 => i32::try_from(value).ok()?.to_send_value(),
+        t if t == glib::Type::U64 => value.to_send_value(),
+        t if t == glib::Type::I64 => i64::try_from(value).ok()?.to_send_value(),
+        other => {
+            log::debug!("unsupported {factory}::{property} type {other}");
+            return None;
+        }
+    };
+    Some(
+        gst_pbutils::ElementProperties::builder_map()
+            .item(
+                gst_pbutils::ElementPropertiesMapItem::builder(factory)
+                    .field_value(property, value)
+                    .build(),
+            )
+            .build(),
+    )
+}
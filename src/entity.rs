@@ -0,0 +1,95 @@
+use crate::video::Video;
+use gpui::{App, AppContext, Entity, Task};
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+/// How often the wrapper checks the video for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Position updates are coarser than frames; labels and seek bars need no more.
+const POSITION_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Observable state of a [`Video`], kept in a gpui entity by [`VideoEntity`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VideoState {
+    pub position: Duration,
+    pub duration: Duration,
+    pub paused: bool,
+    pub eos: bool,
+    pub buffering_percent: u8,
+    /// Changes whenever a new frame was decoded; see [`Video::frame_generation`].
+    pub frame_generation: u64,
+    pub error: Option<String>,
+}
+
+impl VideoState {
+    fn read(video: &Video, position: Duration) -> Self {
+        Self {
+            position,
+            duration: video.duration(),
+            paused: video.paused(),
+            eos: video.eos(),
+            buffering_percent: video.buffering_percent(),
+            frame_generation: video.frame_generation(),
+            error: video.error(),
+        }
+    }
+}
+
+/// A [`Video`] paired with an [`Entity<VideoState>`] that is notified only when
+/// something changed: a new frame, the position, play state, buffering, or an error.
+///
+/// Views observe the state (`cx.observe(player.state(), ..)`) instead of calling
+/// `cx.notify()` every frame, so a paused or finished video costs no renders.
+pub struct VideoEntity {
+    video: Video,
+    state: Entity<VideoState>,
+    _updates: Task<()>,
+}
+
+impl VideoEntity {
+    pub fn new(video: Video, cx: &mut App) -> Self {
+        let positions = video.watch_position(POSITION_INTERVAL);
+        let state = cx.new(|_| VideoState::read(&video, video.position()));
+        let updates = cx.spawn({
+            let video = video.clone();
+            let state = state.downgrade();
+            async move |cx| {
+                loop {
+                    cx.background_executor().timer(POLL_INTERVAL).await;
+                    let updated = state.update(cx, |state, cx| {
+                        let position = latest(&positions).unwrap_or(state.position);
+                        let next = VideoState::read(&video, position);
+                        if *state != next {
+                            *state = next;
+                            cx.notify();
+                        }
+                    });
+                    // The state entity was released.
+                    if updated.is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Self {
+            video,
+            state,
+            _updates: updates,
+        }
+    }
+
+    pub fn video(&self) -> &Video {
+        &self.video
+    }
+
+    /// The state entity to observe or read in `render`.
+    pub fn state(&self) -> &Entity<VideoState> {
+        &self.state
+    }
+}
+
+/// The newest position sent by [`Video::watch_position`], if any.
+fn latest(positions: &Receiver<Duration>) -> Option<Duration> {
+    positions.try_iter().last()
+}
@@ -1,7 +1,11 @@
+use crate::capabilities::missing_plugin;
+use crate::video::is_network_uri;
 use gstreamer as gst;
+use gstreamer::prelude::*;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
     #[error("{0}")]
     Glib(#[from] glib::Error),
@@ -11,13 +15,15 @@ pub enum Error {
     Bus,
     #[error("failed to get AppSink element with name='{0}' from gstreamer pipeline")]
     AppSink(String),
+    #[error("video sink '{name}' cannot receive frames: {reason}")]
+    VideoSink { name: String, reason: &'static str },
     #[error("{0}")]
     StateChange(#[from] gst::StateChangeError),
-    #[error("failed to cast gstreamer element")]
+    #[error("a gstreamer element was not of the expected type")]
     Cast,
     #[error("{0}")]
     Io(#[from] std::io::Error),
-    #[error("invalid URI")]
+    #[error("invalid URI, or no installed source handles its scheme")]
     Uri,
     #[error("failed to get media capabilities")]
     Caps,
@@ -29,4 +35,172 @@ pub enum Error {
     Lock,
     #[error("invalid framerate: {0}")]
     Framerate(f64),
+    #[error("invalid playback rate: {0}")]
+    Rate(f64),
+    #[error("invalid loop range: {0:?}..{1:?}")]
+    LoopRange(std::time::Duration, std::time::Duration),
+    #[error("invalid clip range: {0:?}..{1:?}")]
+    ClipRange(std::time::Duration, std::time::Duration),
+    #[error("no video frame is available yet")]
+    NoFrame,
+    #[error("{0}")]
+    Image(#[from] image::ImageError),
+    #[error("no encoder is installed for {0}")]
+    Encoder(String),
+    #[error("a sequence needs at least one clip")]
+    EmptySequence,
+    #[error("{0}")]
+    Frame(#[from] FrameError),
+    #[error("audio filter '{0}' was not enabled in VideoOptions::audio_filters")]
+    AudioFilter(&'static str),
+    #[error("color balance was not enabled in VideoOptions::color_balance")]
+    ColorBalance,
+    #[error("the equalizer has no band {0}")]
+    EqBand(usize),
+    #[error("the media has no audio stream {0}")]
+    AudioStream(usize),
+    #[error("the pipeline rejected the stream selection")]
+    StreamSelection,
+    #[error("the media has no subtitle stream {0}")]
+    SubtitleStream(usize),
+    #[error("subtitle stream format {0} cannot be exported as text")]
+    SubtitleCodec(String),
+    #[error("the pipeline cannot load subtitle files")]
+    SubtitleUri,
+    #[error(
+        "invalid image sequence pattern '{0}': expected a %d index and a png, jpg, or webp extension"
+    )]
+    ImageSequence(String),
+    #[error("the animated image is larger than {0} bytes; encode it as video instead")]
+    ImageTooLarge(usize),
+    #[error("no capture device with id '{0}' is connected")]
+    CaptureDevice(String),
+    #[error("the recording was not finished in time")]
+    RecordingTimeout,
+    #[error("hardware decoding was required but no hardware video decoder is installed")]
+    NoHardwareDecoder,
+    #[error("GStreamer was already initialized; call init() before opening any video")]
+    AlreadyInitialized,
+    #[error("a GStreamer plugin is missing: {name}")]
+    MissingPlugin { name: String },
+    #[error("no decoder is installed for {caps}")]
+    UnsupportedCodec { caps: String },
+    #[error("the media has no video stream; set VideoOptions::allow_audio_only to play it")]
+    NoVideoStream,
+    #[error("the pipeline did not finish starting in time")]
+    StateChangeTimeout,
+    #[error("media not found: {uri}")]
+    SourceNotFound { uri: String },
+    #[error("network request failed{}", .code.map(|code| format!(" with HTTP status {code}")).unwrap_or_default())]
+    Network {
+        /// HTTP status the server answered with, if it answered.
+        code: Option<u16>,
+    },
+}
+
+impl Error {
+    /// Classify an error posted on a pipeline's bus. `uri` is the media the
+    /// pipeline plays, if known.
+    pub(crate) fn from_bus(message: &gst::message::Error, uri: Option<&str>) -> Self {
+        let error = message.error();
+        let debug = message.debug().unwrap_or_default();
+        let network = uri.is_some_and(is_network_uri);
+
+        if error.matches(gst::CoreError::MissingPlugin) {
+            return Self::MissingPlugin {
+                name: error.message().to_string(),
+            };
+        }
+        if error.matches(gst::StreamError::CodecNotFound)
+            || error.matches(gst::StreamError::TypeNotFound)
+        {
+            // decodebin lists what it could not decode as "Missing decoder: <codec
+            // description> (<caps>)" in the debug message.
+            let caps = debug
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("Missing decoder: "))
+                .map(|missing| {
+                    missing
+                        .rsplit_once(" (")
+                        .map_or(missing, |(_, caps)| caps.strip_suffix(')').unwrap_or(caps))
+                })
+                .unwrap_or(error.message());
+            return Self::UnsupportedCodec {
+                caps: caps.to_string(),
+            };
+        }
+        if error.matches(gst::ResourceError::NotFound) && !network {
+            return Self::SourceNotFound {
+                uri: uri.unwrap_or_default().to_string(),
+            };
+        }
+        let resource = [
+            gst::ResourceError::NotFound,
+            gst::ResourceError::OpenRead,
+            gst::ResourceError::Read,
+            gst::ResourceError::Seek,
+            gst::ResourceError::NotAuthorized,
+            gst::ResourceError::Failed,
+        ];
+        if network && resource.iter().any(|kind| error.matches(*kind)) {
+            return Self::Network {
+                code: http_status(&debug),
+            };
+        }
+        Self::Glib(error)
+    }
+}
+
+/// The first HTTP status code in a source's debug message, e.g. 404 from
+/// `Not Found (404), URL: ...` as `souphttpsrc` reports it.
+fn http_status(debug: &str) -> Option<u16> {
+    debug.match_indices('(').find_map(|(start, _)| {
+        let code = debug.as_bytes().get(start + 1..start + 5)?;
+        if code[3] != b')' || !code[..3].iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        std::str::from_utf8(&code[..3])
+            .ok()?
+            .parse()
+            .ok()
+            .filter(|code| (100..600).contains(code))
+    })
+}
+
+/// The error the pipeline posted on its bus, if any, classified with
+/// [`Error::from_bus`]. A missing plugin decodebin reported takes precedence, since
+/// the error it goes on to post only says that the stream could not be decoded.
+pub(crate) fn pipeline_error(pipeline: &gst::Pipeline) -> Option<Error> {
+    let bus = pipeline.bus()?;
+    let mut missing = None;
+    let mut error = None;
+    while let Some(message) = bus.pop() {
+        match message.view() {
+            gst::MessageView::Element(element) if missing.is_none() => {
+                missing = element.structure().and_then(missing_plugin);
+            }
+            gst::MessageView::Error(err) if error.is_none() => {
+                let uri = pipeline
+                    .find_property("uri")
+                    .and_then(|_| pipeline.property::<Option<String>>("uri"));
+                error = Some(Error::from_bus(err, uri.as_deref()));
+            }
+            _ => {}
+        }
+    }
+    match (missing, error?) {
+        (Some(name), Error::UnsupportedCodec { .. } | Error::MissingPlugin { .. }) => {
+            Some(Error::MissingPlugin { name })
+        }
+        (_, error) => Some(error),
+    }
+}
+
+/// Why a decoded frame could not be handed out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum FrameError {
+    #[error("failed to map the frame buffer for reading")]
+    Map,
+    #[error("the stream ended and no frames are left")]
+    Eos,
 }
@@ -0,0 +1,266 @@
+use crate::level::ChannelLevel;
+use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
+use parking_lot::Mutex;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak, mpsc};
+use std::time::{Duration, Instant};
+
+/// Notifications emitted by a [`Video`](crate::Video) while it plays.
+///
+/// Events are produced on the video's worker thread and delivered through the
+/// channels returned by [`Video::subscribe`](crate::Video::subscribe), or to
+/// callbacks registered with [`Video::on_event`](crate::Video::on_event) (on the
+/// worker thread) or [`Video::on_event_foreground`](crate::Video::on_event_foreground)
+/// (on gpui's foreground thread).
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum VideoEvent {
+    /// Playback reached the end (or the end of an A/B loop range) and restarted because
+    /// looping is enabled.
+    Looped,
+    /// Playback reached the end, or [`VideoOptions::stop_at`](crate::VideoOptions::stop_at),
+    /// and stopped there.
+    Ended,
+    /// A boundary preview started by [`Video::preview_boundary`](crate::Video::preview_boundary)
+    /// finished and playback returned to the boundary.
+    PreviewFinished,
+    /// A network source failed and will be reconnected at the last played position
+    /// after a wait that grows with each attempt.
+    Reconnecting { attempt: u32 },
+    /// Frames are flowing again after a reconnect.
+    Reconnected,
+    /// The source's URL expired and it was reopened with one from
+    /// [`VideoOptions::uri_refresher`](crate::VideoOptions::uri_refresher).
+    UriRefreshed,
+    /// A fatal error stopped the pipeline, and it is being restarted at the last
+    /// shown frame per [`VideoOptions::recovery`](crate::VideoOptions::recovery).
+    Recovering { attempt: u32 },
+    /// Frames are flowing again after a restart.
+    Recovered { attempts: u32 },
+    /// Restarting the pipeline did not help, and playback has ended with `error`,
+    /// which is also sent as [`Error`](Self::Error).
+    Failed { attempts: u32, error: String },
+    /// The media's streams or the ones playing changed; re-read
+    /// [`Video::audio_tracks`](crate::Video::audio_tracks) and
+    /// [`Video::subtitle_tracks`](crate::Video::subtitle_tracks). Sent by playbin3
+    /// and other decodebin3 pipelines, which report their streams as they change.
+    TracksChanged,
+    /// The network buffer fill level changed. Playback is held below 100%.
+    Buffering { percent: u8 },
+    /// GStreamer has no plugin for part of the media, e.g. `H.265 (Main Profile)
+    /// decoder`. The stream it was needed for is not played; if that leaves nothing
+    /// to play, [`Error`](Self::Error) follows. Sent once per plugin.
+    MissingPlugin { name: String },
+    /// The pipeline reported a recoverable problem, such as a decoder concealing a
+    /// corrupt frame. Carries the running totals since the video was opened.
+    StreamHealth(StreamHealth),
+    /// Playback jumped over a skip segment or a silent span.
+    Skipped { from: Duration, to: Duration },
+    /// Audio levels for the interval that just played, one entry per channel. Only
+    /// emitted when the video was created with
+    /// [`VideoOptions::audio_levels`](crate::VideoOptions::audio_levels).
+    AudioLevels(Vec<ChannelLevel>),
+    /// A fatal pipeline error, or a panic while handling a frame. Panics are
+    /// survived unless they repeat; check [`Video::is_healthy`](crate::Video::is_healthy).
+    Error(String),
+    /// A recording started by [`Video::record_to`](crate::Video::record_to) was
+    /// stopped and its file at this path is complete.
+    RecordingFinished(PathBuf),
+    /// Fraction (0.0 to 1.0) of a [`Video::export_clip`](crate::Video::export_clip)
+    /// written so far.
+    ClipProgress { path: PathBuf, progress: f32 },
+    /// A clip export finished and its file at this path is complete.
+    ClipExported(PathBuf),
+    /// This percentage of the media has now been watched, counting each part once
+    /// however often it is replayed. Sent once per milestone set with
+    /// [`Video::set_progress_milestones`](crate::Video::set_progress_milestones);
+    /// never for live streams.
+    ProgressMilestone { percent: u8 },
+    /// The video was playing and has been paused because the system is going to
+    /// sleep; see [`system_will_sleep`](crate::system_will_sleep).
+    SystemSleep,
+    /// The system woke after a [`SystemSleep`](Self::SystemSleep). `resumed` tells
+    /// whether playback was resumed, as set with [`set_wake_policy`](crate::set_wake_policy).
+    SystemWake { resumed: bool },
+    /// The [`DecodeGovernor`](crate::DecodeGovernor) paused this video, or held it
+    /// paused when asked to play, until another video frees a slot.
+    DecodeQueued,
+    /// The [`DecodeGovernor`](crate::DecodeGovernor) gave a queued video a slot and
+    /// it started playing.
+    DecodeStarted,
+    /// The registered [`SubtitleProvider`](crate::SubtitleProvider) found subtitles
+    /// for media without any, and they were loaded.
+    SubtitlesFound {
+        language: Option<String>,
+        name: Option<String>,
+    },
+    /// The registered [`SubtitleProvider`](crate::SubtitleProvider) found no
+    /// subtitles for media without any, or they failed to load.
+    SubtitlesNotFound,
+}
+
+/// Running totals of problems GStreamer reported while playback carried on, so
+/// glitchy recordings can be flagged even when they play.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamHealth {
+    /// Warning messages, e.g. decode errors that were concealed.
+    pub warnings: u32,
+    /// Frames dropped for quality of service, summed over all elements.
+    pub dropped_frames: u64,
+    /// Playback position when the latest problem was reported.
+    pub last_issue_at: Option<Duration>,
+    /// Text of the latest warning.
+    pub last_warning: Option<String>,
+}
+
+/// Callback run on the worker thread for every event.
+#[derive(Clone)]
+struct EventCallback(Arc<dyn Fn(&VideoEvent) + Send + Sync>);
+
+impl std::fmt::Debug for EventCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("EventCallback")
+    }
+}
+
+/// Keeps a callback registered with [`Video::on_event`](crate::Video::on_event)
+/// running; dropping it unregisters the callback.
+///
+/// The callback is owned by the video, so one that holds a clone of the video
+/// keeps it alive, pipeline and worker thread included, until the subscription is
+/// dropped. Capture a [`WeakVideo`](crate::WeakVideo) instead where the
+/// subscription is [`detach`](Self::detach)ed.
+#[must_use = "dropping the subscription unregisters the callback"]
+#[derive(Debug)]
+pub struct EventSubscription {
+    hub: Weak<EventHub>,
+    id: u64,
+}
+
+impl EventSubscription {
+    /// Keep the callback registered for as long as the video lives.
+    pub fn detach(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        if let Some(hub) = self.hub.upgrade() {
+            hub.callbacks.lock().retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+/// Fans events out to every live subscriber, dropping those whose receiver is gone,
+/// and to registered callbacks.
+#[derive(Debug, Default)]
+pub(crate) struct EventHub {
+    subscribers: Mutex<Vec<mpsc::Sender<VideoEvent>>>,
+    async_subscribers: Mutex<Vec<UnboundedSender<VideoEvent>>>,
+    callbacks: Mutex<Vec<(u64, EventCallback)>>,
+    next_callback_id: AtomicU64,
+}
+
+impl EventHub {
+    pub(crate) fn subscribe(&self) -> mpsc::Receiver<VideoEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.lock().push(tx);
+        rx
+    }
+
+    /// Like [`subscribe`](Self::subscribe), for awaiting events on gpui's executors.
+    /// The stream ends when the video is dropped.
+    pub(crate) fn subscribe_async(&self) -> UnboundedReceiver<VideoEvent> {
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        self.async_subscribers.lock().push(tx);
+        rx
+    }
+
+    pub(crate) fn add_callback(
+        self: &Arc<Self>,
+        callback: impl Fn(&VideoEvent) + Send + Sync + 'static,
+    ) -> EventSubscription {
+        let id = self.next_callback_id.fetch_add(1, Ordering::Relaxed);
+        self.callbacks
+            .lock()
+            .push((id, EventCallback(Arc::new(callback))));
+        EventSubscription {
+            hub: Arc::downgrade(self),
+            id,
+        }
+    }
+
+    pub(crate) fn emit(&self, event: VideoEvent) {
+        self.subscribers
+            .lock()
+            .retain(|tx| tx.send(event.clone()).is_ok());
+        self.async_subscribers
+            .lock()
+            .retain(|tx| tx.unbounded_send(event.clone()).is_ok());
+        // Run callbacks without holding the lock, so they may register more.
+        let callbacks = self.callbacks.lock().clone();
+        for (_, callback) in callbacks {
+            // A panicking callback must not take the worker down with it.
+            if std::panic::catch_unwind(AssertUnwindSafe(|| (callback.0)(&event))).is_err() {
+                log::error!("video event callback panicked on {event:?}");
+            }
+        }
+    }
+}
+
+/// Receivers registered with [`Video::watch_position`](crate::Video::watch_position),
+/// fed by the worker thread.
+#[derive(Debug, Default)]
+pub(crate) struct PositionWatchers {
+    watchers: Mutex<Vec<PositionWatcher>>,
+}
+
+#[derive(Debug)]
+struct PositionWatcher {
+    interval: Duration,
+    next_at: Instant,
+    last: Option<Duration>,
+    tx: mpsc::Sender<Duration>,
+}
+
+impl PositionWatchers {
+    pub(crate) fn watch(&self, interval: Duration) -> mpsc::Receiver<Duration> {
+        let (tx, rx) = mpsc::channel();
+        self.watchers.lock().push(PositionWatcher {
+            interval,
+            next_at: Instant::now(),
+            last: None,
+            tx,
+        });
+        rx
+    }
+
+    /// Whether a watcher wants a report, so the pipeline is only queried when needed.
+    pub(crate) fn is_due(&self) -> bool {
+        let now = Instant::now();
+        self.watchers
+            .lock()
+            .iter()
+            .any(|watcher| watcher.next_at <= now)
+    }
+
+    /// Send `position` to every due watcher whose last report differs, dropping
+    /// those whose receiver is gone.
+    pub(crate) fn report(&self, position: Duration) {
+        let now = Instant::now();
+        self.watchers.lock().retain_mut(|watcher| {
+            if watcher.next_at > now {
+                return true;
+            }
+            watcher.next_at = now + watcher.interval;
+            if watcher.last == Some(position) {
+                return true;
+            }
+            watcher.last = Some(position);
+            watcher.tx.send(position).is_ok()
+        });
+    }
+}
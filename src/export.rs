@@ -0,0 +1,758 @@
+use crate::Error;
+use crate::encode::{AudioCodec, Container, EncodeProfile};
+use crate::runtime;
+use crate::thumbnail::sample_to_rgba;
+use crate::video::TimeRange;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use gstreamer_pbutils as gst_pbutils;
+use parking_lot::Mutex;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// How long to wait for export pipelines to preroll before seeking.
+const PREROLL_TIMEOUT: gst::ClockTime = gst::ClockTime::from_seconds(10);
+
+/// How often long exports report progress.
+const PROGRESS_INTERVAL: gst::ClockTime = gst::ClockTime::from_mseconds(250);
+
+/// Display time for subtitle buffers that carry no duration.
+const DEFAULT_CUE_DURATION: Duration = Duration::from_secs(2);
+
+/// File format for [`Video::export_audio`](crate::Video::export_audio).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioFormat {
+    Mp3,
+    /// AAC in an MPEG-4 (`.m4a`) container.
+    Aac,
+    Flac,
+    Wav,
+}
+
+impl AudioFormat {
+    /// Conventional file extension, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::Aac => "m4a",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Wav => "wav",
+        }
+    }
+
+    fn encoding_profile(self) -> gst_pbutils::EncodingProfile {
+        let audio = |caps: &gst::Caps| gst_pbutils::EncodingAudioProfile::builder(caps).build();
+        match self {
+            AudioFormat::Mp3 => audio(
+                &gst::Caps::builder("audio/mpeg")
+                    .field("mpegversion", 1i32)
+                    .field("layer", 3i32)
+                    .build(),
+            )
+            .upcast(),
+            AudioFormat::Flac => audio(&AudioCodec::Flac.caps()).upcast(),
+            AudioFormat::Aac => {
+                gst_pbutils::EncodingContainerProfile::builder(&Container::Mp4.caps())
+                    .add_profile(audio(&AudioCodec::Aac.caps()))
+                    .build()
+                    .upcast()
+            }
+            AudioFormat::Wav => gst_pbutils::EncodingContainerProfile::builder(
+                &gst::Caps::builder("audio/x-wav").build(),
+            )
+            .add_profile(audio(&AudioCodec::Pcm.caps()))
+            .build()
+            .upcast(),
+        }
+    }
+}
+
+/// Image file format for [`Video::export_frames`](crate::Video::export_frames).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Png,
+    /// Lossy and without alpha, but much smaller than PNG.
+    Jpeg,
+}
+
+impl ImageFormat {
+    /// Conventional file extension, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpg",
+        }
+    }
+}
+
+/// Settings for [`Video::export_clip`](crate::Video::export_clip).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipOptions {
+    /// Container written when the streams are copied. Ignored when re-encoding,
+    /// where the profile's container is used.
+    pub container: Container,
+    /// Re-encode with this profile instead of copying the streams. Copying is fast
+    /// and lossless, but starts the clip at the keyframe before `start`, and drops
+    /// streams the container cannot hold.
+    pub profile: Option<EncodeProfile>,
+}
+
+impl Default for ClipOptions {
+    fn default() -> Self {
+        Self {
+            // Matroska can hold nearly any codec, so stream copies rarely lose a track.
+            container: Container::Matroska,
+            profile: None,
+        }
+    }
+}
+
+/// Caption file format for [`Video::export_subtitles`](crate::Video::export_subtitles).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SubtitleFormat {
+    /// SubRip (`.srt`).
+    Srt,
+    /// WebVTT (`.vtt`).
+    Vtt,
+}
+
+impl SubtitleFormat {
+    /// Conventional file extension, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::Vtt => "vtt",
+        }
+    }
+
+    fn timestamp(self, time: Duration) -> String {
+        let millis = time.as_millis();
+        let separator = match self {
+            SubtitleFormat::Srt => ',',
+            SubtitleFormat::Vtt => '.',
+        };
+        format!(
+            "{:02}:{:02}:{:02}{separator}{:03}",
+            millis / 3_600_000,
+            millis / 60_000 % 60,
+            millis / 1000 % 60,
+            millis % 1000
+        )
+    }
+
+    /// Turn a buffer's text into cue text. Pango markup (from ASS/SSA or styled
+    /// streams) keeps only the italic, bold, and underline tags both formats share.
+    fn cue_text(self, text: &str, markup: bool) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('<').filter(|_| markup) {
+            out.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('>') else {
+                rest = &rest[start..];
+                break;
+            };
+            let tag = &rest[start..=start + end];
+            if matches!(tag, "<i>" | "</i>" | "<b>" | "</b>" | "<u>" | "</u>") {
+                out.push_str(tag);
+            }
+            rest = &rest[start + end + 1..];
+        }
+        out.push_str(rest);
+
+        // Markup escapes text like WebVTT does; SubRip has no escapes.
+        let out = match (self, markup) {
+            (SubtitleFormat::Srt, true) => out
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&amp;", "&"),
+            (SubtitleFormat::Vtt, false) => out
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;"),
+            _ => out,
+        };
+        // A blank line would end the cue early.
+        out.trim()
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Transcode the audio track of `uri` (optionally only `range`) to `path`.
+pub(crate) fn export_audio(
+    uri: &url::Url,
+    range: Option<TimeRange>,
+    format: AudioFormat,
+    path: &Path,
+) -> Result<(), Error> {
+    runtime::ensure_init()?;
+
+    let pipeline = gst::Pipeline::new();
+    let source = gst::ElementFactory::make("uridecodebin")
+        .property("uri", uri.as_str())
+        .build()?;
+    let convert = gst::ElementFactory::make("audioconvert").build()?;
+    let resample = gst::ElementFactory::make("audioresample").build()?;
+    let encoder = gst::ElementFactory::make("encodebin")
+        .property("profile", format.encoding_profile())
+        .build()?;
+    let sink = gst::ElementFactory::make("filesink")
+        .property("location", path.to_string_lossy().as_ref())
+        .build()?;
+
+    pipeline.add_many([&source, &convert, &resample, &encoder, &sink])?;
+    gst::Element::link_many([&convert, &resample])?;
+    let encoder_pad = encoder.request_pad_simple("audio_%u").ok_or(Error::Caps)?;
+    resample
+        .static_pad("src")
+        .ok_or(Error::Caps)?
+        .link(&encoder_pad)
+        .map_err(|_| Error::Caps)?;
+    encoder.link(&sink)?;
+
+    link_decoded_pads(&source, &convert, "audio/");
+    run_to_eos(&pipeline, range)
+}
+
+/// Decode the video track of `uri` as fast as possible and write every
+/// `every_nth` frame within `range` to `dir` as `frame_000000.<ext>`.
+/// Returns the number of images written.
+pub(crate) fn export_frames(
+    uri: &url::Url,
+    range: Option<TimeRange>,
+    every_nth: usize,
+    dir: &Path,
+    format: ImageFormat,
+    mut progress: Option<&mut dyn FnMut(usize, Duration)>,
+) -> Result<usize, Error> {
+    runtime::ensure_init()?;
+    std::fs::create_dir_all(dir)?;
+    let every_nth = every_nth.max(1);
+
+    let pipeline = gst::Pipeline::new();
+    let source = gst::ElementFactory::make("uridecodebin")
+        .property("uri", uri.as_str())
+        .build()?;
+    let convert = gst::ElementFactory::make("videoconvert").build()?;
+    let caps = gst::Caps::builder("video/x-raw")
+        .field("format", "RGBA")
+        .field("pixel-aspect-ratio", gst::Fraction::new(1, 1))
+        .build();
+    // Unsynchronized so frames are decoded as fast as possible, not in real time.
+    let sink = gst_app::AppSink::builder().caps(&caps).sync(false).build();
+
+    pipeline.add_many([&source, &convert, sink.upcast_ref()])?;
+    convert.link(&sink)?;
+    link_decoded_pads(&source, &convert, "video/");
+
+    let result = (|| -> Result<usize, Error> {
+        pipeline.set_state(gst::State::Paused)?;
+        pipeline.state(PREROLL_TIMEOUT).0?;
+        if let Some(range) = range {
+            pipeline.seek(
+                1.0,
+                gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                gst::SeekType::Set,
+                gst::ClockTime::from_nseconds(range.start.as_nanos() as u64),
+                gst::SeekType::Set,
+                gst::ClockTime::from_nseconds(range.end.as_nanos() as u64),
+            )?;
+        }
+        pipeline.set_state(gst::State::Playing)?;
+
+        let mut decoded = 0;
+        let mut written = 0;
+        // pull_sample fails at EOS or when the pipeline errors out.
+        while let Ok(sample) = sink.pull_sample() {
+            decoded += 1;
+            if (decoded - 1) % every_nth != 0 {
+                continue;
+            }
+            let image = sample_to_rgba(&sample)?;
+            let path = dir.join(format!("frame_{written:06}.{}", format.extension()));
+            match format {
+                ImageFormat::Png => image.save_with_format(&path, image::ImageFormat::Png)?,
+                ImageFormat::Jpeg => image::DynamicImage::ImageRgba8(image)
+                    .to_rgb8()
+                    .save_with_format(&path, image::ImageFormat::Jpeg)?,
+            }
+            written += 1;
+
+            if let Some(progress) = progress.as_mut() {
+                let position = sample
+                    .buffer()
+                    .and_then(|buffer| buffer.pts())
+                    .map(|pts| Duration::from_nanos(pts.nseconds()))
+                    .unwrap_or_default();
+                progress(written, position);
+            }
+        }
+
+        let bus = pipeline.bus().ok_or(Error::Bus)?;
+        if let Some(msg) = bus.pop_filtered(&[gst::MessageType::Error])
+            && let gst::MessageView::Error(err) = msg.view()
+        {
+            return Err(Error::from_bus(err, Some(uri.as_str())));
+        }
+        Ok(written)
+    })();
+
+    if let Err(err) = pipeline.set_state(gst::State::Null) {
+        log::error!("failed to shut down export pipeline: {}", err);
+    }
+    result
+}
+
+/// Write one row per video frame of `uri` (PTS, DTS, duration, keyframe flag, and
+/// size in bytes) to `path`, as JSON if its extension is `json` and CSV otherwise.
+/// Returns the number of frames indexed.
+pub(crate) fn export_frame_index(uri: &url::Url, path: &Path) -> Result<usize, Error> {
+    runtime::ensure_init()?;
+    let json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+    // Parsers split the stream into frames and flag keyframes without decoding,
+    // which is far cheaper than a decode pipeline.
+    let pipeline = gst::Pipeline::new();
+    let source = gst::ElementFactory::make("urisourcebin")
+        .property("uri", uri.as_str())
+        .build()?;
+    let parse = gst::ElementFactory::make("parsebin").build()?;
+    let sink = gst_app::AppSink::builder().sync(false).build();
+
+    pipeline.add_many([&source, &parse, sink.upcast_ref()])?;
+    link_source_to_parser(&source, &parse);
+    link_decoded_pads(&parse, sink.upcast_ref(), "video/");
+
+    let result = (|| -> Result<usize, Error> {
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        if json {
+            write!(out, "[")?;
+        } else {
+            writeln!(out, "pts_ns,dts_ns,duration_ns,keyframe,size")?;
+        }
+
+        pipeline.set_state(gst::State::Playing)?;
+        let mut frames = 0;
+        // pull_sample fails at EOS or when the pipeline errors out.
+        while let Ok(sample) = sink.pull_sample() {
+            let Some(buffer) = sample.buffer() else {
+                continue;
+            };
+            let pts = buffer.pts().map(|t| t.nseconds());
+            let dts = buffer.dts().map(|t| t.nseconds());
+            let duration = buffer.duration().map(|t| t.nseconds());
+            let keyframe = !buffer.flags().contains(gst::BufferFlags::DELTA_UNIT);
+            let size = buffer.size();
+            if json {
+                let field =
+                    |value: Option<u64>| value.map_or("null".to_string(), |v| v.to_string());
+                write!(
+                    out,
+                    "{}\n  {{\"pts_ns\": {}, \"dts_ns\": {}, \"duration_ns\": {}, \"keyframe\": {keyframe}, \"size\": {size}}}",
+                    if frames == 0 { "" } else { "," },
+                    field(pts),
+                    field(dts),
+                    field(duration),
+                )?;
+            } else {
+                let field = |value: Option<u64>| value.map(|v| v.to_string()).unwrap_or_default();
+                writeln!(
+                    out,
+                    "{},{},{},{keyframe},{size}",
+                    field(pts),
+                    field(dts),
+                    field(duration),
+                )?;
+            }
+            frames += 1;
+        }
+        if json {
+            writeln!(out, "\n]")?;
+        }
+        out.flush()?;
+
+        let bus = pipeline.bus().ok_or(Error::Bus)?;
+        if let Some(msg) = bus.pop_filtered(&[gst::MessageType::Error])
+            && let gst::MessageView::Error(err) = msg.view()
+        {
+            return Err(Error::from_bus(err, Some(uri.as_str())));
+        }
+        Ok(frames)
+    })();
+
+    if let Err(err) = pipeline.set_state(gst::State::Null) {
+        log::error!("failed to shut down frame index pipeline: {}", err);
+    }
+    result
+}
+
+/// Extract subtitle stream `stream_index` (counting subtitle streams only) of
+/// `uri` to `path` as `format`. The container is only parsed, not decoded.
+/// Returns the number of cues written.
+pub(crate) fn export_subtitles(
+    uri: &url::Url,
+    stream_index: usize,
+    format: SubtitleFormat,
+    path: &Path,
+) -> Result<usize, Error> {
+    runtime::ensure_init()?;
+
+    let pipeline = gst::Pipeline::new();
+    let source = gst::ElementFactory::make("urisourcebin")
+        .property("uri", uri.as_str())
+        .build()?;
+    let parse = gst::ElementFactory::make("parsebin").build()?;
+    let sink = gst_app::AppSink::builder().sync(false).build();
+    pipeline.add_many([&source, &parse, sink.upcast_ref()])?;
+    link_source_to_parser(&source, &parse);
+
+    // Caps of the chosen stream, recorded to explain a failed export.
+    let chosen: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    let linked = Arc::new(AtomicBool::new(false));
+    let seen = AtomicUsize::new(0);
+    let pipeline_weak = pipeline.downgrade();
+    let sink_weak = sink.downgrade();
+    let chosen_ref = Arc::clone(&chosen);
+    let linked_ref = Arc::clone(&linked);
+    parse.connect_pad_added(move |_, pad| {
+        let caps = pad.current_caps().unwrap_or_else(|| pad.query_caps(None));
+        let Some(name) = caps.structure(0).map(|s| s.name().to_string()) else {
+            return;
+        };
+        let is_ssa = matches!(name.as_str(), "application/x-ssa" | "application/x-ass");
+        let is_subtitle = name.starts_with("text/") || name.starts_with("subpicture/") || is_ssa;
+        if !is_subtitle || seen.fetch_add(1, Ordering::SeqCst) != stream_index {
+            return;
+        }
+        *chosen_ref.lock() = Some(name.clone());
+        let (Some(pipeline), Some(sink)) = (pipeline_weak.upgrade(), sink_weak.upgrade()) else {
+            return;
+        };
+        let result = (|| -> Result<(), Error> {
+            let target = if is_ssa {
+                // ssaparse turns ASS/SSA events into pango-markup text.
+                let ssaparse = gst::ElementFactory::make("ssaparse").build()?;
+                pipeline.add(&ssaparse)?;
+                ssaparse.link(&sink)?;
+                ssaparse.sync_state_with_parent()?;
+                ssaparse
+            } else if name == "text/x-raw" {
+                sink.upcast()
+            } else {
+                return Ok(());
+            };
+            let target_pad = target.static_pad("sink").ok_or(Error::Caps)?;
+            pad.link(&target_pad).map_err(|_| Error::Caps)?;
+            linked_ref.store(true, Ordering::SeqCst);
+            Ok(())
+        })();
+        if let Err(err) = result {
+            log::error!("failed to link subtitle stream for export: {}", err);
+        }
+    });
+    // Without a linked stream the sink would wait for data forever.
+    let sink_weak = sink.downgrade();
+    let linked_ref = Arc::clone(&linked);
+    parse.connect_no_more_pads(move |_| {
+        if !linked_ref.load(Ordering::SeqCst)
+            && let Some(sink) = sink_weak.upgrade()
+        {
+            sink.send_event(gst::event::Eos::new());
+        }
+    });
+
+    let result = (|| -> Result<usize, Error> {
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        if format == SubtitleFormat::Vtt {
+            writeln!(out, "WEBVTT\n")?;
+        }
+
+        pipeline.set_state(gst::State::Playing)?;
+        let mut cues = 0;
+        // pull_sample fails at EOS or when the pipeline errors out.
+        while let Ok(sample) = sink.pull_sample() {
+            let (Some(buffer), Some(segment)) = (sample.buffer(), sample.segment()) else {
+                continue;
+            };
+            let Some(start) = buffer.pts().and_then(|pts| {
+                segment
+                    .downcast_ref::<gst::ClockTime>()
+                    .and_then(|segment| segment.to_stream_time(pts))
+            }) else {
+                continue;
+            };
+            let start = Duration::from_nanos(start.nseconds());
+            let end = start
+                + buffer
+                    .duration()
+                    .map_or(DEFAULT_CUE_DURATION, |d| Duration::from_nanos(d.nseconds()));
+            let markup = sample
+                .caps()
+                .and_then(|caps| caps.structure(0))
+                .is_some_and(|s| s.get::<&str>("format") == Ok("pango-markup"));
+            let Ok(map) = buffer.map_readable() else {
+                continue;
+            };
+            let text = format.cue_text(&String::from_utf8_lossy(&map), markup);
+            if text.is_empty() {
+                continue;
+            }
+
+            cues += 1;
+            if format == SubtitleFormat::Srt {
+                writeln!(out, "{cues}")?;
+            }
+            writeln!(
+                out,
+                "{} --> {}\n{text}\n",
+                format.timestamp(start),
+                format.timestamp(end)
+            )?;
+        }
+        out.flush()?;
+
+        let bus = pipeline.bus().ok_or(Error::Bus)?;
+        if let Some(msg) = bus.pop_filtered(&[gst::MessageType::Error])
+            && let gst::MessageView::Error(err) = msg.view()
+        {
+            return Err(Error::from_bus(err, Some(uri.as_str())));
+        }
+        if !linked.load(Ordering::SeqCst) {
+            return Err(match chosen.lock().take() {
+                Some(caps) => Error::SubtitleCodec(caps),
+                None => Error::SubtitleStream(stream_index),
+            });
+        }
+        Ok(cues)
+    })();
+
+    if let Err(err) = pipeline.set_state(gst::State::Null) {
+        log::error!("failed to shut down subtitle export pipeline: {}", err);
+    }
+    result
+}
+
+/// Write `range` of `uri` to `path`, copying the streams unless `options` asks
+/// for re-encoding. `progress` is called with the fraction done as it runs.
+pub(crate) fn export_clip(
+    uri: &url::Url,
+    range: TimeRange,
+    path: &Path,
+    options: &ClipOptions,
+    progress: impl Fn(f32),
+) -> Result<(), Error> {
+    runtime::ensure_init()?;
+
+    let pipeline = gst::Pipeline::new();
+    let sink = gst::ElementFactory::make("filesink")
+        .property("location", path.to_string_lossy().as_ref())
+        .build()?;
+    let (source, target, flags) = match &options.profile {
+        None => {
+            let source = gst::ElementFactory::make("urisourcebin")
+                .property("uri", uri.as_str())
+                .build()?;
+            let parse = gst::ElementFactory::make("parsebin").build()?;
+            let muxer = gst::ElementFactory::make(options.container.muxer()).build()?;
+            pipeline.add_many([&source, &parse, &muxer, &sink])?;
+            link_source_to_parser(&source, &parse);
+            // Copied streams can only start at a keyframe.
+            let flags = gst::SeekFlags::KEY_UNIT | gst::SeekFlags::SNAP_BEFORE;
+            (parse, muxer, flags)
+        }
+        Some(profile) => {
+            let source = gst::ElementFactory::make("uridecodebin")
+                .property("uri", uri.as_str())
+                .build()?;
+            let encoder = gst::ElementFactory::make("encodebin")
+                .property("profile", profile.to_encoding_profile()?)
+                .build()?;
+            pipeline.add_many([&source, &encoder, &sink])?;
+            (source, encoder, gst::SeekFlags::ACCURATE)
+        }
+    };
+    target.link(&sink)?;
+
+    let copy = options.profile.is_none();
+    let keep_video = options.profile.as_ref().is_none_or(|p| p.video.is_some());
+    let keep_audio = options.profile.as_ref().is_none_or(|p| p.audio.is_some());
+    let pipeline_weak = pipeline.downgrade();
+    let target_weak = target.downgrade();
+    source.connect_pad_added(move |_, pad| {
+        let (Some(pipeline), Some(target)) = (pipeline_weak.upgrade(), target_weak.upgrade())
+        else {
+            return;
+        };
+        let caps = pad.current_caps().unwrap_or_else(|| pad.query_caps(None));
+        let Some(name) = caps.structure(0).map(|s| s.name().to_string()) else {
+            return;
+        };
+        let (chain, template): (&[&str], _) = match name.split('/').next() {
+            Some("video") if keep_video && copy => (&["queue"], None),
+            Some("video") if keep_video => (&["videoconvert", "queue"], Some("video_%u")),
+            Some("audio") if keep_audio && copy => (&["queue"], None),
+            Some("audio") if keep_audio => (
+                &["audioconvert", "audioresample", "queue"],
+                Some("audio_%u"),
+            ),
+            _ => return,
+        };
+        if let Err(err) = attach_stream(&pipeline, pad, &caps, chain, &target, template) {
+            log::warn!("dropping {name} stream from clip: {}", err);
+        }
+    });
+
+    run_range(&pipeline, Some(range), flags, progress)
+}
+
+/// Link `pad` through new `chain` elements into `target`: a request pad from
+/// `template`, or, for muxers, whichever request pad accepts `caps`.
+fn attach_stream(
+    pipeline: &gst::Pipeline,
+    pad: &gst::Pad,
+    caps: &gst::Caps,
+    chain: &[&str],
+    target: &gst::Element,
+    template: Option<&str>,
+) -> Result<(), Error> {
+    let target_pad = match template {
+        Some(template) => target.request_pad_simple(template),
+        None => target.compatible_pad(pad, Some(caps)),
+    }
+    .ok_or(Error::Caps)?;
+    let chain = chain
+        .iter()
+        .map(|factory| gst::ElementFactory::make(factory).build())
+        .collect::<Result<Vec<_>, _>>()?;
+    pipeline.add_many(&chain)?;
+    gst::Element::link_many(&chain)?;
+    let (Some(first), Some(last)) = (chain.first(), chain.last()) else {
+        return Err(Error::Caps);
+    };
+    last.static_pad("src")
+        .ok_or(Error::Caps)?
+        .link(&target_pad)
+        .map_err(|_| Error::Caps)?;
+    for element in &chain {
+        element.sync_state_with_parent()?;
+    }
+    pad.link(&first.static_pad("sink").ok_or(Error::Caps)?)
+        .map_err(|_| Error::Caps)?;
+    Ok(())
+}
+
+/// Feed the pads `source` (a `urisourcebin`) exposes into `parse`.
+fn link_source_to_parser(source: &gst::Element, parse: &gst::Element) {
+    let parse_weak = parse.downgrade();
+    source.connect_pad_added(move |_, pad| {
+        let Some(sink_pad) = parse_weak
+            .upgrade()
+            .and_then(|parse| parse.static_pad("sink"))
+        else {
+            return;
+        };
+        if !sink_pad.is_linked()
+            && let Err(err) = pad.link(&sink_pad)
+        {
+            log::error!("failed to link source to parser: {}", err);
+        }
+    });
+}
+
+/// Link the first decoded pad of `source` whose caps start with `media` to `target`.
+/// Other streams are left unlinked so they are not decoded further.
+pub(crate) fn link_decoded_pads(source: &gst::Element, target: &gst::Element, media: &'static str) {
+    let target_weak = target.downgrade();
+    source.connect_pad_added(move |_, pad| {
+        let Some(target) = target_weak.upgrade() else {
+            return;
+        };
+        let Some(sink_pad) = target.static_pad("sink") else {
+            return;
+        };
+        if sink_pad.is_linked() {
+            return;
+        }
+        let caps = pad.current_caps().unwrap_or_else(|| pad.query_caps(None));
+        let matches = caps
+            .structure(0)
+            .is_some_and(|s| s.name().starts_with(media));
+        if matches && let Err(err) = pad.link(&sink_pad) {
+            log::error!("failed to link {media} stream for export: {}", err);
+        }
+    });
+}
+
+/// Play an export pipeline until EOS, restricted to `range` if given, and shut it down.
+pub(crate) fn run_to_eos(pipeline: &gst::Pipeline, range: Option<TimeRange>) -> Result<(), Error> {
+    run_range(pipeline, range, gst::SeekFlags::ACCURATE, |_| {})
+}
+
+/// [`run_to_eos`] with extra seek `flags`, calling `progress` with the fraction of
+/// `range` (or of the whole media) done every [`PROGRESS_INTERVAL`].
+fn run_range(
+    pipeline: &gst::Pipeline,
+    range: Option<TimeRange>,
+    flags: gst::SeekFlags,
+    progress: impl Fn(f32),
+) -> Result<(), Error> {
+    let result = (|| -> Result<(), Error> {
+        pipeline.set_state(gst::State::Paused)?;
+        pipeline.state(PREROLL_TIMEOUT).0?;
+        if let Some(range) = range {
+            pipeline.seek(
+                1.0,
+                gst::SeekFlags::FLUSH | flags,
+                gst::SeekType::Set,
+                gst::ClockTime::from_nseconds(range.start.as_nanos() as u64),
+                gst::SeekType::Set,
+                gst::ClockTime::from_nseconds(range.end.as_nanos() as u64),
+            )?;
+        }
+        pipeline.set_state(gst::State::Playing)?;
+
+        let (start, end) = match range {
+            Some(range) => (range.start, range.end),
+            None => (
+                Duration::ZERO,
+                pipeline
+                    .query_duration::<gst::ClockTime>()
+                    .map_or(Duration::ZERO, |d| Duration::from_nanos(d.nseconds())),
+            ),
+        };
+        let bus = pipeline.bus().ok_or(Error::Bus)?;
+        loop {
+            let msg = bus.timed_pop_filtered(
+                PROGRESS_INTERVAL,
+                &[gst::MessageType::Eos, gst::MessageType::Error],
+            );
+            match msg.as_ref().map(|msg| msg.view()) {
+                Some(gst::MessageView::Eos(_)) => {
+                    progress(1.0);
+                    return Ok(());
+                }
+                Some(gst::MessageView::Error(err)) => return Err(Error::from_bus(err, None)),
+                _ => {}
+            }
+            if end > start
+                && let Some(position) = pipeline.query_position::<gst::ClockTime>()
+            {
+                let done = Duration::from_nanos(position.nseconds()).saturating_sub(start);
+                progress((done.as_secs_f64() / (end - start).as_secs_f64()).min(1.0) as f32);
+            }
+        }
+    })();
+
+    if let Err(err) = pipeline.set_state(gst::State::Null) {
+        log::error!("failed to shut down export pipeline: {}", err);
+    }
+    result
+}
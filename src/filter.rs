@@ -0,0 +1,187 @@
+use crate::Error;
+use crate::level::make_level_element;
+use crate::spectrum::make_spectrum_element;
+use crate::video::VideoOptions;
+use crate::waveform::make_audio_tap;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+
+pub(crate) const EQUALIZER_NAME: &str = "gpui_equalizer";
+pub(crate) const KARAOKE_NAME: &str = "gpui_karaoke";
+pub(crate) const PITCH_NAME: &str = "gpui_pitch";
+pub(crate) const BALANCE_NAME: &str = "gpui_balance";
+
+/// Band layout of the equalizer inserted by [`AudioFilters::equalizer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Equalizer {
+    /// `equalizer-3bands`: 100 Hz, 1.1 kHz, and 11 kHz.
+    ThreeBands,
+    /// `equalizer-10bands`: octaves from 29 Hz to 15 kHz.
+    TenBands,
+}
+
+impl Equalizer {
+    fn factory(self) -> &'static str {
+        match self {
+            Self::ThreeBands => "equalizer-3bands",
+            Self::TenBands => "equalizer-10bands",
+        }
+    }
+
+    pub fn bands(self) -> usize {
+        match self {
+            Self::ThreeBands => 3,
+            Self::TenBands => 10,
+        }
+    }
+}
+
+/// Audio effects built into the audio branch when the video is created, and
+/// adjusted afterwards through [`Video`](crate::Video) methods.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioFilters {
+    /// Equalizer controlled with [`Video::set_eq_band`](crate::Video::set_eq_band).
+    /// All bands start flat.
+    pub equalizer: Option<Equalizer>,
+    /// Insert `audiokaraoke`, which removes centered vocals. It starts disabled; set
+    /// its strength with [`Video::set_karaoke_level`](crate::Video::set_karaoke_level).
+    pub karaoke: bool,
+    /// Insert `pitch` from the soundtouch plugin, so
+    /// [`Video::set_pitch`](crate::Video::set_pitch) can shift pitch without
+    /// changing tempo.
+    pub pitch: bool,
+}
+
+/// Picture adjustments applied by a `videobalance` element, set with
+/// [`VideoOptions::color_balance`] and
+/// [`Video::set_color_balance`](crate::Video::set_color_balance).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorBalance {
+    /// From -1.0 to 1.0; 0.0 leaves the picture unchanged.
+    pub brightness: f64,
+    /// From 0.0 to 2.0; 1.0 leaves the picture unchanged.
+    pub contrast: f64,
+    /// From 0.0 (grayscale) to 2.0; 1.0 leaves the picture unchanged.
+    pub saturation: f64,
+    /// Rotation of the color wheel from -1.0 to 1.0 (a half turn either way);
+    /// 0.0 leaves the picture unchanged.
+    pub hue: f64,
+}
+
+impl Default for ColorBalance {
+    fn default() -> Self {
+        Self {
+            brightness: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            hue: 0.0,
+        }
+    }
+}
+
+impl ColorBalance {
+    /// These settings limited to the ranges `videobalance` accepts.
+    pub(crate) fn clamped(self) -> Self {
+        Self {
+            brightness: self.brightness.clamp(-1.0, 1.0),
+            contrast: self.contrast.clamp(0.0, 2.0),
+            saturation: self.saturation.clamp(0.0, 2.0),
+            hue: self.hue.clamp(-1.0, 1.0),
+        }
+    }
+
+    pub(crate) fn apply(self, element: &gst::Element) {
+        let balance = self.clamped();
+        element.set_property("brightness", balance.brightness);
+        element.set_property("contrast", balance.contrast);
+        element.set_property("saturation", balance.saturation);
+        element.set_property("hue", balance.hue);
+    }
+
+    pub(crate) fn read(element: &gst::Element) -> Self {
+        Self {
+            brightness: element.property("brightness"),
+            contrast: element.property("contrast"),
+            saturation: element.property("saturation"),
+            hue: element.property("hue"),
+        }
+    }
+}
+
+/// The `videobalance` element for the requested picture adjustments, if any.
+/// It passes frames through untouched while its settings are neutral.
+pub(crate) fn video_filters(options: &VideoOptions) -> Result<Vec<gst::Element>, Error> {
+    let Some(balance) = options.color_balance else {
+        return Ok(Vec::new());
+    };
+    let element = gst::ElementFactory::make("videobalance")
+        .name(BALANCE_NAME)
+        .build()?;
+    balance.apply(&element);
+    Ok(vec![element])
+}
+
+/// The elements for the requested audio effects and analyzers, in order.
+pub(crate) fn audio_filters(options: &VideoOptions) -> Result<Vec<gst::Element>, Error> {
+    let mut filters = Vec::new();
+    if let Some(effects) = &options.audio_filters {
+        if let Some(equalizer) = effects.equalizer {
+            filters.push(
+                gst::ElementFactory::make(equalizer.factory())
+                    .name(EQUALIZER_NAME)
+                    .build()?,
+            );
+        }
+        if effects.pitch {
+            filters.push(
+                gst::ElementFactory::make("pitch")
+                    .name(PITCH_NAME)
+                    .build()?,
+            );
+        }
+        if effects.karaoke {
+            filters.push(
+                gst::ElementFactory::make("audiokaraoke")
+                    .name(KARAOKE_NAME)
+                    .property("level", 0.0_f32)
+                    .build()?,
+            );
+        }
+        // The effects only accept float samples.
+        if !filters.is_empty() {
+            filters.insert(0, gst::ElementFactory::make("audioconvert").build()?);
+            filters.push(gst::ElementFactory::make("audioconvert").build()?);
+        }
+    }
+    // Analyzers go last so they measure what is actually heard.
+    if let Some(bands) = options.spectrum_bands {
+        filters.push(make_spectrum_element(bands)?);
+    }
+    if options.audio_levels.unwrap_or_default() {
+        filters.push(make_level_element()?);
+    }
+    if options.audio_window.is_some() {
+        filters.extend(make_audio_tap()?);
+    }
+    Ok(filters)
+}
+
+/// Chain `filters` into one element for playbin's `audio-filter` or `video-filter`.
+/// playbin takes a single element, so several are linked inside a bin.
+pub(crate) fn chain(mut filters: Vec<gst::Element>) -> Result<Option<gst::Element>, Error> {
+    if filters.len() <= 1 {
+        return Ok(filters.pop());
+    }
+
+    let bin = gst::Bin::new();
+    bin.add_many(&filters)?;
+    gst::Element::link_many(&filters)?;
+    let (Some(first), Some(last)) = (filters.first(), filters.last()) else {
+        return Ok(None);
+    };
+    let sink = first.static_pad("sink").ok_or(Error::Caps)?;
+    let src = last.static_pad("src").ok_or(Error::Caps)?;
+    bin.add_pad(&gst::GhostPad::with_target(&sink)?)?;
+    bin.add_pad(&gst::GhostPad::with_target(&src)?)?;
+    Ok(Some(bin.upcast()))
+}
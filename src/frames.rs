@@ -0,0 +1,396 @@
+use crate::convert::{FrameLayout, PixelFormat};
+use crate::error::{Error, FrameError};
+use crate::export::link_decoded_pads;
+use crate::runtime;
+use crate::thread;
+use crate::thumbnail::sample_to_rgba;
+use crate::video::{Frame, Video};
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+/// How long to wait for the extraction pipeline to preroll or finish a seek.
+const PREROLL_TIMEOUT: gst::ClockTime = gst::ClockTime::from_seconds(5);
+
+/// A decoded frame borrowed where GStreamer mapped it, from
+/// [`Video::with_current_frame`] or [`Video::with_buffered_frame`].
+///
+/// Unlike [`Video::current_frame_data`], nothing is copied, and the planes keep
+/// their row padding: use each plane's stride rather than the frame width.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameRef<'a> {
+    data: &'a [u8],
+    layout: FrameLayout,
+    width: u32,
+    height: u32,
+    pts: Option<Duration>,
+}
+
+/// One plane of a [`FrameRef`]: luma or chroma for YUV formats, or all the pixels
+/// of a packed format.
+#[derive(Debug, Clone, Copy)]
+pub struct Plane<'a> {
+    /// The plane's rows, `stride` bytes apart.
+    pub data: &'a [u8],
+    /// Bytes per row, including padding.
+    pub stride: usize,
+    pub rows: usize,
+}
+
+impl<'a> FrameRef<'a> {
+    pub(crate) fn new(data: &'a [u8], layout: FrameLayout, width: u32, height: u32) -> Self {
+        Self {
+            data,
+            layout,
+            width,
+            height,
+            pts: None,
+        }
+    }
+
+    pub(crate) fn with_pts(mut self, pts: Option<gst::ClockTime>) -> Self {
+        self.pts = pts.map(|pts| Duration::from_nanos(pts.nseconds()));
+        self
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The sink's pixel format, or None for a format this crate cannot describe;
+    /// [`data`](Self::data) is still available then.
+    pub fn format(&self) -> Option<PixelFormat> {
+        self.layout.format
+    }
+
+    /// Presentation timestamp of the frame.
+    pub fn pts(&self) -> Option<Duration> {
+        self.pts
+    }
+
+    /// The whole mapped buffer, in the layout [`Video::current_frame_data`] returns.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Number of planes: 2 for NV12 and P010, 3 for I420, 1 for RGBA and BGRA.
+    pub fn plane_count(&self) -> usize {
+        match self.layout.format {
+            Some(PixelFormat::Nv12 | PixelFormat::P010) => 2,
+            Some(PixelFormat::I420) => 3,
+            Some(PixelFormat::Rgba | PixelFormat::Bgra) => 1,
+            None => 0,
+        }
+    }
+
+    /// The plane at `index`, or None past [`plane_count`](Self::plane_count) or if
+    /// the buffer is shorter than its layout.
+    pub fn plane(&self, index: usize) -> Option<Plane<'a>> {
+        if index >= self.plane_count() {
+            return None;
+        }
+        // Chroma planes of the 4:2:0 formats have one row per two luma rows.
+        let rows = if index > 0 {
+            self.height.div_ceil(2)
+        } else {
+            self.height
+        } as usize;
+        let stride = self.layout.stride[index];
+        let start = self.layout.offset[index];
+        let data = self.data.get(start..start + stride * rows)?;
+        Some(Plane { data, stride, rows })
+    }
+}
+
+/// A decoded frame taken from a [`Video`] by [`Video::pop_buffered_frame`],
+/// [`Video::wait_for_frame`], or [`FrameIter`].
+///
+/// The frame keeps GStreamer's buffer mapped instead of copying it, so it is cheap
+/// to take; drop it when done, since decoders with a fixed buffer pool wait for
+/// their buffers to come back. Like [`FrameRef`], planes keep their row padding.
+pub struct VideoFrame {
+    buffer: gst::MappedBuffer<gst::buffer::Readable>,
+    layout: FrameLayout,
+    width: u32,
+    height: u32,
+}
+
+impl VideoFrame {
+    /// Map `frame` for reading. `Ok(None)` means it carries no frame data.
+    pub(crate) fn new(
+        frame: &Frame,
+        layout: FrameLayout,
+        width: u32,
+        height: u32,
+    ) -> Result<Option<Self>, FrameError> {
+        let Some(buffer) = frame.sample.buffer_owned() else {
+            return Ok(None);
+        };
+        let buffer = buffer
+            .into_mapped_buffer_readable()
+            .map_err(|_| FrameError::Map)?;
+        if buffer.as_slice().is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            buffer,
+            layout,
+            width,
+            height,
+        }))
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The sink's pixel format, or None for a format this crate cannot describe.
+    pub fn format(&self) -> Option<PixelFormat> {
+        self.layout.format
+    }
+
+    /// Presentation timestamp of the frame.
+    pub fn pts(&self) -> Option<Duration> {
+        self.buffer
+            .buffer()
+            .pts()
+            .map(|pts| Duration::from_nanos(pts.nseconds()))
+    }
+
+    /// How long the frame is shown at normal speed.
+    pub fn duration(&self) -> Option<Duration> {
+        self.buffer
+            .buffer()
+            .duration()
+            .map(|duration| Duration::from_nanos(duration.nseconds()))
+    }
+
+    /// Bytes per row of `plane`, or None past [`plane_count`](Self::plane_count).
+    pub fn stride(&self, plane: usize) -> Option<usize> {
+        (plane < self.plane_count()).then(|| self.layout.stride[plane])
+    }
+
+    /// Number of planes: 2 for NV12 and P010, 3 for I420, 1 for RGBA and BGRA.
+    pub fn plane_count(&self) -> usize {
+        self.as_frame_ref().plane_count()
+    }
+
+    /// The plane at `index`; see [`FrameRef::plane`].
+    pub fn plane(&self, index: usize) -> Option<Plane<'_>> {
+        self.as_frame_ref().plane(index)
+    }
+
+    /// The whole mapped buffer.
+    pub fn data(&self) -> &[u8] {
+        self.buffer.as_slice()
+    }
+
+    /// Copy the whole buffer out, e.g. to keep it past the frame.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.data().to_vec()
+    }
+
+    /// Borrow the frame as a [`FrameRef`], as [`Video::with_current_frame`] lends it.
+    pub fn as_frame_ref(&self) -> FrameRef<'_> {
+        FrameRef::new(self.data(), self.layout, self.width, self.height)
+            .with_pts(self.buffer.buffer().pts())
+    }
+}
+
+impl std::fmt::Debug for VideoFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VideoFrame")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("format", &self.layout.format)
+            .field("pts", &self.pts())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Iterator over every decoded frame of a [`Video`], created by
+/// [`Video::frames_blocking`].
+///
+/// Yields each [`VideoFrame`] and ends at end of stream. While it exists the video sink does not drop
+/// frames: decoding waits for the consumer, so each frame is seen exactly once.
+pub struct FrameIter {
+    video: Video,
+    sink: gst_app::AppSink,
+    restore_drop: bool,
+    done: bool,
+}
+
+impl FrameIter {
+    pub(crate) fn new(video: Video) -> Self {
+        let sink = video.read().video_sink.clone();
+        let restore_drop = sink.property::<bool>("drop");
+        sink.set_property("drop", false);
+        // The worker would otherwise race this iterator for samples.
+        video.read().external_consumer.store(true, Ordering::SeqCst);
+        Self {
+            video,
+            sink,
+            restore_drop,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for FrameIter {
+    type Item = Result<VideoFrame, FrameError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        thread::debug_assert_may_block("frames_blocking");
+        loop {
+            // pull_sample blocks for the next frame and fails at EOS or on error.
+            let Ok(sample) = self.sink.pull_sample() else {
+                self.done = true;
+                return None;
+            };
+            match self.video.video_frame(&Frame::new(sample)) {
+                Ok(Some(frame)) => return Some(Ok(frame)),
+                // A sample without data carries no frame; wait for the next one.
+                Ok(None) => continue,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+impl Drop for FrameIter {
+    fn drop(&mut self) {
+        self.sink.set_property("drop", self.restore_drop);
+        self.video
+            .read()
+            .external_consumer
+            .store(false, Ordering::SeqCst);
+    }
+}
+
+/// A decoded frame from a [`FrameExtractor`].
+#[derive(Debug, Clone)]
+pub struct ExtractedFrame {
+    /// Position of the frame in the media.
+    pub timestamp: Duration,
+    pub image: image::RgbaImage,
+}
+
+/// Decodes the frames of a media file one at a time, as fast or as slow as the
+/// caller asks for them, e.g. to feed a computer vision model.
+///
+/// Runs its own pipeline, so unlike [`FrameIter`] it needs no playing [`Video`]
+/// and never affects playback. Decoding pauses between calls to `next`; each call
+/// blocks until the next frame is decoded, so iterate on a background thread.
+pub struct FrameExtractor {
+    uri: url::Url,
+    pipeline: gst::Pipeline,
+    sink: gst_app::AppSink,
+    done: bool,
+}
+
+impl FrameExtractor {
+    /// Open `uri` for extraction, starting at its first frame.
+    pub fn new(uri: &url::Url) -> Result<Self, Error> {
+        runtime::ensure_init()?;
+
+        let pipeline = gst::Pipeline::new();
+        let source = gst::ElementFactory::make("uridecodebin")
+            .property("uri", uri.as_str())
+            .build()?;
+        let convert = gst::ElementFactory::make("videoconvert").build()?;
+        let caps = gst::Caps::builder("video/x-raw")
+            .field("format", "RGBA")
+            .build();
+        // One queued frame without dropping: the decoder waits for the consumer.
+        let sink = gst_app::AppSink::builder()
+            .caps(&caps)
+            .sync(false)
+            .max_buffers(1)
+            .drop(false)
+            .build();
+
+        pipeline.add_many([&source, &convert, sink.upcast_ref()])?;
+        convert.link(&sink)?;
+        link_decoded_pads(&source, &convert, "video/");
+
+        let extractor = Self {
+            uri: uri.clone(),
+            pipeline,
+            sink,
+            done: false,
+        };
+        extractor.pipeline.set_state(gst::State::Playing)?;
+        extractor.pipeline.state(PREROLL_TIMEOUT).0?;
+        Ok(extractor)
+    }
+
+    /// Continue from the first frame at or after `position`.
+    pub fn seek(&mut self, position: Duration) -> Result<(), Error> {
+        self.pipeline.seek_simple(
+            gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+            gst::ClockTime::from_nseconds(position.as_nanos() as u64),
+        )?;
+        self.pipeline.state(PREROLL_TIMEOUT).0?;
+        self.done = false;
+        Ok(())
+    }
+
+    /// The pipeline error that ended extraction early, if any.
+    fn take_error(&self) -> Option<Error> {
+        let msg = self
+            .pipeline
+            .bus()?
+            .pop_filtered(&[gst::MessageType::Error])?;
+        match msg.view() {
+            gst::MessageView::Error(err) => Some(Error::from_bus(err, Some(self.uri.as_str()))),
+            _ => None,
+        }
+    }
+}
+
+impl Iterator for FrameExtractor {
+    type Item = Result<ExtractedFrame, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        // pull_sample blocks for the next frame and fails at EOS or on error.
+        let Ok(sample) = self.sink.pull_sample() else {
+            self.done = true;
+            return self.take_error().map(Err);
+        };
+        let timestamp = sample
+            .buffer()
+            .and_then(|buffer| buffer.pts())
+            .and_then(|pts| {
+                sample
+                    .segment()?
+                    .downcast_ref::<gst::ClockTime>()?
+                    .to_stream_time(pts)
+            })
+            .map_or(Duration::ZERO, |time| Duration::from_nanos(time.nseconds()));
+        Some(sample_to_rgba(&sample).map(|image| ExtractedFrame { timestamp, image }))
+    }
+}
+
+impl Drop for FrameExtractor {
+    fn drop(&mut self) {
+        if let Err(err) = self.pipeline.set_state(gst::State::Null) {
+            log::error!("failed to shut down frame extraction pipeline: {}", err);
+        }
+    }
+}
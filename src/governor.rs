@@ -0,0 +1,267 @@
+//! A crate-wide limit on how many videos decode at once.
+
+use crate::event::VideoEvent;
+use crate::video::{Internal, Video};
+use parking_lot::{Mutex, RwLock};
+use std::sync::{Arc, Weak};
+
+/// Caps how many videos play at the same time, e.g. in a grid of previews where
+/// every additional decoder slows all the others down.
+///
+/// Videos asked to play beyond the limit stay paused on their current frame or
+/// poster and are queued; the one with the highest [`DecodePriority`] starts when
+/// another pauses or is dropped. A video with a higher priority than one that is
+/// playing takes its place instead of waiting. Install one with
+/// [`set_decode_governor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DecodeGovernor {
+    /// Most videos playing at once.
+    pub max_playing: usize,
+}
+
+impl DecodeGovernor {
+    pub fn new(max_playing: usize) -> Self {
+        Self {
+            max_playing: max_playing.max(1),
+        }
+    }
+}
+
+/// How a [`DecodeGovernor`] ranks a video, set with
+/// [`Video::set_decode_priority`](crate::Video::set_decode_priority).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(u8)]
+pub enum DecodePriority {
+    /// Background previews, first to be paused for others.
+    Low = 0,
+    #[default]
+    Normal = 1,
+    /// The video the user is focused on; never waits for lower priorities.
+    High = 2,
+}
+
+impl DecodePriority {
+    pub(crate) fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Low,
+            2 => Self::High,
+            _ => Self::Normal,
+        }
+    }
+}
+
+/// A video known to the governor. Only weak references are held, and they are
+/// only upgraded for videos about to be paused or started: a video upgraded and
+/// then dropped under the state lock could run its destructor there, which
+/// releases its slot and would deadlock.
+struct Entry {
+    video: Weak<RwLock<Internal>>,
+    priority: DecodePriority,
+}
+
+impl Entry {
+    fn new(video: &Video) -> Self {
+        Self {
+            video: Arc::downgrade(&video.0),
+            priority: video.decode_priority(),
+        }
+    }
+
+    fn is(&self, video: &Video) -> bool {
+        std::ptr::eq(self.video.as_ptr(), Arc::as_ptr(&video.0))
+    }
+}
+
+struct State {
+    governor: Option<DecodeGovernor>,
+    /// Videos holding a slot, oldest first.
+    playing: Vec<Entry>,
+    /// Videos waiting for a slot, oldest first.
+    queued: Vec<Entry>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    governor: None,
+    playing: Vec::new(),
+    queued: Vec::new(),
+});
+
+/// Pauses and resumes decided under the state lock, carried out after it is
+/// released, since they take the videos' own locks and run event callbacks.
+#[derive(Default)]
+struct Changes {
+    pause: Vec<Video>,
+    start: Vec<Video>,
+}
+
+impl Changes {
+    fn apply(self) {
+        for video in self.pause {
+            if let Err(err) = video.write().set_paused_by_governor(true) {
+                log::warn!("failed to pause video for a queued one: {err}");
+            }
+            emit(&video, VideoEvent::DecodeQueued);
+        }
+        for video in self.start {
+            if let Err(err) = video.write().set_paused_by_governor(false) {
+                log::warn!("failed to start queued video: {err}");
+            }
+            emit(&video, VideoEvent::DecodeStarted);
+        }
+    }
+}
+
+fn emit(video: &Video, event: VideoEvent) {
+    let events = Arc::clone(&video.read().events);
+    events.emit(event);
+}
+
+impl State {
+    fn prune(&mut self) {
+        self.playing.retain(|entry| entry.video.strong_count() > 0);
+        self.queued.retain(|entry| entry.video.strong_count() > 0);
+    }
+
+    /// Move queued videos into free slots, highest priority first.
+    fn promote(&mut self, changes: &mut Changes) {
+        let max_playing = self
+            .governor
+            .map_or(usize::MAX, |governor| governor.max_playing);
+        while self.playing.len() < max_playing {
+            // Oldest first among equals: max_by_key keeps the last maximum.
+            let next = self
+                .queued
+                .iter()
+                .enumerate()
+                .rev()
+                .max_by_key(|(_, entry)| entry.priority)
+                .map(|(index, _)| index);
+            let Some(index) = next else {
+                break;
+            };
+            let entry = self.queued.remove(index);
+            if let Some(video) = entry.video.upgrade() {
+                changes.start.push(Video(video));
+                self.playing.push(entry);
+            }
+        }
+    }
+}
+
+/// Install a crate-wide [`DecodeGovernor`], or remove it with `None`. Videos over
+/// a lowered limit keep playing until they pause; a raised or removed limit starts
+/// queued videos right away.
+pub fn set_decode_governor(governor: Option<DecodeGovernor>) {
+    let mut changes = Changes::default();
+    {
+        let mut state = STATE.lock();
+        state.governor = governor;
+        state.prune();
+        state.promote(&mut changes);
+    }
+    changes.apply();
+}
+
+/// The [`DecodeGovernor`] installed with [`set_decode_governor`], if any.
+pub fn decode_governor() -> Option<DecodeGovernor> {
+    STATE.lock().governor
+}
+
+/// Ask for a slot for `video`, which is about to play. Returns false if it was
+/// queued instead and must stay paused.
+pub(crate) fn request_play(video: &Video) -> bool {
+    let entry = Entry::new(video);
+    let mut changes = Changes::default();
+    let admitted = {
+        let mut state = STATE.lock();
+        state.prune();
+        if state.playing.iter().any(|entry| entry.is(video)) {
+            true
+        } else {
+            state.queued.retain(|entry| !entry.is(video));
+            match state.governor {
+                Some(governor) if state.playing.len() >= governor.max_playing => {
+                    // Take the slot of the newest video with the lowest priority.
+                    let victim = state
+                        .playing
+                        .iter()
+                        .enumerate()
+                        .rev()
+                        .min_by_key(|(_, playing)| playing.priority)
+                        .filter(|(_, playing)| playing.priority < entry.priority)
+                        .map(|(index, _)| index);
+                    match victim {
+                        Some(index) => {
+                            let preempted = state.playing.remove(index);
+                            if let Some(playing) = preempted.video.upgrade() {
+                                changes.pause.push(Video(playing));
+                            }
+                            // It played before anything still waiting.
+                            state.queued.insert(0, preempted);
+                            state.playing.push(entry);
+                            true
+                        }
+                        None => {
+                            state.queued.push(entry);
+                            false
+                        }
+                    }
+                }
+                _ => {
+                    state.playing.push(entry);
+                    true
+                }
+            }
+        }
+    };
+    changes.apply();
+    if !admitted {
+        emit(video, VideoEvent::DecodeQueued);
+    }
+    admitted
+}
+
+/// Give up `video`'s slot or place in the queue, starting the next queued video.
+pub(crate) fn release(video: &Video) {
+    let mut changes = Changes::default();
+    {
+        let mut state = STATE.lock();
+        state.playing.retain(|entry| !entry.is(video));
+        state.queued.retain(|entry| !entry.is(video));
+        state.prune();
+        state.promote(&mut changes);
+    }
+    changes.apply();
+}
+
+/// Record a new priority for `video`, which may let it take a slot.
+pub(crate) fn priority_changed(video: &Video) {
+    let priority = video.decode_priority();
+    let queued = {
+        let mut state = STATE.lock();
+        for entry in state.playing.iter_mut().chain(state.queued.iter_mut()) {
+            if entry.is(video) {
+                entry.priority = priority;
+            }
+        }
+        state.queued.iter().any(|entry| entry.is(video))
+    };
+    if queued && request_play(video) {
+        // request_play paused whichever video it displaced; start this one.
+        if let Err(err) = video.write().set_paused_by_governor(false) {
+            log::warn!("failed to start queued video: {err}");
+        }
+        emit(video, VideoEvent::DecodeStarted);
+    }
+}
+
+/// Start queued videos in the slot of a dropped one.
+pub(crate) fn video_dropped() {
+    let mut changes = Changes::default();
+    {
+        let mut state = STATE.lock();
+        state.prune();
+        state.promote(&mut changes);
+    }
+    changes.apply();
+}
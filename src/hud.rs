@@ -0,0 +1,237 @@
+use crate::stats::{PlaybackStats, STATS_HISTORY, VideoStats};
+use crate::theme::PlayerTheme;
+use gpui::{Bounds, Hsla, Pixels, Window};
+use std::time::Duration;
+
+const PANEL_WIDTH: f32 = 300.0;
+const STATS_PANEL_WIDTH: f32 = 180.0;
+const GRAPH_HEIGHT: f32 = 32.0;
+const DROP_STRIP_HEIGHT: f32 = 6.0;
+const PADDING: f32 = 6.0;
+
+/// Budget assumed when the stream reports no framerate and nothing was measured yet.
+const FALLBACK_FRAME_BUDGET: Duration = Duration::from_micros(16_667);
+
+/// Paint the frame pacing HUD in the top-left corner of `bounds`: a summary line,
+/// frame intervals, conversion times, and a strip marking dropped frames.
+///
+/// Graphs are scaled so the frame budget sits at half height; bars over 1.5x the
+/// budget are what viewers perceive as stutter and are drawn in red.
+pub(crate) fn paint_debug_overlay(
+    window: &mut Window,
+    cx: &mut gpui::App,
+    bounds: Bounds<Pixels>,
+    stats: &PlaybackStats,
+    framerate: f64,
+    theme: &PlayerTheme,
+) {
+    let budget = if framerate > 0.0 {
+        Duration::from_secs_f64(1.0 / framerate)
+    } else {
+        stats.mean_frame_interval().unwrap_or(FALLBACK_FRAME_BUDGET)
+    };
+    let font_size = theme.font_size * 0.85;
+    let line_height = font_size * 1.4;
+    let padding = gpui::px(PADDING);
+    let width = gpui::px(PANEL_WIDTH).min(bounds.size.width - padding * 2.0);
+    if width <= gpui::px(0.0) {
+        return;
+    }
+    let height =
+        padding * 5.0 + line_height + gpui::px(GRAPH_HEIGHT) * 2.0 + gpui::px(DROP_STRIP_HEIGHT);
+    let panel = Bounds::new(
+        bounds.origin + gpui::point(padding, padding),
+        gpui::size(width + padding * 2.0, height),
+    );
+    window.paint_quad(gpui::fill(panel, theme.overlay).corner_radii(theme.radius));
+
+    let mut y = panel.origin.y + padding;
+    let x = panel.origin.x + padding;
+
+    let fps = stats.fps();
+    let conversion = stats
+        .conversion_times
+        .back()
+        .map_or(0.0, |time| time.as_secs_f64() * 1000.0);
+    let latency = stats
+        .paint_latencies
+        .back()
+        .map_or(0.0, |time| time.as_secs_f64() * 1000.0);
+    let label = format!(
+        "{fps:.1} fps  convert {conversion:.1} ms  latency {latency:.1} ms  dropped {}",
+        stats.dropped_total
+    );
+    paint_label(
+        window,
+        cx,
+        label,
+        gpui::point(x, y),
+        font_size,
+        line_height,
+        theme,
+    );
+    y += line_height + padding;
+
+    let graph_size = gpui::size(width, gpui::px(GRAPH_HEIGHT));
+    paint_graph(
+        window,
+        Bounds::new(gpui::point(x, y), graph_size),
+        stats.frame_intervals.iter().copied(),
+        budget,
+        theme,
+    );
+    y += gpui::px(GRAPH_HEIGHT) + padding;
+    paint_graph(
+        window,
+        Bounds::new(gpui::point(x, y), graph_size),
+        stats.conversion_times.iter().copied(),
+        budget,
+        theme,
+    );
+    y += gpui::px(GRAPH_HEIGHT) + padding;
+
+    let strip = Bounds::new(
+        gpui::point(x, y),
+        gpui::size(width, gpui::px(DROP_STRIP_HEIGHT)),
+    );
+    window.paint_quad(gpui::fill(strip, theme.track));
+    let bar_width = width / STATS_HISTORY as f32;
+    let offset = STATS_HISTORY.saturating_sub(stats.dropped_frames.len());
+    for (index, dropped) in stats.dropped_frames.iter().enumerate() {
+        if *dropped == 0 {
+            continue;
+        }
+        let mark = Bounds::new(
+            gpui::point(x + bar_width * (offset + index) as f32, y),
+            gpui::size(bar_width, strip.size.height),
+        );
+        window.paint_quad(gpui::fill(mark, stutter_color()));
+    }
+}
+
+/// Paint the numbers from [`Video::stats`](crate::Video::stats) as a text panel in
+/// the top-right corner of `bounds`, clear of the pacing HUD.
+pub(crate) fn paint_stats_overlay(
+    window: &mut Window,
+    cx: &mut gpui::App,
+    bounds: Bounds<Pixels>,
+    stats: &VideoStats,
+    theme: &PlayerTheme,
+) {
+    let milliseconds = |time: Option<Duration>| {
+        time.map_or("-".to_string(), |time| {
+            format!("{:.1} ms", time.as_secs_f64() * 1000.0)
+        })
+    };
+    let lines = [
+        format!("decoded {}", stats.decoded_frames),
+        format!("dropped {}", stats.dropped_frames),
+        format!("decode {}", milliseconds(stats.mean_decode_time)),
+        format!("{:.1} fps", stats.fps),
+        format!("queue {}", stats.queue_depth),
+        stats.bitrate.map_or("bitrate -".to_string(), |bitrate| {
+            format!("bitrate {} kb/s", bitrate / 1000)
+        }),
+    ];
+
+    let font_size = theme.font_size * 0.85;
+    let line_height = font_size * 1.4;
+    let padding = gpui::px(PADDING);
+    let width = gpui::px(STATS_PANEL_WIDTH).min(bounds.size.width - padding * 2.0);
+    if width <= gpui::px(0.0) {
+        return;
+    }
+    let height = padding * 2.0 + line_height * lines.len() as f32;
+    let panel = Bounds::new(
+        gpui::point(
+            bounds.right() - width - padding * 3.0,
+            bounds.origin.y + padding,
+        ),
+        gpui::size(width + padding * 2.0, height),
+    );
+    window.paint_quad(gpui::fill(panel, theme.overlay).corner_radii(theme.radius));
+
+    let x = panel.origin.x + padding;
+    let mut y = panel.origin.y + padding;
+    for line in lines {
+        paint_label(
+            window,
+            cx,
+            line,
+            gpui::point(x, y),
+            font_size,
+            line_height,
+            theme,
+        );
+        y += line_height;
+    }
+}
+
+fn paint_label(
+    window: &mut Window,
+    cx: &mut gpui::App,
+    text: String,
+    origin: gpui::Point<Pixels>,
+    font_size: Pixels,
+    line_height: Pixels,
+    theme: &PlayerTheme,
+) {
+    let run = gpui::TextRun {
+        len: text.len(),
+        font: window.text_style().font(),
+        color: theme.foreground,
+        background_color: None,
+        underline: None,
+        strikethrough: None,
+    };
+    let line = window
+        .text_system()
+        .shape_line(text.into(), font_size, &[run], None);
+    if let Err(err) = line.paint(origin, line_height, window, cx) {
+        log::debug!("failed to paint overlay label: {err}");
+    }
+}
+
+/// Bar graph of `values`, newest on the right, with a line at the frame budget.
+fn paint_graph(
+    window: &mut Window,
+    area: Bounds<Pixels>,
+    values: impl ExactSizeIterator<Item = Duration>,
+    budget: Duration,
+    theme: &PlayerTheme,
+) {
+    window.paint_quad(gpui::fill(area, theme.track));
+    let bar_width = area.size.width / STATS_HISTORY as f32;
+    let offset = STATS_HISTORY.saturating_sub(values.len());
+    let scale = budget.as_secs_f32() * 2.0;
+    for (index, value) in values.enumerate() {
+        let ratio = if scale > 0.0 {
+            (value.as_secs_f32() / scale).min(1.0)
+        } else {
+            1.0
+        };
+        let height = area.size.height * ratio;
+        let bar = Bounds::new(
+            gpui::point(
+                area.origin.x + bar_width * (offset + index) as f32,
+                area.bottom() - height,
+            ),
+            gpui::size(bar_width, height),
+        );
+        let color = if value > budget.mul_f32(1.5) {
+            stutter_color()
+        } else {
+            theme.accent
+        };
+        window.paint_quad(gpui::fill(bar, color));
+    }
+    let budget_line = Bounds::new(
+        gpui::point(area.origin.x, area.origin.y + area.size.height * 0.5),
+        gpui::size(area.size.width, gpui::px(1.0)),
+    );
+    window.paint_quad(gpui::fill(budget_line, theme.foreground.opacity(0.5)));
+}
+
+fn stutter_color() -> Hsla {
+    gpui::red()
+}
@@ -0,0 +1,107 @@
+use gstreamer as gst;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Interval between level messages posted on the bus.
+pub(crate) const LEVEL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Build the `level` element inserted into playbin's audio filter chain.
+pub(crate) fn make_level_element() -> Result<gst::Element, glib::BoolError> {
+    gst::ElementFactory::make("level")
+        .name("gpui_level")
+        .property("interval", LEVEL_INTERVAL.as_nanos() as u64)
+        .property("post-messages", true)
+        .build()
+}
+
+/// Loudness of one audio channel over the latest analysis interval.
+///
+/// Values are in dBFS: 0.0 is full scale and silence approaches negative infinity.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChannelLevel {
+    pub rms: f64,
+    pub peak: f64,
+}
+
+impl ChannelLevel {
+    /// Peak as a linear amplitude in `0.0..=1.0`, for drawing meters.
+    pub fn peak_linear(&self) -> f64 {
+        db_to_linear(self.peak)
+    }
+
+    /// RMS as a linear amplitude in `0.0..=1.0`, for drawing meters.
+    pub fn rms_linear(&self) -> f64 {
+        db_to_linear(self.rms)
+    }
+}
+
+fn db_to_linear(db: f64) -> f64 {
+    10f64.powf(db / 20.0).clamp(0.0, 1.0)
+}
+
+/// Like spectrum messages, level messages arrive ahead of what the sink plays, so
+/// they are queued by stream time and released as playback reaches them.
+#[derive(Debug, Default)]
+pub(crate) struct LevelState {
+    pending: VecDeque<(Duration, Vec<ChannelLevel>)>,
+    current: Option<Vec<ChannelLevel>>,
+}
+
+impl LevelState {
+    /// Parse a `level` element message and queue it. Returns false for unrelated messages.
+    pub(crate) fn push_message(&mut self, s: &gst::StructureRef) -> bool {
+        if !s.has_name("level") {
+            return false;
+        }
+        let Ok(stream_time) = s.get::<u64>("stream-time") else {
+            return true;
+        };
+        let channels = |field: &str| -> Vec<f64> {
+            s.get::<glib::ValueArray>(field)
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|value| value.get::<f64>().ok())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let levels = channels("rms")
+            .into_iter()
+            .zip(channels("peak"))
+            .map(|(rms, peak)| ChannelLevel { rms, peak })
+            .collect();
+        self.pending
+            .push_back((Duration::from_nanos(stream_time), levels));
+        true
+    }
+
+    /// Release every queued interval whose stream time has been reached. Returns the
+    /// latest one if anything was released.
+    pub(crate) fn advance(&mut self, position: Duration) -> Option<Vec<ChannelLevel>> {
+        let mut released = false;
+        while let Some((at, _)) = self.pending.front()
+            && *at <= position
+        {
+            if let Some((_, levels)) = self.pending.pop_front() {
+                self.current = Some(levels);
+                released = true;
+            }
+        }
+        if released { self.current.clone() } else { None }
+    }
+
+    pub(crate) fn current(&self) -> Option<Vec<ChannelLevel>> {
+        self.current.clone()
+    }
+
+    /// Whether any intervals are waiting for playback to reach them.
+    pub(crate) fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.pending.clear();
+        self.current = None;
+    }
+}
@@ -70,13 +70,104 @@
 //!
 //! See the `examples/` directory for more complete usage patterns.
 
+mod animation;
+mod badge;
+mod cache;
+pub mod capabilities;
+mod capture;
+mod chapter;
+mod compositor;
+mod convert;
+pub mod debug;
+mod decoder;
+#[cfg(all(target_os = "linux", feature = "dmabuf"))]
+mod dmabuf;
 mod element;
+mod encode;
+mod entity;
 mod error;
+mod event;
+mod export;
+mod filter;
+mod frames;
+mod governor;
+mod hud;
+mod level;
+mod locale;
+mod memory;
+mod metadata;
+mod motion;
+mod network;
+mod pipeline;
+mod playbin;
+mod power;
+mod record;
+mod recovery;
+mod repaint;
+mod runtime;
+mod sequence;
+mod silence;
+mod spectrum;
+mod stats;
+mod subtitles;
+mod theme;
+mod thread;
+mod thumbnail;
+mod tile;
+mod time_label;
+mod timeline;
+mod tracks;
 mod video;
+mod watched;
+mod waveform;
 
+pub use cache::{cache_dir, cached_poster, set_cache_dir};
+pub use capabilities::{Diagnosis, diagnose};
+pub use capture::{CaptureMode, CaptureOptions, DeviceId, VideoDevice, list_video_devices};
+pub use chapter::Chapter;
+pub use compositor::{Overlay, VideoCompositor, video_compositor};
+pub use convert::{ColorMatrix, ColorRange, PixelFormat};
+pub use decoder::{DecoderInfo, HwPreference};
 pub use element::{VideoElement, video};
-pub use error::Error;
-pub use video::{Position, Video, VideoOptions};
+#[cfg(any(feature = "inspector", debug_assertions))]
+pub use element::{VideoInspectorState, register_video_inspector};
+pub use encode::{
+    AudioCodec, Container, EncodeProfile, EncoderInfo, EncoderPreference, VideoCodec,
+};
+pub use entity::{VideoEntity, VideoState};
+pub use error::{Error, FrameError};
+pub use event::{EventSubscription, StreamHealth, VideoEvent};
+pub use export::{AudioFormat, ClipOptions, ImageFormat, SubtitleFormat};
+pub use filter::{AudioFilters, ColorBalance, Equalizer};
+pub use frames::{ExtractedFrame, FrameExtractor, FrameIter, FrameRef, Plane, VideoFrame};
+pub use governor::{DecodeGovernor, DecodePriority, decode_governor, set_decode_governor};
+pub use level::ChannelLevel;
+pub use locale::{EnglishLocalizer, Localizer, StatusText, localizer, set_localizer};
+pub use memory::{MemoryBudget, MemoryUsage, memory_budget, set_memory_budget, total_memory_usage};
+pub use metadata::Metadata;
+pub use network::NetworkTuning;
+pub use pipeline::{BuiltPipeline, PipelineBuilder};
+pub use playbin::{Backend, PlayFlags, PlaybinSettings};
+pub use power::{
+    WakePolicy, set_wake_policy, system_did_wake, system_will_sleep, wake_policy, watch_system_wake,
+};
+pub use record::RecordOptions;
+pub use recovery::RetryPolicy;
+pub use runtime::{RuntimeConfig, init, runtime_config};
+pub use sequence::{Clip, Sequence, SequenceElement, sequence};
+pub use spectrum::{SpectrumElement, spectrum};
+pub use stats::{PlaybackStats, VideoStats};
+pub use subtitles::{
+    SubtitleMatch, SubtitleProvider, SubtitleQuery, set_subtitle_provider, subtitle_provider,
+};
+pub use theme::{PlayerTheme, player_theme, set_player_theme};
+pub use thread::WorkerPriority;
+pub use thumbnail::Thumbnailer;
+pub use tile::{VideoTile, video_tile};
+pub use time_label::{TimeDisplay, TimeLabelElement, time_label};
+pub use timeline::{TimelineElement, timeline};
+pub use tracks::Track;
+pub use video::{Position, TimeRange, UriRefresher, Video, VideoOptions, WeakVideo};
 
 // Re-export commonly used types
 pub use gstreamer as gst;
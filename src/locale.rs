@@ -0,0 +1,67 @@
+use gpui::{App, Global, SharedString};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A human-readable status shown by the crate's UI components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StatusText {
+    Loading,
+    Playing,
+    Paused,
+    Ended,
+    /// Network buffering, with the fill level in percent.
+    Buffering(u8),
+    Error,
+}
+
+/// Supplies the strings the crate's widgets display.
+///
+/// Every method has an English default, so implementors only override what they translate.
+/// Register an implementation with [`set_localizer`].
+pub trait Localizer: Send + Sync + 'static {
+    fn status(&self, status: StatusText) -> SharedString {
+        match status {
+            StatusText::Loading => "Loading".into(),
+            StatusText::Playing => "Playing".into(),
+            StatusText::Paused => "Paused".into(),
+            StatusText::Ended => "Ended".into(),
+            StatusText::Buffering(percent) => format!("Buffering {percent}%").into(),
+            StatusText::Error => "Playback error".into(),
+        }
+    }
+
+    /// Format a media time such as a position or duration, e.g. `1:05` or `1:02:03`.
+    fn duration(&self, duration: Duration) -> SharedString {
+        let total = duration.as_secs();
+        let (hours, minutes, seconds) = (total / 3600, (total / 60) % 60, total % 60);
+        if hours > 0 {
+            format!("{hours}:{minutes:02}:{seconds:02}").into()
+        } else {
+            format!("{minutes}:{seconds:02}").into()
+        }
+    }
+}
+
+/// The built-in English strings.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EnglishLocalizer;
+
+impl Localizer for EnglishLocalizer {}
+
+struct GlobalLocalizer(Arc<dyn Localizer>);
+
+impl Global for GlobalLocalizer {}
+
+/// Install the [`Localizer`] used by the crate's widgets in this app.
+pub fn set_localizer(cx: &mut App, localizer: impl Localizer) {
+    cx.set_global(GlobalLocalizer(Arc::new(localizer)));
+}
+
+/// The [`Localizer`] registered with [`set_localizer`], or [`EnglishLocalizer`] if none was.
+pub fn localizer(cx: &App) -> Arc<dyn Localizer> {
+    match cx.try_global::<GlobalLocalizer>() {
+        Some(global) => Arc::clone(&global.0),
+        None => Arc::new(EnglishLocalizer),
+    }
+}
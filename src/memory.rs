@@ -0,0 +1,250 @@
+use crate::video::Frame;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+/// Estimated bytes held by a [`Video`](crate::Video).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Decoded frames queued in the frame buffer.
+    pub frame_buffer: usize,
+    /// The latest decoded frame, unless it is also queued in the frame buffer.
+    pub current_frame: usize,
+    /// The last frame converted for display by a [`VideoElement`](crate::VideoElement),
+    /// and spare conversion buffers kept for reuse.
+    pub converted: usize,
+}
+
+impl MemoryUsage {
+    pub fn total(&self) -> usize {
+        self.frame_buffer + self.current_frame + self.converted
+    }
+}
+
+/// A crate-wide cap on the memory held by all videos.
+///
+/// When exceeded, the oldest buffered frames of the videos with the longest
+/// frame buffers are dropped first. Install one with [`set_memory_budget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryBudget {
+    /// Total bytes allowed across all videos.
+    pub max_bytes: usize,
+    /// Frames each video keeps buffered even when the budget is exceeded.
+    pub min_buffered_frames: usize,
+}
+
+impl MemoryBudget {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            min_buffered_frames: 1,
+        }
+    }
+}
+
+/// How often buffering a frame re-checks the budget. Summing the usage locks every
+/// video's frame buffer, too much to do for each frame of each video.
+const CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Most spare conversion buffers a video keeps.
+const MAX_SPARE_PIXELS: usize = 2;
+
+static BUDGET: Mutex<Option<MemoryBudget>> = Mutex::new(None);
+static TRACKERS: Mutex<Vec<Weak<MemoryTracker>>> = Mutex::new(Vec::new());
+static LAST_CHECK: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Install a crate-wide [`MemoryBudget`], or remove it with `None`. The budget is
+/// enforced immediately, and then at most every 100 ms as videos buffer frames.
+pub fn set_memory_budget(budget: Option<MemoryBudget>) {
+    *BUDGET.lock() = budget;
+    enforce_budget();
+}
+
+/// The [`MemoryBudget`] installed with [`set_memory_budget`], if any.
+pub fn memory_budget() -> Option<MemoryBudget> {
+    *BUDGET.lock()
+}
+
+/// Combined memory usage of all live videos.
+pub fn total_memory_usage() -> MemoryUsage {
+    live_trackers().iter().map(|tracker| tracker.usage()).fold(
+        MemoryUsage::default(),
+        |acc, usage| MemoryUsage {
+            frame_buffer: acc.frame_buffer + usage.frame_buffer,
+            current_frame: acc.current_frame + usage.current_frame,
+            converted: acc.converted + usage.converted,
+        },
+    )
+}
+
+/// Frame storage of one video, shared with the budget registry.
+///
+/// The registry only holds weak references to this rather than to the video
+/// itself, so enforcing the budget from a worker thread can never end up
+/// dropping (and joining) another video's pipeline.
+#[derive(Debug)]
+pub(crate) struct MemoryTracker {
+    frame: Arc<Mutex<Frame>>,
+    frame_buffer: Arc<Mutex<VecDeque<Frame>>>,
+    converted: AtomicUsize,
+    spare: Arc<SparePixels>,
+}
+
+impl MemoryTracker {
+    /// Create a tracker and register it with the crate-wide budget.
+    pub(crate) fn register(
+        frame: Arc<Mutex<Frame>>,
+        frame_buffer: Arc<Mutex<VecDeque<Frame>>>,
+    ) -> Arc<Self> {
+        let tracker = Arc::new(Self {
+            frame,
+            frame_buffer,
+            converted: AtomicUsize::new(0),
+            spare: Arc::default(),
+        });
+        let mut trackers = TRACKERS.lock();
+        trackers.retain(|tracker| tracker.strong_count() > 0);
+        trackers.push(Arc::downgrade(&tracker));
+        tracker
+    }
+
+    pub(crate) fn set_converted(&self, bytes: usize) {
+        self.converted.store(bytes, Ordering::Relaxed);
+    }
+
+    pub(crate) fn spare_pixels(&self) -> Arc<SparePixels> {
+        Arc::clone(&self.spare)
+    }
+
+    pub(crate) fn usage(&self) -> MemoryUsage {
+        let frame = self.frame.lock();
+        let buffer = self.frame_buffer.lock();
+        let current_frame = if buffer.iter().any(|queued| queued.shares_buffer(&frame)) {
+            0
+        } else {
+            frame.size()
+        };
+        MemoryUsage {
+            frame_buffer: buffer.iter().map(Frame::size).sum(),
+            current_frame,
+            converted: self.converted.load(Ordering::Relaxed) + self.spare.bytes(),
+        }
+    }
+}
+
+/// Pixel buffers converted ahead of painting for frames dropped before any view
+/// painted them, e.g. on a seek, which the worker converts the next frames into
+/// instead of allocating. Painted pixels are owned by gpui's `RenderImage` and
+/// never come back.
+#[derive(Debug, Default)]
+pub(crate) struct SparePixels(Mutex<Vec<Vec<u8>>>);
+
+impl SparePixels {
+    pub(crate) fn put(&self, pixels: Vec<u8>) {
+        let mut spare = self.0.lock();
+        if !pixels.is_empty() && spare.len() < MAX_SPARE_PIXELS {
+            spare.push(pixels);
+        }
+    }
+
+    /// A buffer of exactly `len` bytes, reusing a spare one when there is one. Its
+    /// contents are stale; conversion overwrites every byte.
+    pub(crate) fn take(&self, len: usize) -> Vec<u8> {
+        let reused = {
+            let mut spare = self.0.lock();
+            // Buffers of another size are left over from before a resolution change.
+            spare.retain(|pixels| pixels.len() == len);
+            spare.pop()
+        };
+        reused.unwrap_or_else(|| vec![0; len])
+    }
+
+    fn bytes(&self) -> usize {
+        self.0.lock().iter().map(Vec::len).sum()
+    }
+
+    /// Free all spare buffers, returning the bytes they held.
+    fn clear(&self) -> usize {
+        let spare = std::mem::take(&mut *self.0.lock());
+        spare.iter().map(Vec::len).sum()
+    }
+}
+
+fn live_trackers() -> Vec<Arc<MemoryTracker>> {
+    TRACKERS.lock().iter().filter_map(Weak::upgrade).collect()
+}
+
+/// Enforce the budget after a video buffered a frame, unless it was checked less
+/// than [`CHECK_INTERVAL`] ago.
+pub(crate) fn frame_buffered() {
+    if BUDGET.lock().is_none() {
+        return;
+    }
+    {
+        let mut last_check = LAST_CHECK.lock();
+        if last_check.is_some_and(|last| last.elapsed() < CHECK_INTERVAL) {
+            return;
+        }
+        *last_check = Some(Instant::now());
+    }
+    enforce_budget();
+}
+
+/// Trim until all videos fit in the installed budget: first spare conversion
+/// buffers, then the pixels converted ahead of painting for queued frames, which
+/// painting can redo, then the oldest buffered frames.
+fn enforce_budget() {
+    let Some(budget) = memory_budget() else {
+        return;
+    };
+    let trackers = live_trackers();
+    let mut total: usize = trackers.iter().map(|t| t.usage().total()).sum();
+    if total <= budget.max_bytes {
+        return;
+    }
+    log::debug!(
+        "memory budget exceeded ({total} > {} bytes); trimming frame buffers",
+        budget.max_bytes
+    );
+
+    for tracker in &trackers {
+        if total <= budget.max_bytes {
+            break;
+        }
+        total = total.saturating_sub(tracker.spare.clear());
+    }
+
+    // The front frame is painted next, so it keeps its pixels.
+    'converted: for tracker in &trackers {
+        for frame in tracker.frame_buffer.lock().iter().skip(1).rev() {
+            if total <= budget.max_bytes {
+                break 'converted;
+            }
+            if let Some(pixels) = frame.take_converted() {
+                total = total.saturating_sub(pixels.len());
+            }
+        }
+    }
+
+    while total > budget.max_bytes {
+        let Some(longest) = trackers
+            .iter()
+            .map(|tracker| (tracker, tracker.frame_buffer.lock().len()))
+            .filter(|(_, len)| *len > budget.min_buffered_frames)
+            .max_by_key(|(_, len)| *len)
+            .map(|(tracker, _)| tracker)
+        else {
+            break;
+        };
+        match longest.frame_buffer.lock().pop_front() {
+            Some(frame) => {
+                total = total.saturating_sub(frame.size());
+                // Free its pixels instead of letting the drop keep them as spares.
+                drop(frame.take_converted());
+            }
+            None => break,
+        }
+    }
+}
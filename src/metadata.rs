@@ -0,0 +1,112 @@
+use gstreamer as gst;
+
+/// Descriptive tags and stream information gathered from the media's tag messages.
+///
+/// Fields fill in as playback reaches the streams that carry them; most files
+/// report everything while prerolling.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    /// Human-readable container name, e.g. `ISO MP4/M4A`.
+    pub container: Option<String>,
+    /// Human-readable codec names, e.g. `H.264 (High Profile)`.
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    /// Nominal or exact bitrate in bits per second.
+    pub bitrate: Option<u32>,
+    /// Creation date in ISO 8601 form.
+    pub creation_date: Option<String>,
+    /// Clockwise rotation in degrees from the `image-orientation` tag.
+    pub rotation: Option<u32>,
+}
+
+impl Metadata {
+    /// Merge a tag list into the metadata; later tags override earlier ones.
+    pub(crate) fn merge(&mut self, tags: &gst::TagListRef) {
+        let text = |value: Option<gst::tags::TagValue<&str>>| value.map(|v| v.get().to_string());
+        if let Some(title) = text(tags.get::<gst::tags::Title>()) {
+            self.title = Some(title);
+        }
+        if let Some(artist) = text(tags.get::<gst::tags::Artist>()) {
+            self.artist = Some(artist);
+        }
+        if let Some(album) = text(tags.get::<gst::tags::Album>()) {
+            self.album = Some(album);
+        }
+        if let Some(container) = text(tags.get::<gst::tags::ContainerFormat>()) {
+            self.container = Some(container);
+        }
+        if let Some(codec) = text(tags.get::<gst::tags::VideoCodec>()) {
+            self.video_codec = Some(codec);
+        }
+        if let Some(codec) = text(tags.get::<gst::tags::AudioCodec>()) {
+            self.audio_codec = Some(codec);
+        }
+        // Not every demuxer reports an exact bitrate, so fall back to the nominal one.
+        if let Some(bitrate) = tags
+            .get::<gst::tags::Bitrate>()
+            .or_else(|| tags.get::<gst::tags::NominalBitrate>())
+        {
+            self.bitrate = Some(bitrate.get());
+        }
+        if let Some(date) = tags.get::<gst::tags::DateTime>()
+            && let Ok(date) = date.get().to_iso8601_string()
+        {
+            self.creation_date = Some(date.to_string());
+        }
+        if let Some(orientation) = tags.get::<gst::tags::ImageOrientation>()
+            && let Some(orientation) = Orientation::parse(orientation.get())
+        {
+            self.rotation = Some(orientation.degrees);
+        }
+    }
+}
+
+/// How decoded frames must be transformed to appear upright, parsed from an
+/// `image-orientation` tag such as `rotate-90` or `flip-rotate-180`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Orientation {
+    /// Clockwise rotation: 0, 90, 180, or 270.
+    pub(crate) degrees: u32,
+    /// Mirror horizontally before rotating.
+    pub(crate) flip: bool,
+}
+
+impl Orientation {
+    pub(crate) fn parse(tag: &str) -> Option<Self> {
+        let (flip, rest) = match tag.strip_prefix("flip-") {
+            Some(rest) => (true, rest),
+            None => (false, tag),
+        };
+        let degrees = rest.strip_prefix("rotate-")?.parse().ok()?;
+        matches!(degrees, 0 | 90 | 180 | 270).then_some(Self { degrees, flip })
+    }
+
+    pub(crate) fn is_identity(self) -> bool {
+        self.degrees == 0 && !self.flip
+    }
+
+    /// Whether the upright frame is transposed relative to the decoded one.
+    pub(crate) fn swaps_axes(self) -> bool {
+        self.degrees % 180 == 90
+    }
+
+    /// Transform a decoded frame so it displays upright. Works for any 4-byte
+    /// pixel order, since only whole pixels move.
+    pub(crate) fn apply(self, mut image: image::RgbaImage) -> image::RgbaImage {
+        if self.flip {
+            image::imageops::flip_horizontal_in_place(&mut image);
+        }
+        match self.degrees {
+            90 => image::imageops::rotate90(&image),
+            180 => {
+                image::imageops::rotate180_in_place(&mut image);
+                image
+            }
+            270 => image::imageops::rotate270(&image),
+            _ => image,
+        }
+    }
+}
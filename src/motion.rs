@@ -0,0 +1,122 @@
+use crate::Error;
+use crate::export::link_decoded_pads;
+use crate::runtime;
+use gstreamer as gst;
+use gstreamer_app as gst_app;
+use gstreamer_app::prelude::*;
+use gstreamer_video as gst_video;
+use std::time::Duration;
+
+/// Frames are compared at this size; motion that survives the downscale is what
+/// a viewer would notice, and sensor noise mostly averages out.
+const ANALYSIS_SIZE: (i32, i32) = (64, 36);
+
+/// Frames compared per second of media. Decoding still touches every frame, but
+/// comparing a handful per second is enough to locate activity.
+const ANALYSIS_FPS: i32 = 5;
+
+/// Decode `uri` and score how much the picture changes during each second.
+///
+/// A score is the mean absolute difference of the luma plane between consecutive
+/// analyzed frames, scaled to `0.0..=1.0`; entry `i` covers second `i`.
+pub(crate) fn motion_scores(uri: &url::Url) -> Result<Vec<f32>, Error> {
+    runtime::ensure_init()?;
+
+    let pipeline = gst::Pipeline::new();
+    let source = gst::ElementFactory::make("uridecodebin")
+        .property("uri", uri.as_str())
+        .build()?;
+    let convert = gst::ElementFactory::make("videoconvert").build()?;
+    let scale = gst::ElementFactory::make("videoscale").build()?;
+    let rate = gst::ElementFactory::make("videorate").build()?;
+    let caps = gst::Caps::builder("video/x-raw")
+        .field("format", "GRAY8")
+        .field("width", ANALYSIS_SIZE.0)
+        .field("height", ANALYSIS_SIZE.1)
+        .field("pixel-aspect-ratio", gst::Fraction::new(1, 1))
+        .field("framerate", gst::Fraction::new(ANALYSIS_FPS, 1))
+        .build();
+    let sink = gst_app::AppSink::builder().caps(&caps).sync(false).build();
+
+    pipeline.add_many([&source, &convert, &scale, &rate, sink.upcast_ref()])?;
+    gst::Element::link_many([&convert, &scale, &rate, sink.upcast_ref()])?;
+    link_decoded_pads(&source, &convert, "video/");
+
+    let result = (|| -> Result<Vec<f32>, Error> {
+        pipeline.set_state(gst::State::Playing)?;
+
+        // Per second: summed frame differences and how many were added.
+        let mut seconds: Vec<(f64, u32)> = Vec::new();
+        let mut previous: Option<Vec<u8>> = None;
+        // pull_sample fails at EOS or when the pipeline errors out.
+        while let Ok(sample) = sink.pull_sample() {
+            let (Some(buffer), Some(caps)) = (sample.buffer(), sample.caps()) else {
+                continue;
+            };
+            let info = gst_video::VideoInfo::from_caps(caps).map_err(|_| Error::Caps)?;
+            let Ok(map) = buffer.map_readable() else {
+                continue;
+            };
+            let luma = tight_plane(map.as_slice(), &info);
+
+            if let Some(previous) = &previous
+                && previous.len() == luma.len()
+                && !luma.is_empty()
+                && let Some(pts) = buffer.pts()
+            {
+                let sad: u64 = previous
+                    .iter()
+                    .zip(&luma)
+                    .map(|(a, b)| u64::from(a.abs_diff(*b)))
+                    .sum();
+                let score = sad as f64 / (luma.len() as f64 * 255.0);
+                let second = Duration::from_nanos(pts.nseconds()).as_secs() as usize;
+                if seconds.len() <= second {
+                    seconds.resize(second + 1, (0.0, 0));
+                }
+                seconds[second].0 += score;
+                seconds[second].1 += 1;
+            }
+            previous = Some(luma);
+        }
+
+        let bus = pipeline.bus().ok_or(Error::Bus)?;
+        if let Some(msg) = bus.pop_filtered(&[gst::MessageType::Error])
+            && let gst::MessageView::Error(err) = msg.view()
+        {
+            return Err(Error::from_bus(err, Some(uri.as_str())));
+        }
+        Ok(seconds
+            .into_iter()
+            .map(|(sum, count)| {
+                if count == 0 {
+                    0.0
+                } else {
+                    (sum / f64::from(count)) as f32
+                }
+            })
+            .collect())
+    })();
+
+    if let Err(err) = pipeline.set_state(gst::State::Null) {
+        log::error!("failed to shut down motion analysis pipeline: {}", err);
+    }
+    result
+}
+
+/// Copy the first plane without row padding.
+fn tight_plane(data: &[u8], info: &gst_video::VideoInfo) -> Vec<u8> {
+    let width = info.width() as usize;
+    let height = info.height() as usize;
+    let stride = info.stride()[0].max(0) as usize;
+    let offset = info.offset()[0];
+    let mut plane = Vec::with_capacity(width * height);
+    for row in 0..height {
+        let start = offset + row * stride;
+        match data.get(start..start + width) {
+            Some(row) => plane.extend_from_slice(row),
+            None => return Vec::new(),
+        }
+    }
+    plane
+}
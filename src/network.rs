@@ -0,0 +1,59 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+
+/// Limits on playbin's network buffering, for devices where GStreamer's defaults
+/// use more memory than can be spared. Fields left at None keep the current value.
+///
+/// Set at creation with [`VideoOptions::network_tuning`](crate::VideoOptions::network_tuning)
+/// or later with [`Video::set_network_tuning`](crate::Video::set_network_tuning);
+/// [`Video::network_tuning`](crate::Video::network_tuning) reports the values in effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NetworkTuning {
+    /// Most bytes of network data queued ahead of the decoders (playbin's
+    /// `buffer-size`). GStreamer's default is 2 MiB.
+    pub buffer_size: Option<u32>,
+    /// Most bytes kept of an already downloaded stream for seeking back (playbin's
+    /// `ring-buffer-max-size`). 0 keeps none.
+    pub ring_buffer_max_size: Option<u64>,
+    /// Bandwidth in kbit/s assumed when picking among the variants of an adaptive
+    /// stream (playbin's `connection-speed`). 0 lets GStreamer measure it.
+    pub connection_speed_kbps: Option<u64>,
+}
+
+impl NetworkTuning {
+    /// Set the given limits on `pipeline`, skipping any property it lacks, as a
+    /// custom pipeline may.
+    pub(crate) fn apply(&self, pipeline: &gst::Element) {
+        if let Some(size) = self.buffer_size
+            && pipeline.find_property("buffer-size").is_some()
+        {
+            // playbin takes a signed size where -1 means the default.
+            pipeline.set_property("buffer-size", size.min(i32::MAX as u32) as i32);
+        }
+        if let Some(size) = self.ring_buffer_max_size
+            && pipeline.find_property("ring-buffer-max-size").is_some()
+        {
+            pipeline.set_property("ring-buffer-max-size", size);
+        }
+        if let Some(speed) = self.connection_speed_kbps
+            && pipeline.find_property("connection-speed").is_some()
+        {
+            pipeline.set_property("connection-speed", speed);
+        }
+    }
+
+    /// Read the limits currently set on `pipeline`.
+    pub(crate) fn read(pipeline: &gst::Element) -> Self {
+        Self {
+            buffer_size: pipeline
+                .find_property("buffer-size")
+                .and_then(|_| u32::try_from(pipeline.property::<i32>("buffer-size")).ok()),
+            ring_buffer_max_size: pipeline
+                .find_property("ring-buffer-max-size")
+                .map(|_| pipeline.property::<u64>("ring-buffer-max-size")),
+            connection_speed_kbps: pipeline
+                .find_property("connection-speed")
+                .map(|_| pipeline.property::<u64>("connection-speed")),
+        }
+    }
+}
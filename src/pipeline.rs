@@ -0,0 +1,345 @@
+use crate::Error;
+use crate::convert::ColorSpace;
+use crate::filter;
+use crate::playbin::{self, Backend, PlayFlags};
+use crate::runtime;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use gstreamer_video as gst_video;
+
+/// Name of the video appsink unless [`PipelineBuilder::video_sink_name`] changes it.
+pub(crate) const VIDEO_SINK_NAME: &str = "gpui_video";
+
+/// Builds the playbin pipeline [`Video::new`](crate::Video::new) plays a URI with:
+/// playbin (or playbin3) feeding `videoscale ! videoconvert ! appsink`, with optional filters.
+///
+/// Use it to customize that pipeline without assembling one by hand for
+/// [`Video::from_gst_pipeline`](crate::Video::from_gst_pipeline). Pass the builder
+/// to [`Video::from_pipeline_builder`](crate::Video::from_pipeline_builder), or
+/// [`build`](Self::build) it to adjust the pipeline further first.
+///
+/// ```no_run
+/// # use gpui_video_player::{PipelineBuilder, PlayFlags, Url, Video, VideoOptions, gst};
+/// # fn main() -> Result<(), gpui_video_player::Error> {
+/// let uri = Url::parse("file:///tmp/clip \"final\".mp4").unwrap();
+/// let deinterlace = gst::ElementFactory::make("deinterlace").build()?;
+/// let builder = PipelineBuilder::new(&uri)
+///     .video_filter(deinterlace)
+///     .flags(PlayFlags::VIDEO | PlayFlags::AUDIO);
+/// let video = Video::from_pipeline_builder(builder, VideoOptions::default())?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct PipelineBuilder {
+    uri: url::Url,
+    pub(crate) backend: Option<Backend>,
+    pub(crate) sink_caps: Option<gst::Caps>,
+    /// Caps the video appsink switches to while the decoded stream is PQ or HLG.
+    pub(crate) hdr_sink_caps: Option<gst::Caps>,
+    pub(crate) video_filters: Vec<gst::Element>,
+    pub(crate) audio_filters: Vec<gst::Element>,
+    flags: Option<PlayFlags>,
+    video_sink_name: String,
+    text_sink_name: Option<String>,
+    pub(crate) max_buffers: Option<u32>,
+}
+
+/// The pipeline made by [`PipelineBuilder::build`], with the sinks to pass to
+/// [`Video::from_gst_pipeline`](crate::Video::from_gst_pipeline).
+#[derive(Debug, Clone)]
+pub struct BuiltPipeline {
+    pub pipeline: gst::Pipeline,
+    pub video_sink: gst_app::AppSink,
+    /// Set if [`PipelineBuilder::text_sink`] was requested.
+    pub text_sink: Option<gst_app::AppSink>,
+}
+
+impl PipelineBuilder {
+    /// Play `uri`. The URI is set as a property, so quotes and spaces in it are fine.
+    pub fn new(uri: &url::Url) -> Self {
+        Self {
+            uri: uri.clone(),
+            backend: None,
+            sink_caps: None,
+            hdr_sink_caps: None,
+            video_filters: Vec::new(),
+            audio_filters: Vec::new(),
+            flags: None,
+            video_sink_name: VIDEO_SINK_NAME.to_string(),
+            text_sink_name: None,
+            max_buffers: None,
+        }
+    }
+
+    pub fn uri(&self) -> &url::Url {
+        &self.uri
+    }
+
+    /// The element that plays the URI. Defaults to playbin.
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Caps the video appsink accepts. Defaults to the formats `VideoOptions` asks
+    /// for (NV12 unless set otherwise) with square pixels.
+    pub fn sink_caps(mut self, caps: gst::Caps) -> Self {
+        self.sink_caps = Some(caps);
+        self
+    }
+
+    /// Add an element to the video branch, before conversion to the sink format.
+    /// Filters run in the order they were added.
+    pub fn video_filter(mut self, element: gst::Element) -> Self {
+        self.video_filters.push(element);
+        self
+    }
+
+    /// Add an element to the audio branch. Filters run in the order they were
+    /// added, before the audio effects and analyzers `VideoOptions` asks for.
+    pub fn audio_filter(mut self, element: gst::Element) -> Self {
+        self.audio_filters.push(element);
+        self
+    }
+
+    /// playbin's flags. Defaults to playbin's own.
+    pub fn flags(mut self, flags: PlayFlags) -> Self {
+        self.flags = Some(flags);
+        self
+    }
+
+    /// Name of the video appsink, to find it with `by_name`. Defaults to `gpui_video`.
+    pub fn video_sink_name(mut self, name: impl Into<String>) -> Self {
+        self.video_sink_name = name.into();
+        self
+    }
+
+    /// Take subtitles as text from an appsink with this name instead of having
+    /// playbin render them into the frames.
+    pub fn text_sink(mut self, name: impl Into<String>) -> Self {
+        self.text_sink_name = Some(name.into());
+        self
+    }
+
+    /// Decoded frames the video appsink queues before dropping the oldest.
+    /// Defaults to 200, or 2 for RTSP streams, where queued frames only add delay.
+    pub fn max_buffers(mut self, max_buffers: u32) -> Self {
+        self.max_buffers = Some(max_buffers);
+        self
+    }
+
+    /// Assemble the pipeline. It is left in the Null state.
+    ///
+    /// Fails with [`Error::Uri`] if no installed source handles the URI's scheme,
+    /// with a not-found I/O error if it names a local file that does not exist, and
+    /// with [`Error::MissingPlugin`] if the backend is not installed.
+    pub fn build(self) -> Result<BuiltPipeline, Error> {
+        validate_uri(&self.uri)?;
+
+        let backend = self.backend.unwrap_or_default().element();
+        if gst::ElementFactory::find(backend).is_none() {
+            return Err(Error::MissingPlugin {
+                name: format!("{backend} element"),
+            });
+        }
+        let playbin = gst::ElementFactory::make(backend)
+            .property("uri", self.uri.as_str())
+            .build()?
+            .downcast::<gst::Pipeline>()
+            .map_err(|_| Error::Cast)?;
+
+        let scale = gst::ElementFactory::make("videoscale").build()?;
+        let convert = gst::ElementFactory::make("videoconvert").build()?;
+        let mut video_sink = gst_app::AppSink::builder()
+            .name(self.video_sink_name.as_str())
+            .drop(true)
+            .max_buffers(self.max_buffers.unwrap_or(200))
+            .enable_last_sample(false);
+        if let Some(caps) = &self.sink_caps {
+            video_sink = video_sink.caps(caps);
+        }
+        let video_sink = video_sink.build();
+        let sink_bin = gst::Bin::new();
+        sink_bin.add_many([&scale, &convert, video_sink.upcast_ref()])?;
+        gst::Element::link_many([&scale, &convert, video_sink.upcast_ref()])?;
+        let sink_pad = scale.static_pad("sink").ok_or(Error::Caps)?;
+        if let Some(hdr_caps) = self.hdr_sink_caps {
+            // videoconvert picks P010 for any 10-bit source if the sink offers it, so
+            // only offer it once the decoded caps say the source is HDR.
+            let sdr_caps = self.sink_caps.clone();
+            let sink = video_sink.downgrade();
+            sink_pad.add_probe(gst::PadProbeType::EVENT_DOWNSTREAM, move |_, info| {
+                if let Some(event) = info.event()
+                    && let gst::EventView::Caps(caps) = event.view()
+                    && let Some(sink) = sink.upgrade()
+                {
+                    let hdr = gst_video::VideoInfo::from_caps(caps.caps())
+                        .is_ok_and(|info| ColorSpace::from_video_info(&info).is_hdr());
+                    sink.set_caps(if hdr {
+                        Some(&hdr_caps)
+                    } else {
+                        sdr_caps.as_ref()
+                    });
+                }
+                gst::PadProbeReturn::Ok
+            });
+        }
+        sink_bin.add_pad(&gst::GhostPad::with_target(&sink_pad)?)?;
+        playbin.set_property("video-sink", &sink_bin);
+
+        let text_sink = match &self.text_sink_name {
+            Some(name) => {
+                let sink = gst_app::AppSink::builder()
+                    .name(name.as_str())
+                    .caps(&gst::Caps::builder("text/x-raw").build())
+                    .drop(true)
+                    .build();
+                playbin.set_property("text-sink", &sink);
+                Some(sink)
+            }
+            None => None,
+        };
+
+        if let Some(flags) = self.flags {
+            playbin::set_flags(&playbin, flags);
+        }
+        if let Some(filter) = filter::chain(self.audio_filters)? {
+            playbin.set_property("audio-filter", &filter);
+        }
+        if let Some(filter) = filter::chain(self.video_filters)? {
+            playbin.set_property("video-filter", &filter);
+        }
+
+        Ok(BuiltPipeline {
+            pipeline: playbin,
+            video_sink,
+            text_sink,
+        })
+    }
+}
+
+/// The pad frames enter `video_sink` through, once it is checked that they can
+/// reach it: the sink must be in `pipeline`, or be or sit inside the `video-sink`
+/// of a playbin or playbin3, which only adds its sinks once it starts. A playbin
+/// without a video sink is given `video_sink`.
+pub(crate) fn video_sink_pad(
+    pipeline: &gst::Pipeline,
+    video_sink: &gst_app::AppSink,
+) -> Result<gst::Pad, Error> {
+    let unusable = |reason| Error::VideoSink {
+        name: video_sink.name().to_string(),
+        reason,
+    };
+    let pad = video_sink
+        .static_pad("sink")
+        .ok_or_else(|| unusable("it has no sink pad"))?;
+    if video_sink.has_as_ancestor(pipeline) {
+        return Ok(pad);
+    }
+    if pipeline.find_property("video-sink").is_none() {
+        return Err(unusable("it was not added to the pipeline"));
+    }
+    match pipeline.property::<Option<gst::Element>>("video-sink") {
+        None => pipeline.set_property("video-sink", video_sink),
+        Some(sink)
+            if &sink == video_sink.upcast_ref::<gst::Element>()
+                || video_sink.has_as_ancestor(&sink) => {}
+        Some(_) => return Err(unusable("the pipeline's video-sink is a different element")),
+    }
+    Ok(pad)
+}
+
+/// Reject URIs playbin would only fail on later, with an opaque state change error:
+/// schemes no installed source element handles, and missing local files.
+pub(crate) fn validate_uri(uri: &url::Url) -> Result<(), Error> {
+    runtime::ensure_init()?;
+    if !gst::Uri::protocol_is_supported(gst::URIType::Src, uri.scheme()) {
+        return Err(Error::Uri);
+    }
+    if uri.scheme() == "file" {
+        let path = uri.to_file_path().map_err(|()| Error::Uri)?;
+        if !path.is_file() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{} does not exist", path.display()),
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// File names playbin's launch syntax would have choked on when the URI was
+    /// pasted into a pipeline description.
+    const NAMES: &[&str] = &[
+        "two words.mp4",
+        "clip!.mp4",
+        "it's.mp4",
+        "100%22.mp4",
+        #[cfg(unix)]
+        "clip \"final\".mp4",
+    ];
+
+    fn temp_dir(test: &str) -> Result<PathBuf, Error> {
+        let dir =
+            std::env::temp_dir().join(format!("gpui-video-player-{test}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    fn file_uri(path: &std::path::Path) -> url::Url {
+        url::Url::from_file_path(path).expect("temp paths are absolute")
+    }
+
+    #[test]
+    fn build_sets_uri_verbatim() -> Result<(), Error> {
+        let dir = temp_dir("build")?;
+        for name in NAMES {
+            let path = dir.join(name);
+            std::fs::write(&path, b"")?;
+            let uri = file_uri(&path);
+            let built = PipelineBuilder::new(&uri).build()?;
+            assert_eq!(built.pipeline.property::<String>("uri"), uri.as_str());
+        }
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_uri_accepts_existing_files() -> Result<(), Error> {
+        let dir = temp_dir("existing")?;
+        for name in NAMES {
+            let path = dir.join(name);
+            std::fs::write(&path, b"")?;
+            validate_uri(&file_uri(&path))?;
+        }
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_uri_rejects_missing_files() -> Result<(), Error> {
+        let dir = temp_dir("missing")?;
+        for name in NAMES {
+            match validate_uri(&file_uri(&dir.join(name))) {
+                Err(Error::Io(error)) => assert_eq!(error.kind(), std::io::ErrorKind::NotFound),
+                other => panic!("expected a not-found error for {name}, got {other:?}"),
+            }
+        }
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn validate_uri_rejects_unknown_schemes() {
+        let uri = url::Url::parse("no-such-scheme://host/clip.mp4").expect("valid URI");
+        assert!(matches!(validate_uri(&uri), Err(Error::Uri)));
+    }
+}
@@ -0,0 +1,156 @@
+use crate::Error;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::ops::{BitOr, BitOrAssign};
+
+/// playbin's `flags`: which streams it plays and which helpers it plugs in.
+///
+/// Combine with `|`, e.g. `PlayFlags::VIDEO | PlayFlags::AUDIO` to turn off
+/// subtitles. Read the current set with [`Video::play_flags`](crate::Video::play_flags).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PlayFlags(u32);
+
+impl PlayFlags {
+    pub const VIDEO: Self = Self(1 << 0);
+    pub const AUDIO: Self = Self(1 << 1);
+    /// Render subtitles.
+    pub const TEXT: Self = Self(1 << 2);
+    /// Show a visualization when there is no video.
+    pub const VIS: Self = Self(1 << 3);
+    /// Apply volume in software rather than in the audio sink.
+    pub const SOFT_VOLUME: Self = Self(1 << 4);
+    /// Only plug audio elements that keep the decoder's output format.
+    pub const NATIVE_AUDIO: Self = Self(1 << 5);
+    /// Only plug video elements that keep the decoder's output format.
+    pub const NATIVE_VIDEO: Self = Self(1 << 6);
+    /// Download progressive network media to disk.
+    pub const DOWNLOAD: Self = Self(1 << 7);
+    /// Buffer network media in memory.
+    pub const BUFFERING: Self = Self(1 << 8);
+    pub const DEINTERLACE: Self = Self(1 << 9);
+    /// Apply color balance in software.
+    pub const SOFT_COLORBALANCE: Self = Self(1 << 10);
+    /// Keep audio and video filters even when they could be skipped.
+    pub const FORCE_FILTERS: Self = Self(1 << 11);
+    /// Ignore hardware decoders.
+    pub const FORCE_SW_DECODERS: Self = Self(1 << 12);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub const fn without(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+}
+
+impl BitOr for PlayFlags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for PlayFlags {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+/// The element that plays media opened by URI, set with
+/// [`VideoOptions::backend`](crate::VideoOptions::backend) or
+/// [`PipelineBuilder::backend`](crate::PipelineBuilder::backend).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Backend {
+    /// playbin, available in every GStreamer release.
+    #[default]
+    Playbin,
+    /// playbin3 (stable since GStreamer 1.22), built on uridecodebin3 and
+    /// decodebin3. It reports the media's streams as a collection and switches
+    /// audio and subtitle tracks without rebuilding its decoders.
+    Playbin3,
+}
+
+impl Backend {
+    pub(crate) fn element(self) -> &'static str {
+        match self {
+            Self::Playbin => "playbin",
+            Self::Playbin3 => "playbin3",
+        }
+    }
+}
+
+/// playbin properties applied when a [`Video`](crate::Video) is created through
+/// [`VideoOptions::playbin`](crate::VideoOptions::playbin). Fields left at None
+/// keep playbin's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlaybinSettings {
+    /// Streams and helpers to use; change later with
+    /// [`Video::set_play_flags`](crate::Video::set_play_flags).
+    pub flags: Option<PlayFlags>,
+    /// Pango font description for rendered subtitles, e.g. `Sans Bold 24`; change
+    /// later with [`Video::set_subtitle_font`](crate::Video::set_subtitle_font).
+    pub subtitle_font: Option<String>,
+    /// Audio sink to use instead of the automatic one, as an element name or a
+    /// `gst-launch` description such as `pulsesink device=headphones`. Can only be
+    /// set here: playbin does not swap sinks while playing.
+    pub audio_sink: Option<String>,
+    /// Start muted, so nothing is heard before the first
+    /// [`Video::set_muted`](crate::Video::set_muted).
+    pub muted: bool,
+}
+
+impl PlaybinSettings {
+    /// Set these properties on a playbin that has not started yet.
+    pub(crate) fn apply(&self, playbin: &gst::Pipeline) -> Result<(), Error> {
+        if let Some(flags) = self.flags {
+            set_flags(playbin, flags);
+        }
+        if let Some(font) = &self.subtitle_font {
+            playbin.set_property("subtitle-font-desc", font);
+        }
+        if let Some(description) = &self.audio_sink {
+            let sink = gst::parse::bin_from_description(description, true)?;
+            playbin.set_property("audio-sink", sink.upcast::<gst::Element>());
+        }
+        if self.muted {
+            playbin.set_property("mute", true);
+        }
+        Ok(())
+    }
+}
+
+/// Set playbin's `flags`. It is a GFlags property, so the bits are wrapped in its
+/// registered type rather than passed as a plain integer.
+pub(crate) fn set_flags(playbin: &gst::Pipeline, flags: PlayFlags) {
+    let Some(pspec) = playbin.find_property("flags") else {
+        return;
+    };
+    let value = glib::FlagsClass::with_type(pspec.value_type())
+        .and_then(|class| class.to_value(flags.bits()));
+    match value {
+        Some(value) => playbin.set_property_from_value("flags", &value),
+        None => log::warn!("the pipeline does not accept play flags {flags:?}"),
+    }
+}
+
+/// Read playbin's `flags`, if the pipeline has them.
+pub(crate) fn flags(playbin: &gst::Pipeline) -> Option<PlayFlags> {
+    playbin.find_property("flags")?;
+    let bits = playbin
+        .property_value("flags")
+        .transform::<u32>()
+        .ok()?
+        .get::<u32>()
+        .ok()?;
+    Some(PlayFlags(bits))
+}
@@ -0,0 +1,180 @@
+//! Pausing every video while the system sleeps.
+//!
+//! gpui does not report sleep or screen lock, so apps that get these notifications
+//! from the platform call [`system_will_sleep`] and [`system_did_wake`] themselves.
+//! [`watch_system_wake`] is a fallback for the rest: it notices a sleep only once
+//! the system has woken from it.
+
+use crate::event::VideoEvent;
+use crate::governor;
+use crate::video::{Internal, Video};
+use parking_lot::{Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+/// How often the sleep detector compares the clocks.
+const DETECT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Shorter sleeps between two checks are ignored, e.g. a lid closed by accident.
+const MIN_SLEEP_GAP: Duration = Duration::from_secs(5);
+
+/// What [`system_did_wake`] does with the videos [`system_will_sleep`] paused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum WakePolicy {
+    /// Leave them paused, so a laptop opened in a meeting stays quiet.
+    #[default]
+    StayPaused,
+    /// Resume the videos that were playing.
+    Resume,
+}
+
+static VIDEOS: Mutex<Vec<Weak<RwLock<Internal>>>> = Mutex::new(Vec::new());
+/// Videos paused by `system_will_sleep` and not yet woken.
+static ASLEEP: Mutex<Vec<Weak<RwLock<Internal>>>> = Mutex::new(Vec::new());
+static WAKE_POLICY: Mutex<WakePolicy> = Mutex::new(WakePolicy::StayPaused);
+static DETECTING: AtomicBool = AtomicBool::new(false);
+
+/// Track a new video, so it is paused with the rest when the system sleeps.
+pub(crate) fn register(video: &Video) {
+    let mut videos = VIDEOS.lock();
+    videos.retain(|video| video.strong_count() > 0);
+    videos.push(Arc::downgrade(&video.0));
+}
+
+fn upgrade(videos: &[Weak<RwLock<Internal>>]) -> Vec<Video> {
+    videos.iter().filter_map(Weak::upgrade).map(Video).collect()
+}
+
+fn emit(video: &Video, event: VideoEvent) {
+    // Callbacks may use the video, so they run without its lock held.
+    let events = Arc::clone(&video.read().events);
+    events.emit(event);
+}
+
+/// Set what happens to paused videos when the system wakes. Defaults to
+/// [`WakePolicy::StayPaused`].
+pub fn set_wake_policy(policy: WakePolicy) {
+    *WAKE_POLICY.lock() = policy;
+}
+
+pub fn wake_policy() -> WakePolicy {
+    *WAKE_POLICY.lock()
+}
+
+/// Pause every playing video and send each a [`VideoEvent::SystemSleep`]. Call this
+/// from the platform's sleep or screen lock notification.
+pub fn system_will_sleep() {
+    let playing: Vec<Video> = upgrade(&VIDEOS.lock())
+        .into_iter()
+        .filter(|video| !video.paused())
+        .collect();
+    if playing.is_empty() {
+        return;
+    }
+    log::debug!("pausing {} videos for system sleep", playing.len());
+    // Paused behind the governor's back, so the videos keep their slots rather
+    // than handing them to queued videos while the system sleeps.
+    for video in &playing {
+        if let Err(err) = video.write().set_paused(true) {
+            log::warn!("failed to pause video for system sleep: {err}");
+        }
+    }
+    ASLEEP
+        .lock()
+        .extend(playing.iter().map(|video| Arc::downgrade(&video.0)));
+    for video in &playing {
+        emit(video, VideoEvent::SystemSleep);
+    }
+}
+
+/// Apply the [`WakePolicy`] to the videos [`system_will_sleep`] paused and send
+/// each a [`VideoEvent::SystemWake`]. Call this from the platform's wake or
+/// screen unlock notification.
+pub fn system_did_wake() {
+    let asleep = upgrade(&std::mem::take(&mut *ASLEEP.lock()));
+    let resume = wake_policy() == WakePolicy::Resume;
+    for video in &asleep {
+        // A video the user resumed meanwhile is left alone.
+        let resumed = resume && video.paused();
+        if resumed {
+            if let Err(err) = video.write().set_paused(false) {
+                log::warn!("failed to resume video after system wake: {err}");
+            }
+        } else {
+            governor::release(video);
+        }
+        emit(video, VideoEvent::SystemWake { resumed });
+    }
+}
+
+/// Start a background thread that notices, once the system is awake again, that it
+/// slept, and then runs [`system_will_sleep`] and [`system_did_wake`]. Returns
+/// false on platforms without a clock that counts time asleep (anything but Linux
+/// and macOS), where nothing is started. Calling it again does nothing.
+///
+/// This is a fallback for apps without platform sleep notifications, not a
+/// replacement for them: it does not listen for logind's `PrepareForSleep`,
+/// `NSWorkspace` sleep notifications or Windows power broadcasts, so videos decode
+/// until the system actually sleeps, and it never sees screen lock. With
+/// [`WakePolicy::StayPaused`] it still leaves videos that were playing when the
+/// lid closed paused after waking.
+pub fn watch_system_wake() -> bool {
+    if time_asleep().is_none() {
+        log::debug!("no clock counts time asleep here; not watching for system wake");
+        return false;
+    }
+    if DETECTING.swap(true, Ordering::SeqCst) {
+        return true;
+    }
+    let spawned = std::thread::Builder::new()
+        .name("gpui-video-sleep".into())
+        .spawn(|| {
+            let mut asleep = time_asleep().unwrap_or_default();
+            loop {
+                std::thread::sleep(DETECT_INTERVAL);
+                let now = time_asleep().unwrap_or(asleep);
+                let gap = now.saturating_sub(asleep);
+                asleep = now;
+                if gap >= MIN_SLEEP_GAP {
+                    log::debug!("system was asleep for about {}s", gap.as_secs());
+                    system_will_sleep();
+                    system_did_wake();
+                }
+            }
+        });
+    match spawned {
+        Ok(_) => true,
+        Err(err) => {
+            log::warn!("failed to start the sleep detector: {err}");
+            DETECTING.store(false, Ordering::SeqCst);
+            false
+        }
+    }
+}
+
+/// Total time the system has slept since boot: the difference between a clock that
+/// counts time asleep and one that stops meanwhile. Neither moves when the wall
+/// clock is set, so a time change or NTP step never reads as a sleep.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn time_asleep() -> Option<Duration> {
+    #[cfg(target_os = "linux")]
+    let (with_sleep, without_sleep) = (libc::CLOCK_BOOTTIME, libc::CLOCK_MONOTONIC);
+    // macOS's monotonic clock keeps counting while asleep; the uptime clock does not.
+    #[cfg(target_os = "macos")]
+    let (with_sleep, without_sleep) = (libc::CLOCK_MONOTONIC, libc::CLOCK_UPTIME_RAW);
+    let read = |clock| {
+        let mut time = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        let result = unsafe { libc::clock_gettime(clock, &mut time) };
+        (result == 0).then(|| Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+    };
+    Some(read(with_sleep)?.saturating_sub(read(without_sleep)?))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn time_asleep() -> Option<Duration> {
+    None
+}
@@ -0,0 +1,308 @@
+use crate::encode::{AudioCodec, Container, EncodeProfile, EncoderPreference, VideoCodec};
+use crate::error::Error;
+use crate::event::{EventHub, VideoEvent};
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use parking_lot::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Raw data queued in a recorder's source before new buffers are dropped, so a
+/// slow encoder cannot grow memory without bound.
+const MAX_QUEUED_BYTES: u64 = 128 * 1024 * 1024;
+
+/// Audio is timestamped by when it will be heard; delays beyond this are bogus.
+const MAX_AUDIO_DELAY: Duration = Duration::from_secs(1);
+
+/// How long the muxer gets to finish the file after [`Video::stop_recording`](crate::Video::stop_recording).
+const FINALIZE_TIMEOUT: gst::ClockTime = gst::ClockTime::from_seconds(10);
+
+/// Settings for [`Video::record_to`](crate::Video::record_to).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecordOptions {
+    pub container: Container,
+    /// Video codec, or None to record audio only.
+    pub video_codec: Option<VideoCodec>,
+    /// Audio codec, or None to record video only.
+    pub audio_codec: Option<AudioCodec>,
+    /// Target video bitrate. None leaves the encoder's default.
+    pub bitrate_kbps: Option<u32>,
+}
+
+impl Default for RecordOptions {
+    fn default() -> Self {
+        Self {
+            container: Container::Mp4,
+            video_codec: Some(VideoCodec::H264),
+            audio_codec: Some(AudioCodec::Aac),
+            bitrate_kbps: None,
+        }
+    }
+}
+
+impl RecordOptions {
+    fn profile(&self) -> EncodeProfile {
+        EncodeProfile {
+            name: "recording".into(),
+            container: self.container,
+            video: self.video_codec,
+            audio: self.audio_codec,
+            size: None,
+            video_bitrate_kbps: self.bitrate_kbps,
+            audio_bitrate_kbps: None,
+            // Recording runs next to playback, so keep the CPU free for decoding.
+            encoder: EncoderPreference::PreferHardware,
+        }
+    }
+}
+
+/// Recording time, which stands still while the recording is paused.
+#[derive(Debug)]
+struct RecordClock {
+    started: Instant,
+    paused_since: Option<Instant>,
+    paused_total: Duration,
+}
+
+impl RecordClock {
+    /// Timestamp for something shown or heard `delay` from now, or None while paused.
+    fn timestamp(&self, delay: Duration) -> Option<gst::ClockTime> {
+        if self.paused_since.is_some() {
+            return None;
+        }
+        let elapsed = (Instant::now() + delay)
+            .duration_since(self.started)
+            .saturating_sub(self.paused_total);
+        Some(gst::ClockTime::from_nseconds(elapsed.as_nanos() as u64))
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        match (paused, self.paused_since) {
+            (true, None) => self.paused_since = Some(Instant::now()),
+            (false, Some(since)) => {
+                self.paused_total += since.elapsed();
+                self.paused_since = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Source feeding one stream of the recording.
+#[derive(Debug)]
+struct Input {
+    src: gst_app::AppSrc,
+    clock: Arc<Mutex<RecordClock>>,
+}
+
+impl Input {
+    fn push(&self, buffer: &gst::BufferRef, caps: Option<&gst::Caps>, delay: Duration) {
+        let Some(pts) = self.clock.lock().timestamp(delay) else {
+            return;
+        };
+        if self.src.current_level_bytes() > MAX_QUEUED_BYTES {
+            log::debug!("recording encoder is behind; dropping a buffer");
+            return;
+        }
+        if let Some(caps) = caps
+            && self.src.caps().as_ref() != Some(caps)
+        {
+            self.src.set_caps(Some(caps));
+        }
+        // A shallow copy: the data is shared with playback, only timestamps change.
+        let mut buffer = buffer.copy();
+        {
+            let buffer = buffer.make_mut();
+            buffer.set_pts(pts);
+            buffer.set_dts(gst::ClockTime::NONE);
+            buffer.set_duration(gst::ClockTime::NONE);
+        }
+        if let Err(err) = self.src.push_buffer(buffer) {
+            log::debug!("recording stopped accepting buffers: {err}");
+        }
+    }
+}
+
+/// Encodes what a video plays into a file, fed by copies of the decoded frames and
+/// of the audio reaching the audio sink. Runs in its own pipeline, so encoding
+/// never holds up playback.
+#[derive(Debug)]
+pub(crate) struct Recorder {
+    path: PathBuf,
+    pipeline: gst::Pipeline,
+    clock: Arc<Mutex<RecordClock>>,
+    video: Option<Input>,
+    audio: Option<Arc<Input>>,
+    audio_probe: Option<(gst::Pad, gst::PadProbeId)>,
+}
+
+impl Recorder {
+    /// Start recording `playback` to `path`.
+    pub(crate) fn start(
+        playback: &gst::Pipeline,
+        path: &Path,
+        options: RecordOptions,
+    ) -> Result<Self, Error> {
+        let pipeline = gst::Pipeline::new();
+        let encoder = gst::ElementFactory::make("encodebin")
+            .property("profile", options.profile().to_encoding_profile()?)
+            .build()?;
+        let sink = gst::ElementFactory::make("filesink")
+            .property("location", path.to_string_lossy().as_ref())
+            .build()?;
+        pipeline.add_many([&encoder, &sink])?;
+        encoder.link(&sink)?;
+
+        let clock = Arc::new(Mutex::new(RecordClock {
+            started: Instant::now(),
+            paused_since: None,
+            paused_total: Duration::ZERO,
+        }));
+        let add_input = |convert: &[&str], pad: &str| -> Result<Input, Error> {
+            let src = gst_app::AppSrc::builder()
+                .format(gst::Format::Time)
+                .is_live(true)
+                .build();
+            let mut chain = vec![src.clone().upcast::<gst::Element>()];
+            for factory in convert.iter().chain(&["queue"]) {
+                chain.push(gst::ElementFactory::make(factory).build()?);
+            }
+            pipeline.add_many(&chain)?;
+            gst::Element::link_many(&chain)?;
+            let encoder_pad = encoder.request_pad_simple(pad).ok_or(Error::Caps)?;
+            chain
+                .last()
+                .and_then(|last| last.static_pad("src"))
+                .ok_or(Error::Caps)?
+                .link(&encoder_pad)
+                .map_err(|_| Error::Caps)?;
+            Ok(Input {
+                src,
+                clock: Arc::clone(&clock),
+            })
+        };
+
+        let video = options
+            .video_codec
+            .map(|_| add_input(&["videoconvert"], "video_%u"))
+            .transpose()?;
+        let audio_sink_pad = playback
+            .find_property("audio-sink")
+            .and_then(|_| playback.property::<Option<gst::Element>>("audio-sink"))
+            .and_then(|sink| sink.static_pad("sink"));
+        let audio = match (options.audio_codec, &audio_sink_pad) {
+            (Some(_), Some(_)) => Some(Arc::new(add_input(
+                &["audioconvert", "audioresample"],
+                "audio_%u",
+            )?)),
+            (Some(_), None) => {
+                log::warn!("the pipeline has no audio sink to record from; recording video only");
+                None
+            }
+            (None, _) => None,
+        };
+
+        pipeline.set_state(gst::State::Playing)?;
+
+        let audio_probe = match (&audio, audio_sink_pad) {
+            (Some(input), Some(pad)) => {
+                let input = Arc::clone(input);
+                let playback = playback.downgrade();
+                let probe = pad.add_probe(gst::PadProbeType::BUFFER, move |pad, info| {
+                    if let Some(buffer) = info.buffer() {
+                        let delay = playback.upgrade().map_or(Duration::ZERO, |playback| {
+                            audio_delay(&playback, pad, buffer)
+                        });
+                        input.push(buffer, pad.current_caps().as_ref(), delay);
+                    }
+                    gst::PadProbeReturn::Ok
+                });
+                probe.map(|id| (pad, id))
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            pipeline,
+            clock,
+            video,
+            audio,
+            audio_probe,
+        })
+    }
+
+    /// Record a decoded frame. Frames reach the video sink when they are shown.
+    pub(crate) fn push_video(&self, sample: &gst::Sample) {
+        if let (Some(input), Some(buffer)) = (&self.video, sample.buffer()) {
+            input.push(buffer, sample.caps_owned().as_ref(), Duration::ZERO);
+        }
+    }
+
+    pub(crate) fn set_paused(&self, paused: bool) {
+        self.clock.lock().set_paused(paused);
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.clock.lock().paused_since.is_some()
+    }
+
+    /// End the streams and let the muxer finish the file in the background.
+    /// Emits [`VideoEvent::RecordingFinished`], or [`VideoEvent::Error`] on failure.
+    pub(crate) fn finish(mut self, events: Arc<EventHub>) {
+        if let Some((pad, probe)) = self.audio_probe.take() {
+            pad.remove_probe(probe);
+        }
+        let audio = self.audio.as_deref();
+        for input in self.video.iter().chain(audio) {
+            if let Err(err) = input.src.end_of_stream() {
+                log::debug!("failed to end recording stream: {err}");
+            }
+        }
+
+        let Self { path, pipeline, .. } = self;
+        std::thread::spawn(move || {
+            let result = (|| -> Result<(), Error> {
+                let bus = pipeline.bus().ok_or(Error::Bus)?;
+                let msg = bus.timed_pop_filtered(
+                    FINALIZE_TIMEOUT,
+                    &[gst::MessageType::Eos, gst::MessageType::Error],
+                );
+                match msg.as_ref().map(|msg| msg.view()) {
+                    Some(gst::MessageView::Error(err)) => Err(Error::from_bus(err, None)),
+                    Some(_) => Ok(()),
+                    None => Err(Error::RecordingTimeout),
+                }
+            })();
+            if let Err(err) = pipeline.set_state(gst::State::Null) {
+                log::error!("failed to shut down recording pipeline: {}", err);
+            }
+            match result {
+                Ok(()) => events.emit(VideoEvent::RecordingFinished(path)),
+                Err(err) => {
+                    log::error!("failed to finish recording {}: {}", path.display(), err);
+                    events.emit(VideoEvent::Error(err.to_string()));
+                }
+            }
+        });
+    }
+}
+
+/// How long until the audio sink plays `buffer`, from how far its running time
+/// is ahead of the playback clock.
+fn audio_delay(playback: &gst::Pipeline, pad: &gst::Pad, buffer: &gst::BufferRef) -> Duration {
+    let Some(buffer_time) = buffer.pts().and_then(|pts| {
+        pad.sticky_event::<gst::event::Segment>(0)?
+            .segment()
+            .downcast_ref::<gst::ClockTime>()?
+            .to_running_time(pts)
+    }) else {
+        return Duration::ZERO;
+    };
+    let Some(now) = playback.current_running_time() else {
+        return Duration::ZERO;
+    };
+    Duration::from_nanos(buffer_time.saturating_sub(now).nseconds()).min(MAX_AUDIO_DELAY)
+}
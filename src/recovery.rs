@@ -0,0 +1,61 @@
+use gstreamer as gst;
+use std::time::Duration;
+
+/// How a video recovers from fatal pipeline errors, such as a decoder failing on
+/// a corrupt stretch of the file. Set with
+/// [`VideoOptions::recovery`](crate::VideoOptions::recovery).
+///
+/// The pipeline is torn down, which discards its source and decoders, and
+/// restarted at the last shown frame. Waits between attempts double up to
+/// `max_delay`. Errors a restart cannot fix, such as a missing file or codec, are
+/// reported right away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetryPolicy {
+    /// Restarts tried before the error is reported as final. The count starts over
+    /// once frames flow again.
+    pub max_attempts: u32,
+    /// Wait before the first restart.
+    pub initial_delay: Duration,
+    /// Longest wait between restarts.
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Self::default()
+        }
+    }
+
+    /// Wait before restart number `attempt`, counting from 1.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(16);
+        self.initial_delay
+            .saturating_mul(1 << doublings)
+            .min(self.max_delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+        }
+    }
+}
+
+/// Whether restarting the pipeline could get past `error`. Sources that cannot be
+/// opened and media nothing can decode fail the same way every time.
+pub(crate) fn is_recoverable(error: &glib::Error) -> bool {
+    !(error.matches(gst::ResourceError::NotFound)
+        || error.matches(gst::ResourceError::OpenRead)
+        || error.matches(gst::ResourceError::NotAuthorized)
+        || error.matches(gst::StreamError::CodecNotFound)
+        || error.matches(gst::StreamError::TypeNotFound)
+        || error.matches(gst::StreamError::WrongType)
+        || error.matches(gst::StreamError::Format)
+        || error.matches(gst::CoreError::MissingPlugin))
+}
@@ -0,0 +1,103 @@
+use crate::convert::ColorOverrides;
+use crate::video::Video;
+use gpui::{App, Entity, EntityId, WeakEntity, Window};
+use std::time::Duration;
+
+/// What a video view draws from. It is compared on every display frame, and the
+/// view only redraws when it changed, so a paused or finished video costs no renders.
+#[derive(Debug, Clone, PartialEq)]
+struct Snapshot {
+    frame_generation: u64,
+    /// Buffered frames whose presentation time has come.
+    due_frames: usize,
+    paused: bool,
+    eos: bool,
+    scrubbing: bool,
+    buffering_percent: u8,
+    subtitle: Option<String>,
+    failed: bool,
+    color_overrides: ColorOverrides,
+    /// Playback position in whole steps, for views that follow it between frames.
+    position_steps: Option<u128>,
+}
+
+impl Snapshot {
+    fn read(video: &Video, position_step: Option<Duration>) -> Self {
+        // In pull-on-paint mode nothing else takes frames from the sink; pulling here
+        // notices a new one at the display refresh and paints it right away.
+        video.pull_latest_frame();
+        let subtitle = video.read().subtitle_text.lock().clone();
+        Self {
+            frame_generation: video.frame_generation(),
+            due_frames: video.due_buffered_len(),
+            paused: video.paused(),
+            eos: video.eos(),
+            scrubbing: video.is_scrubbing(),
+            buffering_percent: video.buffering_percent(),
+            subtitle,
+            failed: video.error().is_some(),
+            color_overrides: video.color_overrides(),
+            position_steps: position_step
+                .filter(|step| !step.is_zero())
+                .map(|step| video.position().as_nanos() / step.as_nanos()),
+        }
+    }
+}
+
+/// Redraw the current view once `video` shows something new: a frame, a subtitle,
+/// or a change of play state. With `position_step`, the position advancing by that
+/// much also counts, for views such as a seek bar that move with audio-only media.
+///
+/// Call this from `prepaint`. The check runs in gpui's next-frame callbacks, which
+/// fire on every display refresh without drawing anything; each call replaces the
+/// check scheduled by the element's previous draw.
+pub(crate) fn repaint_on_change(
+    window: &mut Window,
+    cx: &mut App,
+    video: &Video,
+    position_step: Option<Duration>,
+) {
+    let epoch: Entity<u64> = window.use_state(cx, |_, _| 0);
+    let current = epoch.update(cx, |epoch, _| {
+        *epoch += 1;
+        *epoch
+    });
+    Watch {
+        view: window.current_view(),
+        epoch: epoch.downgrade(),
+        current,
+        snapshot: Snapshot::read(video, position_step),
+        video: video.clone(),
+        position_step,
+    }
+    .schedule(window);
+}
+
+struct Watch {
+    view: EntityId,
+    epoch: WeakEntity<u64>,
+    current: u64,
+    snapshot: Snapshot,
+    video: Video,
+    position_step: Option<Duration>,
+}
+
+impl Watch {
+    fn schedule(self, window: &Window) {
+        window.on_next_frame(move |window, cx| {
+            // The element was drawn again and started its own watch, or is gone.
+            let superseded = self
+                .epoch
+                .upgrade()
+                .is_none_or(|epoch| *epoch.read(cx) != self.current);
+            if superseded {
+                return;
+            }
+            if Snapshot::read(&self.video, self.position_step) != self.snapshot {
+                cx.notify(self.view);
+            } else {
+                self.schedule(window);
+            }
+        });
+    }
+}
@@ -0,0 +1,114 @@
+//! Process-wide GStreamer setup, done once before the first video opens.
+
+use crate::Error;
+use crate::decoder::HwPreference;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use parking_lot::Mutex;
+use std::path::PathBuf;
+
+/// How GStreamer is set up for the process, passed to [`init`].
+///
+/// Everything here is global to GStreamer, so it applies to every video, thumbnail,
+/// and export, including ones made by other libraries in the process.
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeConfig {
+    /// Directories scanned for plugins besides GStreamer's own, e.g. plugins
+    /// bundled with the app.
+    pub plugin_paths: Vec<PathBuf>,
+    /// Optional debug log thresholds in `GST_DEBUG` syntax, e.g.
+    /// `*:2,decodebin:5`. Defaults to None (the `GST_DEBUG` environment variable).
+    pub debug: Option<String>,
+    /// Optional choice between hardware and software decoders for videos whose
+    /// [`VideoOptions::hardware_decoding`](crate::VideoOptions::hardware_decoding)
+    /// is None. Defaults to Auto.
+    pub hardware_decoding: Option<HwPreference>,
+    /// Decoders to use ahead of every other one for the formats they handle,
+    /// most preferred first. Names not installed on this platform are skipped,
+    /// so one list can name decoders for every platform.
+    pub preferred_decoders: Vec<String>,
+    /// Decoders never to pick, e.g. one with a driver bug. Names not installed are
+    /// skipped.
+    pub disabled_decoders: Vec<String>,
+}
+
+/// The config GStreamer was set up with, once it has been.
+static CONFIG: Mutex<Option<RuntimeConfig>> = Mutex::new(None);
+
+/// Initialize GStreamer and apply `config`.
+///
+/// Call it once, before opening any video; the first video, thumbnail, or export
+/// otherwise initializes GStreamer with the defaults. Fails with
+/// [`Error::AlreadyInitialized`] once either has happened, and with a not-found
+/// I/O error if a plugin path is not a directory.
+pub fn init(config: RuntimeConfig) -> Result<(), Error> {
+    let mut state = CONFIG.lock();
+    if state.is_some() {
+        return Err(Error::AlreadyInitialized);
+    }
+    apply(&config)?;
+    *state = Some(config);
+    Ok(())
+}
+
+/// The config passed to [`init`], or the default one if GStreamer was initialized
+/// without it. None before either.
+pub fn runtime_config() -> Option<RuntimeConfig> {
+    CONFIG.lock().clone()
+}
+
+/// Initialize GStreamer with the default config unless [`init`] already did.
+pub(crate) fn ensure_init() -> Result<(), Error> {
+    let mut state = CONFIG.lock();
+    if state.is_none() {
+        let config = RuntimeConfig::default();
+        apply(&config)?;
+        *state = Some(config);
+    }
+    Ok(())
+}
+
+/// Hardware decoding preference for videos that do not choose one.
+pub(crate) fn hardware_decoding() -> HwPreference {
+    CONFIG
+        .lock()
+        .as_ref()
+        .and_then(|config| config.hardware_decoding)
+        .unwrap_or_default()
+}
+
+fn apply(config: &RuntimeConfig) -> Result<(), Error> {
+    gst::init()?;
+    if let Some(debug) = &config.debug {
+        gst::log::set_threshold_from_string(debug, true);
+    }
+
+    let registry = gst::Registry::get();
+    for path in &config.plugin_paths {
+        if !path.is_dir() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("plugin directory {} does not exist", path.display()),
+            )
+            .into());
+        }
+        if !registry.scan_path(path) {
+            log::debug!("no new plugins in {}", path.display());
+        }
+    }
+
+    let set_rank = |name: &str, rank: gst::Rank| match registry.lookup_feature(name) {
+        Some(feature) => feature.set_rank(rank),
+        None => log::debug!("decoder {name} is not installed"),
+    };
+    for name in &config.disabled_decoders {
+        set_rank(name, gst::Rank::NONE);
+    }
+    // decodebin tries decoders by rank, and some hardware ones rank above
+    // PRIMARY, so put these well above that, the first highest.
+    let count = config.preferred_decoders.len() as i32;
+    for (index, name) in config.preferred_decoders.iter().enumerate() {
+        set_rank(name, gst::Rank::PRIMARY + 256 + (count - index as i32));
+    }
+    Ok(())
+}
@@ -0,0 +1,519 @@
+use crate::element::video;
+use crate::video::{TimeRange, Video};
+use crate::{Error, Url};
+use gpui::{IntoElement, ParentElement, Pixels, RenderOnce, Styled, Window, div};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// How often the sequence worker checks for clip boundaries.
+const TICK: Duration = Duration::from_millis(10);
+
+/// One entry of an edit decision list: the part of `uri` between `in_point` and `out_point`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clip {
+    pub uri: Url,
+    pub in_point: Duration,
+    pub out_point: Duration,
+    /// Overlap with the next clip, during which the next clip fades in. Ignored on the last clip.
+    pub crossfade: Duration,
+    /// Playback rate, e.g. 0.5 for slow motion. Must be positive.
+    pub rate: f64,
+}
+
+impl Clip {
+    pub fn new(uri: Url, in_point: Duration, out_point: Duration) -> Self {
+        Self {
+            uri,
+            in_point,
+            out_point,
+            crossfade: Duration::ZERO,
+            rate: 1.0,
+        }
+    }
+
+    /// Fade into the next clip over `duration`.
+    pub fn crossfade(mut self, duration: Duration) -> Self {
+        self.crossfade = duration;
+        self
+    }
+
+    /// Play the clip at `rate` (e.g. 0.5 for half speed).
+    pub fn rate(mut self, rate: f64) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    /// Length of the source media between the in and out points.
+    pub fn len(&self) -> Duration {
+        self.out_point.saturating_sub(self.in_point)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len().is_zero()
+    }
+
+    /// Length of the clip on the sequence timeline, after retiming.
+    pub fn timeline_len(&self) -> Duration {
+        self.len().div_f64(self.rate)
+    }
+
+    /// Media position `offset` of timeline time into the clip.
+    fn media_at(&self, offset: Duration) -> Duration {
+        self.in_point + offset.mul_f64(self.rate)
+    }
+
+    /// Timeline time into the clip at media position `position`, the inverse of
+    /// [`media_at`](Self::media_at), limited to the clip.
+    fn offset_at(&self, position: Duration) -> Duration {
+        position
+            .saturating_sub(self.in_point)
+            .div_f64(self.rate)
+            .min(self.timeline_len())
+    }
+}
+
+/// Timeline overlap between clip `clip` and the one after it.
+fn overlap(clip: &Clip, next: Option<&Clip>) -> Duration {
+    match next {
+        Some(next) => clip
+            .crossfade
+            .min(clip.timeline_len())
+            .min(next.timeline_len()),
+        None => Duration::ZERO,
+    }
+}
+
+/// Timeline start of each of `clips`, and the length of the whole timeline.
+fn layout(clips: &[Clip]) -> (Vec<Duration>, Duration) {
+    let mut starts = Vec::with_capacity(clips.len());
+    let mut start = Duration::ZERO;
+    for (i, clip) in clips.iter().enumerate() {
+        starts.push(start);
+        start += clip.timeline_len() - overlap(clip, clips.get(i + 1));
+    }
+    (starts, start)
+}
+
+/// Crossfade progress of `clip` into `next` at media position `position` of
+/// `clip`, if the fade has begun.
+fn fade_progress(clip: &Clip, next: &Clip, position: Duration) -> Option<f32> {
+    // The fade is measured on the timeline; convert it to media time of this clip.
+    let fade = overlap(clip, Some(next)).mul_f64(clip.rate);
+    let fade_start = clip.out_point.saturating_sub(fade);
+    if position < fade_start {
+        return None;
+    }
+    if fade.is_zero() {
+        return Some(1.0);
+    }
+    Some(((position - fade_start).as_secs_f32() / fade.as_secs_f32()).clamp(0.0, 1.0))
+}
+
+/// Plays an ordered list of [`Clip`]s as one continuous timeline with a unified
+/// position, duration, and seek.
+///
+/// Each clip is played by its own [`Video`]. The next clip is prerolled at its in
+/// point while the current one plays, so cuts do not wait for the file to open.
+/// Render it with [`SequenceElement`].
+#[derive(Debug, Clone)]
+pub struct Sequence(Arc<SequenceInner>);
+
+#[derive(Debug)]
+struct SequenceInner {
+    clips: Vec<Clip>,
+    /// Timeline start of each clip; clips overlap by their crossfade.
+    starts: Vec<Duration>,
+    duration: Duration,
+    state: Mutex<SequenceState>,
+    alive: AtomicBool,
+    worker: Mutex<Option<std::thread::JoinHandle<()>>>,
+}
+
+#[derive(Debug, Default)]
+struct SequenceState {
+    current: usize,
+    /// Loaded players by clip index: the current clip and the prerolled next one.
+    videos: Vec<(usize, Video)>,
+    paused: bool,
+    finished: bool,
+}
+
+impl SequenceState {
+    fn video(&self, index: usize) -> Option<&Video> {
+        self.videos
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map(|(_, video)| video)
+    }
+}
+
+impl Sequence {
+    /// Open the first clips of `clips` and start playing from the beginning.
+    pub fn new(clips: Vec<Clip>) -> Result<Self, Error> {
+        if clips.is_empty() {
+            return Err(Error::EmptySequence);
+        }
+        if let Some(clip) = clips
+            .iter()
+            .find(|clip| !clip.rate.is_finite() || clip.rate <= 0.0)
+        {
+            return Err(Error::Rate(clip.rate));
+        }
+
+        let (starts, duration) = layout(&clips);
+
+        let inner = Arc::new(SequenceInner {
+            clips,
+            starts,
+            duration,
+            state: Mutex::new(SequenceState::default()),
+            alive: AtomicBool::new(true),
+            worker: Mutex::new(None),
+        });
+        let first = inner.open_clip(0, Duration::ZERO)?;
+        first.set_paused(false);
+        inner.state.lock().videos.push((0, first));
+
+        // The worker only holds a weak reference so dropping the last handle stops it.
+        let weak = Arc::downgrade(&inner);
+        let worker = std::thread::spawn(move || {
+            while let Some(inner) = weak.upgrade() {
+                if !inner.alive.load(Ordering::Acquire) {
+                    break;
+                }
+                inner.tick();
+                drop(inner);
+                std::thread::sleep(TICK);
+            }
+        });
+        *inner.worker.lock() = Some(worker);
+        Ok(Self(inner))
+    }
+
+    pub fn clips(&self) -> &[Clip] {
+        &self.0.clips
+    }
+
+    /// Total length of the timeline, with crossfades counted once and clips retimed by their rate.
+    pub fn duration(&self) -> Duration {
+        self.0.duration
+    }
+
+    /// Index of the clip currently playing (the outgoing one during a crossfade).
+    pub fn current_clip(&self) -> usize {
+        self.0.state.lock().current
+    }
+
+    /// Position on the sequence timeline.
+    pub fn position(&self) -> Duration {
+        let state = self.0.state.lock();
+        let index = state.current;
+        let Some(video) = state.video(index) else {
+            return self.0.starts[index];
+        };
+        let local = self.0.clips[index].offset_at(video.position());
+        (self.0.starts[index] + local).min(self.0.duration)
+    }
+
+    /// Jump to `position` on the sequence timeline, opening the clip it falls in.
+    pub fn seek(&self, position: Duration) -> Result<(), Error> {
+        let position = position.min(self.0.duration);
+        // Within a crossfade, land on the incoming clip.
+        let index = self
+            .0
+            .starts
+            .iter()
+            .rposition(|start| *start <= position)
+            .unwrap_or(0);
+        let offset = position - self.0.starts[index];
+
+        let existing = self.0.state.lock().video(index).cloned();
+        let video = match existing {
+            Some(video) => {
+                let clip = &self.0.clips[index];
+                video.read().seek_range(
+                    TimeRange {
+                        start: clip.media_at(offset),
+                        end: clip.out_point,
+                    },
+                    clip.rate,
+                )?;
+                video
+            }
+            None => self.0.open_clip(index, offset)?,
+        };
+
+        let mut state = self.0.state.lock();
+        video.set_volume(1.0);
+        video.set_paused(state.paused);
+        state.videos = vec![(index, video)];
+        state.current = index;
+        state.finished = false;
+        Ok(())
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        let mut state = self.0.state.lock();
+        state.paused = paused;
+        let current = state.current;
+        // A prerolled next clip stays paused until its crossfade or cut begins.
+        for (index, video) in &state.videos {
+            if *index == current || !video.paused() {
+                video.set_paused(paused);
+            }
+        }
+    }
+
+    pub fn paused(&self) -> bool {
+        self.0.state.lock().paused
+    }
+
+    /// Whether playback reached the end of the last clip.
+    pub fn finished(&self) -> bool {
+        self.0.state.lock().finished
+    }
+
+    /// Players to draw, bottom first, with the opacity of each.
+    pub(crate) fn layers(&self) -> Vec<(usize, Video, f32)> {
+        let state = self.0.state.lock();
+        let current = state.current;
+        let mut layers = Vec::with_capacity(2);
+        if let Some(video) = state.video(current) {
+            layers.push((current, video.clone(), 1.0));
+            if let Some(next) = state.video(current + 1)
+                && let Some(progress) = self.0.fade_progress(current, video)
+            {
+                layers.push((current + 1, next.clone(), progress));
+            }
+        }
+        layers
+    }
+}
+
+impl SequenceInner {
+    /// Open the player for clip `index`, paused at timeline time `offset` into the clip.
+    fn open_clip(&self, index: usize, offset: Duration) -> Result<Video, Error> {
+        let clip = &self.clips[index];
+        let video = Video::new(&clip.uri)?;
+        video.set_paused(true);
+        // Stop at the out point with EOS so the cut lands on the right frame.
+        video.read().seek_range(
+            TimeRange {
+                start: clip.media_at(offset),
+                end: clip.out_point,
+            },
+            clip.rate,
+        )?;
+        Ok(video)
+    }
+
+    /// Crossfade progress of clip `index` into the next one, if the fade has begun.
+    fn fade_progress(&self, index: usize, video: &Video) -> Option<f32> {
+        let next = self.clips.get(index + 1)?;
+        fade_progress(&self.clips[index], next, video.position())
+    }
+
+    /// Preroll the next clip, start it when its crossfade begins, and cut over at the out point.
+    fn tick(&self) {
+        let (current, video, next_loaded, paused) = {
+            let state = self.state.lock();
+            if state.finished {
+                return;
+            }
+            let Some(video) = state.video(state.current).cloned() else {
+                return;
+            };
+            let next_loaded = state.video(state.current + 1).is_some();
+            (state.current, video, next_loaded, state.paused)
+        };
+
+        // Opening a clip blocks while it prerolls, so do it without holding the lock.
+        if !next_loaded && current + 1 < self.clips.len() {
+            match self.open_clip(current + 1, Duration::ZERO) {
+                Ok(next) => {
+                    let mut state = self.state.lock();
+                    if state.current == current {
+                        state.videos.push((current + 1, next));
+                    }
+                }
+                Err(err) => log::error!("failed to open clip {}: {}", current + 1, err),
+            }
+            return;
+        }
+
+        let mut state = self.state.lock();
+        if state.current != current {
+            return;
+        }
+        let next = state.video(current + 1).cloned();
+        if let Some(next) = &next
+            && let Some(progress) = self.fade_progress(current, &video)
+        {
+            if !paused && next.paused() {
+                next.set_paused(false);
+            }
+            video.set_volume(f64::from(1.0 - progress));
+            next.set_volume(f64::from(progress));
+        }
+
+        let clip = &self.clips[current];
+        if video.eos() || video.position() >= clip.out_point {
+            match next {
+                Some(next) => {
+                    next.set_volume(1.0);
+                    next.set_paused(paused);
+                    state.videos.retain(|(index, _)| *index != current);
+                    state.current = current + 1;
+                }
+                None if current + 1 == self.clips.len() => {
+                    video.set_paused(true);
+                    state.finished = true;
+                }
+                // The next clip is still opening; hold the last frame until it is ready.
+                None => video.set_paused(true),
+            }
+        }
+    }
+}
+
+impl Drop for SequenceInner {
+    fn drop(&mut self) {
+        self.alive.store(false, Ordering::Release);
+        // The worker may be the one dropping the last reference, and cannot join itself.
+        if let Some(worker) = self.worker.lock().take()
+            && worker.thread().id() != std::thread::current().id()
+            && worker.join().is_err()
+        {
+            log::error!("sequence worker panicked");
+        }
+    }
+}
+
+/// Draws a [`Sequence`], blending the incoming clip over the outgoing one during crossfades.
+#[derive(IntoElement)]
+pub struct SequenceElement {
+    sequence: Sequence,
+    size: Option<(Pixels, Pixels)>,
+}
+
+impl SequenceElement {
+    pub fn new(sequence: Sequence) -> Self {
+        Self {
+            sequence,
+            size: None,
+        }
+    }
+
+    pub fn size(mut self, width: Pixels, height: Pixels) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+}
+
+impl RenderOnce for SequenceElement {
+    fn render(self, _window: &mut Window, _cx: &mut gpui::App) -> impl IntoElement {
+        let mut root = div().relative();
+        if let Some((width, height)) = self.size {
+            root = root.w(width).h(height);
+        }
+        for (layer, (index, clip_video, opacity)) in self.sequence.layers().into_iter().enumerate()
+        {
+            let mut element = video(clip_video).id(("sequence-clip", index));
+            if let Some((width, height)) = self.size {
+                element = element.size(width, height);
+            }
+            // The outgoing clip stays in flow to size the element; the incoming one overlays it.
+            root = if layer == 0 {
+                root.child(element)
+            } else {
+                root.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .opacity(opacity)
+                        .child(element),
+                )
+            };
+        }
+        root
+    }
+}
+
+/// Helper function to create a sequence element
+pub fn sequence(sequence: Sequence) -> SequenceElement {
+    SequenceElement::new(sequence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip(in_secs: f64, out_secs: f64) -> Clip {
+        let uri = Url::parse("file:///clip.mp4").expect("valid URI");
+        Clip::new(
+            uri,
+            Duration::from_secs_f64(in_secs),
+            Duration::from_secs_f64(out_secs),
+        )
+    }
+
+    fn secs(secs: f64) -> Duration {
+        Duration::from_secs_f64(secs)
+    }
+
+    #[test]
+    fn layout_overlaps_clips_by_their_crossfade() {
+        let clips = [
+            clip(0.0, 5.0).crossfade(secs(1.0)),
+            clip(2.0, 6.0),
+            // The last clip has nothing to fade into.
+            clip(0.0, 3.0).crossfade(secs(2.0)),
+        ];
+        let (starts, duration) = layout(&clips);
+        assert_eq!(starts, [secs(0.0), secs(4.0), secs(8.0)]);
+        assert_eq!(duration, secs(11.0));
+    }
+
+    #[test]
+    fn slow_motion_clip_maps_media_to_timeline() {
+        // 4 s of media at half speed fill 8 s of timeline, fading out over the last 2.
+        let slow = clip(10.0, 14.0).rate(0.5).crossfade(secs(2.0));
+        let next = clip(0.0, 6.0);
+        assert_eq!(slow.timeline_len(), secs(8.0));
+        assert_eq!(overlap(&slow, Some(&next)), secs(2.0));
+
+        let (starts, duration) = layout(&[slow.clone(), next.clone()]);
+        assert_eq!(starts, [secs(0.0), secs(6.0)]);
+        assert_eq!(duration, secs(12.0));
+
+        assert_eq!(slow.media_at(secs(3.0)), secs(11.5));
+        assert_eq!(slow.offset_at(secs(11.5)), secs(3.0));
+        assert_eq!(slow.offset_at(secs(9.0)), Duration::ZERO);
+        assert_eq!(slow.offset_at(secs(20.0)), secs(8.0));
+
+        // The 2 s timeline fade is the last 1 s of media, and starts with the next clip.
+        assert_eq!(fade_progress(&slow, &next, secs(12.9)), None);
+        assert_eq!(fade_progress(&slow, &next, secs(13.0)), Some(0.0));
+        assert_eq!(fade_progress(&slow, &next, secs(13.5)), Some(0.5));
+        assert_eq!(fade_progress(&slow, &next, secs(14.0)), Some(1.0));
+        assert_eq!(starts[0] + slow.offset_at(secs(13.0)), starts[1]);
+    }
+
+    #[test]
+    fn crossfade_is_clamped_to_both_clips() {
+        let short = clip(0.0, 1.0).crossfade(secs(5.0));
+        let long = clip(0.0, 3.0);
+        assert_eq!(overlap(&short, Some(&long)), secs(1.0));
+        assert_eq!(layout(&[short.clone(), long.clone()]).1, secs(3.0));
+        assert_eq!(fade_progress(&short, &long, Duration::ZERO), Some(0.0));
+
+        let long = clip(0.0, 4.0).crossfade(secs(5.0));
+        let short = clip(0.0, 2.0);
+        assert_eq!(overlap(&long, Some(&short)), secs(2.0));
+        assert_eq!(layout(&[long.clone(), short]).0, [secs(0.0), secs(2.0)]);
+        assert_eq!(overlap(&long, None), Duration::ZERO);
+    }
+}
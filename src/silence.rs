@@ -0,0 +1,105 @@
+use crate::Error;
+use crate::export::link_decoded_pads;
+use crate::runtime;
+use crate::video::TimeRange;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use std::time::Duration;
+
+/// Peak level (dBFS) below which audio counts as silent when skipping silence.
+pub(crate) const DEFAULT_SILENCE_THRESHOLD_DB: f64 = -50.0;
+
+/// Shortest pause that is skipped; shorter gaps are natural pauses in speech.
+pub(crate) const DEFAULT_MIN_SILENCE: Duration = Duration::from_millis(800);
+
+/// Granularity of the analysis.
+const ANALYSIS_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Decode the audio of `uri` and return the spans whose peak level on every channel
+/// stays below `threshold_db` for at least `min_duration`.
+pub(crate) fn detect_silence(
+    uri: &url::Url,
+    threshold_db: f64,
+    min_duration: Duration,
+) -> Result<Vec<TimeRange>, Error> {
+    runtime::ensure_init()?;
+
+    let pipeline = gst::Pipeline::new();
+    let source = gst::ElementFactory::make("uridecodebin")
+        .property("uri", uri.as_str())
+        .build()?;
+    let convert = gst::ElementFactory::make("audioconvert").build()?;
+    let level = gst::ElementFactory::make("level")
+        .property("interval", ANALYSIS_INTERVAL.as_nanos() as u64)
+        .property("post-messages", true)
+        .build()?;
+    let sink = gst::ElementFactory::make("fakesink")
+        .property("sync", false)
+        .build()?;
+
+    pipeline.add_many([&source, &convert, &level, &sink])?;
+    gst::Element::link_many([&convert, &level, &sink])?;
+    link_decoded_pads(&source, &convert, "audio/");
+
+    let result = (|| -> Result<Vec<TimeRange>, Error> {
+        let bus = pipeline.bus().ok_or(Error::Bus)?;
+        pipeline.set_state(gst::State::Playing)?;
+
+        let mut ranges = Vec::new();
+        let mut silent_since: Option<Duration> = None;
+        let mut last_end = Duration::ZERO;
+        let close = |since: Option<Duration>, end: Duration, ranges: &mut Vec<TimeRange>| {
+            if let Some(start) = since
+                && end.saturating_sub(start) >= min_duration
+            {
+                ranges.push(TimeRange { start, end });
+            }
+        };
+
+        for msg in bus.iter_timed(gst::ClockTime::NONE) {
+            match msg.view() {
+                gst::MessageView::Eos(_) => break,
+                gst::MessageView::Error(err) => {
+                    return Err(Error::from_bus(err, Some(uri.as_str())));
+                }
+                gst::MessageView::Element(element) => {
+                    let Some(s) = element.structure() else {
+                        continue;
+                    };
+                    if !s.has_name("level") {
+                        continue;
+                    }
+                    let (Ok(start), Ok(duration)) =
+                        (s.get::<u64>("stream-time"), s.get::<u64>("duration"))
+                    else {
+                        continue;
+                    };
+                    let start = Duration::from_nanos(start);
+                    last_end = start + Duration::from_nanos(duration);
+                    let silent = s.get::<glib::ValueArray>("peak").is_ok_and(|peaks| {
+                        peaks
+                            .iter()
+                            .filter_map(|value| value.get::<f64>().ok())
+                            .all(|peak| peak < threshold_db)
+                    });
+                    match (silent, silent_since) {
+                        (true, None) => silent_since = Some(start),
+                        (false, Some(_)) => {
+                            close(silent_since.take(), start, &mut ranges);
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+        // Silence running to the end of the media.
+        close(silent_since, last_end, &mut ranges);
+        Ok(ranges)
+    })();
+
+    if let Err(err) = pipeline.set_state(gst::State::Null) {
+        log::error!("failed to shut down silence detection pipeline: {}", err);
+    }
+    result
+}
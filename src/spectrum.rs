@@ -0,0 +1,298 @@
+use crate::video::Video;
+use gpui::{
+    Element, ElementId, GlobalElementId, Hsla, InspectorElementId, IntoElement, LayoutId, Window,
+};
+use gstreamer as gst;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Lowest magnitude (in dB) reported by the `spectrum` element. Anything quieter is clamped.
+pub(crate) const SPECTRUM_THRESHOLD_DB: i32 = -80;
+
+/// Interval between spectrum messages posted on the bus.
+pub(crate) const SPECTRUM_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Build the `spectrum` element inserted as playbin's `audio-filter`.
+pub(crate) fn make_spectrum_element(bands: u32) -> Result<gst::Element, glib::BoolError> {
+    gst::ElementFactory::make("spectrum")
+        .name("gpui_spectrum")
+        .property("bands", bands)
+        .property("threshold", SPECTRUM_THRESHOLD_DB)
+        .property("interval", SPECTRUM_INTERVAL.as_nanos() as u64)
+        .property("post-messages", true)
+        .build()
+}
+
+/// Spectrum messages are posted as soon as the audio is analyzed, which is ahead of
+/// what the sink is actually playing. Queue them by stream time and only release a
+/// frame once the playback position has reached it.
+#[derive(Debug, Default)]
+pub(crate) struct SpectrumState {
+    pending: VecDeque<(Duration, Vec<f32>)>,
+    current: Vec<f32>,
+}
+
+impl SpectrumState {
+    /// Parse a `spectrum` element message and queue it. Returns false for unrelated messages.
+    pub(crate) fn push_message(&mut self, s: &gst::StructureRef) -> bool {
+        if !s.has_name("spectrum") {
+            return false;
+        }
+        let Ok(stream_time) = s.get::<u64>("stream-time") else {
+            return true;
+        };
+        let Ok(magnitude) = s.get::<gst::List>("magnitude") else {
+            return true;
+        };
+        let magnitude = magnitude
+            .iter()
+            .filter_map(|value| value.get::<f32>().ok())
+            .collect();
+        self.pending
+            .push_back((Duration::from_nanos(stream_time), magnitude));
+        true
+    }
+
+    /// Release every queued frame whose stream time has been reached and return the latest.
+    pub(crate) fn advance(&mut self, position: Duration) -> Vec<f32> {
+        self.release(position);
+        self.current.clone()
+    }
+
+    /// Release every queued frame whose stream time has been reached.
+    pub(crate) fn release(&mut self, position: Duration) {
+        while let Some((at, _)) = self.pending.front()
+            && *at <= position
+        {
+            if let Some((_, magnitude)) = self.pending.pop_front() {
+                self.current = magnitude;
+            }
+        }
+    }
+
+    /// Whether any frames are waiting for playback to reach them.
+    pub(crate) fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.pending.clear();
+        self.current.clear();
+    }
+}
+
+/// An audio visualizer drawing spectrum bars for a [`Video`]'s audio track.
+///
+/// Requires the video to be created with [`VideoOptions::spectrum_bands`](crate::VideoOptions::spectrum_bands).
+pub struct SpectrumElement {
+    video: Video,
+    bands: usize,
+    smoothing: f32,
+    gradient: (Hsla, Hsla),
+    gap: gpui::Pixels,
+    display_width: Option<gpui::Pixels>,
+    display_height: Option<gpui::Pixels>,
+    element_id: Option<ElementId>,
+}
+
+impl SpectrumElement {
+    pub fn new(video: Video) -> Self {
+        Self {
+            video,
+            bands: 32,
+            smoothing: 0.6,
+            gradient: (
+                gpui::hsla(0.33, 0.8, 0.5, 1.0),
+                gpui::hsla(0.0, 0.8, 0.5, 1.0),
+            ),
+            gap: gpui::px(1.0),
+            display_width: None,
+            display_height: None,
+            element_id: None,
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<ElementId>) -> Self {
+        self.element_id = Some(id.into());
+        self
+    }
+
+    /// Number of bars to draw. Analyzer bands are averaged into this many bars.
+    pub fn bands(mut self, bands: usize) -> Self {
+        self.bands = bands.max(1);
+        self
+    }
+
+    /// Exponential smoothing factor in `0.0..1.0`; higher values make bars fall slower.
+    pub fn smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing.clamp(0.0, 0.99);
+        self
+    }
+
+    /// Colors of the lowest and highest frequency bars; bars in between are interpolated.
+    pub fn gradient(mut self, low: impl Into<Hsla>, high: impl Into<Hsla>) -> Self {
+        self.gradient = (low.into(), high.into());
+        self
+    }
+
+    /// Horizontal spacing between bars.
+    pub fn gap(mut self, gap: gpui::Pixels) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn size(mut self, width: gpui::Pixels, height: gpui::Pixels) -> Self {
+        self.display_width = Some(width);
+        self.display_height = Some(height);
+        self
+    }
+
+    /// Average raw analyzer magnitudes into `self.bands` levels normalized to `0.0..=1.0`.
+    fn levels(&self, magnitude: &[f32]) -> Vec<f32> {
+        let floor = SPECTRUM_THRESHOLD_DB as f32;
+        (0..self.bands)
+            .map(|i| {
+                let start = i * magnitude.len() / self.bands;
+                let end = ((i + 1) * magnitude.len() / self.bands).max(start + 1);
+                let Some(slice) = magnitude.get(start..end.min(magnitude.len())) else {
+                    return 0.0;
+                };
+                if slice.is_empty() {
+                    return 0.0;
+                }
+                let db = slice.iter().sum::<f32>() / slice.len() as f32;
+                ((db - floor) / -floor).clamp(0.0, 1.0)
+            })
+            .collect()
+    }
+
+    fn color_at(&self, t: f32) -> Hsla {
+        let (low, high) = self.gradient;
+        Hsla {
+            h: low.h + (high.h - low.h) * t,
+            s: low.s + (high.s - low.s) * t,
+            l: low.l + (high.l - low.l) * t,
+            a: low.a + (high.a - low.a) * t,
+        }
+    }
+}
+
+impl Element for SpectrumElement {
+    type RequestLayoutState = ();
+    type PrepaintState = ();
+
+    fn id(&self) -> Option<ElementId> {
+        self.element_id.clone()
+    }
+
+    fn source_location(&self) -> Option<&'static core::panic::Location<'static>> {
+        None
+    }
+
+    fn request_layout(
+        &mut self,
+        _global_id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        window: &mut Window,
+        cx: &mut gpui::App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let length = |value: Option<gpui::Pixels>| match value {
+            Some(px) => gpui::Length::Definite(gpui::DefiniteLength::Absolute(
+                gpui::AbsoluteLength::Pixels(px),
+            )),
+            None => gpui::relative(1.0).into(),
+        };
+        let style = gpui::Style {
+            size: gpui::Size {
+                width: length(self.display_width),
+                height: length(self.display_height),
+            },
+            ..Default::default()
+        };
+
+        let layout_id = window.request_layout(style, [], cx);
+        (layout_id, ())
+    }
+
+    fn prepaint(
+        &mut self,
+        _global_id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        _bounds: gpui::Bounds<gpui::Pixels>,
+        _request_layout_state: &mut Self::RequestLayoutState,
+        window: &mut Window,
+        _cx: &mut gpui::App,
+    ) -> Self::PrepaintState {
+        if !self.video.eos() && !self.video.paused() {
+            window.request_animation_frame();
+        }
+    }
+
+    fn paint(
+        &mut self,
+        _global_id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        bounds: gpui::Bounds<gpui::Pixels>,
+        _request_layout_state: &mut Self::RequestLayoutState,
+        _prepaint_state: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut gpui::App,
+    ) {
+        let target = self.levels(&self.video.spectrum());
+        // Keyed by the video, so two visualizers in one view do not share their bars.
+        let key = self
+            .element_id
+            .clone()
+            .unwrap_or_else(|| ElementId::NamedInteger("spectrum".into(), self.video.read().id));
+        let smoothed_state: gpui::Entity<Vec<f32>> =
+            window.use_keyed_state(key, cx, |_, _| Vec::new());
+        let smoothing = self.smoothing;
+        let levels = smoothed_state.update(cx, |previous, _| {
+            previous.resize(target.len(), 0.0);
+            for (prev, next) in previous.iter_mut().zip(&target) {
+                *prev = *prev * smoothing + next * (1.0 - smoothing);
+            }
+            previous.clone()
+        });
+
+        let total_w: f32 = bounds.size.width.into();
+        let total_h: f32 = bounds.size.height.into();
+        let gap: f32 = self.gap.into();
+        let count = levels.len().max(1) as f32;
+        let bar_w = ((total_w - gap * (count - 1.0)) / count).max(1.0);
+
+        for (i, level) in levels.iter().enumerate() {
+            let bar_h = total_h * level;
+            if bar_h <= 0.0 {
+                continue;
+            }
+            let x = (bar_w + gap) * i as f32;
+            let t = if levels.len() > 1 {
+                i as f32 / (levels.len() - 1) as f32
+            } else {
+                0.0
+            };
+            let bar = gpui::Bounds::new(
+                gpui::point(
+                    bounds.origin.x + gpui::px(x),
+                    bounds.origin.y + gpui::px(total_h - bar_h),
+                ),
+                gpui::size(gpui::px(bar_w), gpui::px(bar_h)),
+            );
+            window.paint_quad(gpui::fill(bar, self.color_at(t)));
+        }
+    }
+}
+
+impl IntoElement for SpectrumElement {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+/// Helper function to create a spectrum visualizer element
+pub fn spectrum(video: Video) -> SpectrumElement {
+    SpectrumElement::new(video)
+}
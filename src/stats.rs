@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Number of recent frames and conversions kept for the pacing graph.
+pub(crate) const STATS_HISTORY: usize = 120;
+
+/// Recent frame pacing of a [`Video`](crate::Video), from
+/// [`Video::playback_stats`](crate::Video::playback_stats).
+///
+/// Each history holds the last 120 entries, oldest first. This is
+/// what [`VideoElement::debug_overlay`](crate::VideoElement::debug_overlay) graphs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaybackStats {
+    /// Time between consecutive decoded frames.
+    pub frame_intervals: VecDeque<Duration>,
+    /// Frames dropped for quality of service just before each decoded frame,
+    /// aligned with `frame_intervals`.
+    pub dropped_frames: VecDeque<u64>,
+    /// Time spent converting frames for painting.
+    pub conversion_times: VecDeque<Duration>,
+    /// Time from taking each painted frame from the video sink to handing it to
+    /// gpui, covering buffering and conversion.
+    pub paint_latencies: VecDeque<Duration>,
+    /// Time each frame spent in the video decoder, from its compressed buffer
+    /// going in to the decoded frame coming out. This includes any frames the
+    /// decoder holds back to reorder, so it is at least one frame for streams
+    /// with B-frames.
+    pub decode_times: VecDeque<Duration>,
+    /// Frames dropped since the video was opened.
+    pub dropped_total: u64,
+    /// Frames taken from the video sink since the video was opened.
+    pub decoded_total: u64,
+    // Part of `dropped_total` already attributed to a frame.
+    attributed_drops: u64,
+    // Compressed buffers in the decoder, by timestamp, and when each went in.
+    decoding: VecDeque<(u64, Instant)>,
+    // Timestamp and size of recent compressed buffers, for the bitrate.
+    input: VecDeque<(u64, usize)>,
+}
+
+/// A summary of a [`Video`](crate::Video)'s decoding performance, from
+/// [`Video::stats`](crate::Video::stats).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VideoStats {
+    /// Frames taken from the video sink since the video was opened.
+    pub decoded_frames: u64,
+    /// Frames dropped for quality of service since the video was opened, as
+    /// reported by the pipeline's QoS messages.
+    pub dropped_frames: u64,
+    /// Average time recent frames spent in the decoder; see
+    /// [`PlaybackStats::decode_times`]. None before any frame was timed.
+    pub mean_decode_time: Option<Duration>,
+    /// Frames decoded per second, averaged over recent frames.
+    pub fps: f64,
+    /// Frames waiting in the frame buffer.
+    pub queue_depth: usize,
+    /// Compressed video bits per second of media time, measured over recent
+    /// buffers going into the decoder. None until two have been seen.
+    pub bitrate: Option<u64>,
+}
+
+impl PlaybackStats {
+    /// Average of the recorded frame intervals, if any.
+    pub fn mean_frame_interval(&self) -> Option<Duration> {
+        let count = self.frame_intervals.len() as u32;
+        (count > 0).then(|| self.frame_intervals.iter().sum::<Duration>() / count)
+    }
+
+    /// Frames per second over the recorded frame intervals, or 0.0 if there are none.
+    pub fn fps(&self) -> f64 {
+        self.mean_frame_interval()
+            .filter(|interval| !interval.is_zero())
+            .map_or(0.0, |interval| 1.0 / interval.as_secs_f64())
+    }
+
+    /// Average of the recorded decode times, if any.
+    pub fn mean_decode_time(&self) -> Option<Duration> {
+        let count = self.decode_times.len() as u32;
+        (count > 0).then(|| self.decode_times.iter().sum::<Duration>() / count)
+    }
+
+    /// Compressed bits per second of media time over the recent decoder input,
+    /// if it spans any time.
+    pub fn bitrate(&self) -> Option<u64> {
+        let first = self.input.iter().map(|(pts, _)| *pts).min()?;
+        let last = self.input.iter().map(|(pts, _)| *pts).max()?;
+        if last == first {
+            return None;
+        }
+        let bytes: usize = self.input.iter().map(|(_, size)| size).sum();
+        Some((bytes as u128 * 8 * 1_000_000_000 / u128::from(last - first)) as u64)
+    }
+
+    /// Average of the recorded paint latencies, if any.
+    pub fn mean_paint_latency(&self) -> Option<Duration> {
+        let count = self.paint_latencies.len() as u32;
+        (count > 0).then(|| self.paint_latencies.iter().sum::<Duration>() / count)
+    }
+
+    pub(crate) fn record_frame(&mut self, interval: Duration) {
+        let dropped = self.dropped_total.saturating_sub(self.attributed_drops);
+        self.attributed_drops = self.dropped_total;
+        self.decoded_total += 1;
+        push_bounded(&mut self.frame_intervals, interval);
+        push_bounded(&mut self.dropped_frames, dropped);
+    }
+
+    pub(crate) fn record_conversion(&mut self, time: Duration) {
+        push_bounded(&mut self.conversion_times, time);
+    }
+
+    /// A compressed buffer with timestamp `pts` (in nanoseconds) and `size`
+    /// bytes went into the decoder.
+    pub(crate) fn record_decoder_input(&mut self, pts: u64, size: usize) {
+        push_bounded(&mut self.decoding, (pts, Instant::now()));
+        push_bounded(&mut self.input, (pts, size));
+    }
+
+    /// A decoded frame with timestamp `pts` came out of the decoder. Frames are
+    /// matched by timestamp, since decoders reorder them.
+    pub(crate) fn record_decoder_output(&mut self, pts: u64) {
+        if let Some(index) = self.decoding.iter().position(|(input, _)| *input == pts)
+            && let Some((_, started)) = self.decoding.remove(index)
+        {
+            push_bounded(&mut self.decode_times, started.elapsed());
+        }
+    }
+
+    /// Forget buffers in the decoder, which a seek or flush discards.
+    pub(crate) fn clear_decoding(&mut self) {
+        self.decoding.clear();
+        self.input.clear();
+    }
+
+    pub(crate) fn record_paint_latency(&mut self, latency: Duration) {
+        push_bounded(&mut self.paint_latencies, latency);
+    }
+
+    /// QoS messages report running totals, so drops are attributed to the next
+    /// frame as the difference.
+    pub(crate) fn set_dropped_total(&mut self, total: u64) {
+        self.dropped_total = total;
+    }
+}
+
+fn push_bounded<T>(history: &mut VecDeque<T>, value: T) {
+    if history.len() == STATS_HISTORY {
+        history.pop_front();
+    }
+    history.push_back(value);
+}
@@ -0,0 +1,183 @@
+//! Finding subtitles for media that has none, through an app-provided service.
+//!
+//! The crate does the plumbing: it notices that a video has no subtitle stream,
+//! describes the media to the registered [`SubtitleProvider`], and loads what it
+//! returns. Talking to a subtitle service is left to the app.
+
+use crate::event::VideoEvent;
+use crate::video::{Internal, Video};
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use parking_lot::{Mutex, RwLock};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+/// Bytes hashed at each end of the file by [`SubtitleQuery::hash`].
+const HASH_CHUNK: u64 = 64 * 1024;
+
+/// How long the lookup waits for the video to preroll before checking its streams.
+const PREROLL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What is known about a video when its subtitles are looked up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubtitleQuery {
+    pub uri: url::Url,
+    /// Last path segment of the URI, e.g. `Some("episode.01.mkv")`.
+    pub file_name: Option<String>,
+    /// Size in bytes, for local files.
+    pub file_size: Option<u64>,
+    /// The OpenSubtitles movie hash, for local files of at least 64 KiB: the file
+    /// size plus the 64-bit little-endian words of its first and last 64 KiB,
+    /// wrapping. Most subtitle services index by it.
+    pub hash: Option<u64>,
+    pub duration: Duration,
+}
+
+/// Subtitles a [`SubtitleProvider`] found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubtitleMatch {
+    /// Where to load them from; usually a file the provider downloaded, but any
+    /// URI GStreamer can read works. SubRip, WebVTT, and ASS/SSA are supported.
+    pub uri: url::Url,
+    /// Language code, e.g. `Some("en")`, passed on in [`VideoEvent::SubtitlesFound`].
+    pub language: Option<String>,
+    /// Release or file name, passed on in [`VideoEvent::SubtitlesFound`].
+    pub name: Option<String>,
+}
+
+/// A client for a subtitle service, registered with [`set_subtitle_provider`].
+///
+/// Lookups run on a background thread, so `find` may block on network requests.
+pub trait SubtitleProvider: Send + Sync + 'static {
+    /// Look up subtitles for the described media. Return None when there are none,
+    /// or when the lookup failed; the provider is expected to log why.
+    fn find(&self, query: &SubtitleQuery) -> Option<SubtitleMatch>;
+}
+
+static PROVIDER: Mutex<Option<Arc<dyn SubtitleProvider>>> = Mutex::new(None);
+
+/// Register a [`SubtitleProvider`] asked for subtitles for every video created from
+/// then on whose media has no subtitle stream, or remove it with `None`.
+///
+/// Found subtitles are loaded with [`Video::set_subtitle_uri`] and reported as
+/// [`VideoEvent::SubtitlesFound`]; otherwise [`VideoEvent::SubtitlesNotFound`] is
+/// sent. Live streams and pipelines other than playbin are not looked up.
+pub fn set_subtitle_provider(provider: Option<Arc<dyn SubtitleProvider>>) {
+    *PROVIDER.lock() = provider;
+}
+
+/// The [`SubtitleProvider`] registered with [`set_subtitle_provider`], if any.
+pub fn subtitle_provider() -> Option<Arc<dyn SubtitleProvider>> {
+    PROVIDER.lock().clone()
+}
+
+/// Look up subtitles for a new video on a background thread, if a provider is
+/// registered and the media turns out to have none.
+pub(crate) fn lookup(video: &Video) {
+    let Some(provider) = subtitle_provider() else {
+        return;
+    };
+    let weak = Arc::downgrade(&video.0);
+    let spawned = std::thread::Builder::new()
+        .name("gpui-video-subtitles".into())
+        .spawn(move || {
+            // The video is only held while it is inspected or changed, so a slow
+            // lookup does not keep a video the app dropped alive.
+            let Some(query) = upgrade(&weak).and_then(|video| query(&video)) else {
+                return;
+            };
+            let found = provider.find(&query);
+            let Some(video) = upgrade(&weak) else {
+                return;
+            };
+            let event = match found {
+                Some(found) => match video.set_subtitle_uri(&found.uri) {
+                    Ok(()) => VideoEvent::SubtitlesFound {
+                        language: found.language,
+                        name: found.name,
+                    },
+                    Err(err) => {
+                        log::warn!("failed to load subtitles from {}: {err}", found.uri);
+                        VideoEvent::SubtitlesNotFound
+                    }
+                },
+                None => VideoEvent::SubtitlesNotFound,
+            };
+            let events = Arc::clone(&video.read().events);
+            events.emit(event);
+        });
+    if let Err(err) = spawned {
+        log::warn!("failed to start the subtitle lookup: {err}");
+    }
+}
+
+fn upgrade(video: &Weak<RwLock<Internal>>) -> Option<Video> {
+    video.upgrade().map(Video)
+}
+
+/// Describe `video` for a lookup, or None if it needs no subtitles.
+fn query(video: &Video) -> Option<SubtitleQuery> {
+    let pipeline = video.read().source.clone();
+    if video.is_live() || pipeline.find_property("suburi").is_none() {
+        return None;
+    }
+    // Streams are only known once the pipeline has prerolled.
+    if pipeline
+        .state(gst::ClockTime::from_nseconds(
+            PREROLL_TIMEOUT.as_nanos() as u64
+        ))
+        .0
+        .is_err()
+    {
+        return None;
+    }
+    let has_subtitles = !video.subtitle_tracks().is_empty()
+        || pipeline.property::<Option<String>>("suburi").is_some();
+    if has_subtitles {
+        return None;
+    }
+
+    let uri = video.read().uri()?;
+    let file_name = uri
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string);
+    let (file_size, hash) = match uri.to_file_path() {
+        Ok(path) => file_hash(&path).unwrap_or_else(|err| {
+            log::debug!("failed to hash {}: {err}", path.display());
+            (None, None)
+        }),
+        Err(()) => (None, None),
+    };
+    Some(SubtitleQuery {
+        uri,
+        file_name,
+        file_size,
+        hash,
+        duration: video.duration(),
+    })
+}
+
+/// Size and OpenSubtitles hash of the file at `path`; the hash is None for files
+/// smaller than one chunk.
+fn file_hash(path: &Path) -> std::io::Result<(Option<u64>, Option<u64>)> {
+    let mut file = std::fs::File::open(path)?;
+    let size = file.metadata()?.len();
+    if size < HASH_CHUNK {
+        return Ok((Some(size), None));
+    }
+    let mut hash = size;
+    let mut chunk = vec![0; HASH_CHUNK as usize];
+    for offset in [0, size - HASH_CHUNK] {
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut chunk)?;
+        for word in chunk.chunks_exact(8) {
+            let word = u64::from_le_bytes(word.try_into().unwrap_or_default());
+            hash = hash.wrapping_add(word);
+        }
+    }
+    Ok((Some(size), Some(hash)))
+}
@@ -0,0 +1,87 @@
+use gpui::{App, Global, Hsla, Pixels};
+
+/// Colors and metrics used by the crate's built-in widgets (controls, seek bar,
+/// subtitles, overlays).
+///
+/// Install one with [`set_player_theme`]. With the `gpui-component` feature enabled,
+/// [`PlayerTheme::from_gpui_component`] derives a theme from the host app's design tokens.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerTheme {
+    /// Text and icon color on top of overlays.
+    pub foreground: Hsla,
+    /// Background of control bars and badges drawn over the video.
+    pub overlay: Hsla,
+    /// Played portion of the seek bar and other highlights.
+    pub accent: Hsla,
+    /// Unplayed portion of the seek bar.
+    pub track: Hsla,
+    /// Downloaded-but-unplayed portion of the seek bar.
+    pub buffered: Hsla,
+    /// Subtitle text color.
+    pub subtitle_foreground: Hsla,
+    /// Box drawn behind subtitle text.
+    pub subtitle_background: Hsla,
+    /// Corner radius of overlays and the seek bar.
+    pub radius: Pixels,
+    /// Base font size for labels and subtitles.
+    pub font_size: Pixels,
+}
+
+impl Default for PlayerTheme {
+    fn default() -> Self {
+        Self {
+            foreground: gpui::white(),
+            overlay: gpui::black().opacity(0.6),
+            accent: gpui::hsla(0.58, 0.9, 0.55, 1.0),
+            track: gpui::white().opacity(0.25),
+            buffered: gpui::white().opacity(0.45),
+            subtitle_foreground: gpui::white(),
+            subtitle_background: gpui::black().opacity(0.7),
+            radius: gpui::px(6.0),
+            font_size: gpui::px(14.0),
+        }
+    }
+}
+
+#[cfg(feature = "gpui-component")]
+impl PlayerTheme {
+    /// Build a theme from gpui-component's active theme tokens.
+    pub fn from_gpui_component(cx: &App) -> Self {
+        use gpui_component::ActiveTheme as _;
+
+        let theme = cx.theme();
+        Self {
+            foreground: theme.foreground,
+            overlay: theme.background.opacity(0.75),
+            accent: theme.primary,
+            track: theme.muted,
+            buffered: theme.muted_foreground.opacity(0.5),
+            subtitle_foreground: theme.foreground,
+            subtitle_background: theme.background.opacity(0.8),
+            radius: theme.radius,
+            font_size: theme.font_size,
+        }
+    }
+}
+
+impl Global for PlayerTheme {}
+
+/// Install the [`PlayerTheme`] used by the crate's widgets in this app.
+pub fn set_player_theme(cx: &mut App, theme: PlayerTheme) {
+    cx.set_global(theme);
+}
+
+/// The [`PlayerTheme`] registered with [`set_player_theme`], or the default theme.
+///
+/// With the `gpui-component` feature enabled and no explicit theme set, the theme
+/// is derived from gpui-component's tokens.
+pub fn player_theme(cx: &App) -> PlayerTheme {
+    if let Some(theme) = cx.try_global::<PlayerTheme>() {
+        return theme.clone();
+    }
+    #[cfg(feature = "gpui-component")]
+    if cx.has_global::<gpui_component::Theme>() {
+        return PlayerTheme::from_gpui_component(cx);
+    }
+    PlayerTheme::default()
+}
@@ -0,0 +1,139 @@
+use std::cell::Cell;
+use std::sync::OnceLock;
+use std::thread::ThreadId;
+
+thread_local! {
+    /// Set on video worker threads, which run event callbacks.
+    static IS_WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// gpui's foreground thread, recorded when a video element is first painted.
+static UI_THREAD: OnceLock<ThreadId> = OnceLock::new();
+
+pub(crate) fn mark_worker_thread() {
+    IS_WORKER.set(true);
+}
+
+pub(crate) fn mark_ui_thread() {
+    UI_THREAD.get_or_init(|| std::thread::current().id());
+}
+
+/// Catch blocking [`Video`](crate::Video) calls where they do harm: on the UI thread
+/// they stall rendering, and in a worker-thread event callback they wait on the
+/// thread that would have to make progress.
+pub(crate) fn debug_assert_may_block(method: &str) {
+    debug_assert!(
+        !IS_WORKER.get(),
+        "Video::{method} blocks and must not be called from an event callback"
+    );
+    debug_assert!(
+        UI_THREAD.get() != Some(&std::thread::current().id()),
+        "Video::{method} blocks and must not be called on the UI thread"
+    );
+}
+
+/// Scheduling priority of a video's frame worker thread.
+///
+/// Applied on a best-effort basis: raising priority usually requires extra
+/// privileges (e.g. `CAP_SYS_NICE` on Linux), and a failure only logs a warning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WorkerPriority {
+    /// Yield to other work, e.g. for background thumbnails or muted previews.
+    Low,
+    /// Inherit the default priority.
+    #[default]
+    Normal,
+    /// Prefer decoding over other threads, e.g. for kiosks and signage.
+    High,
+}
+
+/// Apply priority and CPU affinity to the calling thread.
+pub(crate) fn configure_current_thread(priority: WorkerPriority, affinity: Option<&[usize]>) {
+    if priority != WorkerPriority::Normal
+        && let Err(err) = set_priority(priority)
+    {
+        log::warn!("failed to set video worker priority to {priority:?}: {err}");
+    }
+    if let Some(cores) = affinity
+        && let Err(err) = set_affinity(cores)
+    {
+        log::warn!("failed to pin video worker to cores {cores:?}: {err}");
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_priority(priority: WorkerPriority) -> std::io::Result<()> {
+    let nice = match priority {
+        WorkerPriority::Low => 10,
+        WorkerPriority::Normal => 0,
+        WorkerPriority::High => -10,
+    };
+    // On Linux, PRIO_PROCESS with a thread id only affects that thread.
+    let result = unsafe {
+        let tid = libc::syscall(libc::SYS_gettid) as libc::id_t;
+        libc::setpriority(libc::PRIO_PROCESS, tid, nice)
+    };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn set_priority(priority: WorkerPriority) -> std::io::Result<()> {
+    unsafe {
+        let thread = libc::pthread_self();
+        let mut policy = 0;
+        let mut param: libc::sched_param = std::mem::zeroed();
+        let result = libc::pthread_getschedparam(thread, &mut policy, &mut param);
+        if result != 0 {
+            return Err(std::io::Error::from_raw_os_error(result));
+        }
+        let min = libc::sched_get_priority_min(policy);
+        let max = libc::sched_get_priority_max(policy);
+        param.sched_priority = match priority {
+            WorkerPriority::Low => min,
+            WorkerPriority::Normal => (min + max) / 2,
+            WorkerPriority::High => max,
+        };
+        let result = libc::pthread_setschedparam(thread, policy, &param);
+        if result != 0 {
+            return Err(std::io::Error::from_raw_os_error(result));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_priority(_priority: WorkerPriority) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(target_os = "linux")]
+fn set_affinity(cores: &[usize]) -> std::io::Result<()> {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_ZERO(&mut set);
+        for &core in cores {
+            // The set has no room for larger indices, and CPU_SET would panic on them.
+            if core >= libc::CPU_SETSIZE as usize {
+                log::warn!(
+                    "ignoring core {core}: affinity only covers the first {} cores",
+                    libc::CPU_SETSIZE
+                );
+                continue;
+            }
+            libc::CPU_SET(core, &mut set);
+        }
+        if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_affinity(_cores: &[usize]) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
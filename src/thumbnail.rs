@@ -0,0 +1,415 @@
+use crate::Error;
+use crate::cache;
+use crate::export::link_decoded_pads;
+use crate::runtime;
+use crate::video::Position;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use gstreamer_app as gst_app;
+use gstreamer_video as gst_video;
+use gstreamer_video::prelude::*;
+use parking_lot::{Condvar, Mutex};
+use smallvec::SmallVec;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long to wait for the thumbnail pipeline to preroll.
+const PREROLL_TIMEOUT: gst::ClockTime = gst::ClockTime::from_seconds(5);
+
+/// How long decoding a whole preview clip may take.
+const PREVIEW_CLIP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A paused `uridecodebin` pipeline that decodes single frames on demand.
+struct FramePipeline {
+    pipeline: gst::Pipeline,
+    sink: gst_app::AppSink,
+}
+
+impl FramePipeline {
+    /// Build the pipeline and preroll it. `format` is a raw 4-channel video format
+    /// such as `RGBA`; when `size` is given frames are scaled to exactly that size.
+    fn new(uri: &url::Url, format: &str, size: Option<(u32, u32)>) -> Result<Self, Error> {
+        runtime::ensure_init()?;
+
+        let pipeline = gst::Pipeline::new();
+        let source = gst::ElementFactory::make("uridecodebin")
+            .property("uri", uri.as_str())
+            .build()?;
+        let convert = gst::ElementFactory::make("videoconvert").build()?;
+        let scale = gst::ElementFactory::make("videoscale").build()?;
+
+        let mut caps = gst::Caps::builder("video/x-raw")
+            .field("format", format)
+            .field("pixel-aspect-ratio", gst::Fraction::new(1, 1));
+        if let Some((width, height)) = size {
+            caps = caps
+                .field("width", width as i32)
+                .field("height", height as i32);
+        }
+        let sink = gst_app::AppSink::builder()
+            .caps(&caps.build())
+            .sync(false)
+            .build();
+
+        pipeline.add_many([&source, &convert, &scale, sink.upcast_ref()])?;
+        gst::Element::link_many([&convert, &scale, sink.upcast_ref()])?;
+
+        let convert_weak = convert.downgrade();
+        source.connect_pad_added(move |_, pad| {
+            let Some(convert) = convert_weak.upgrade() else {
+                return;
+            };
+            let Some(sink_pad) = convert.static_pad("sink") else {
+                return;
+            };
+            if sink_pad.is_linked() {
+                return;
+            }
+            let caps = pad.current_caps().unwrap_or_else(|| pad.query_caps(None));
+            let is_video = caps
+                .structure(0)
+                .is_some_and(|s| s.name().starts_with("video/"));
+            if is_video && let Err(err) = pad.link(&sink_pad) {
+                log::error!("failed to link thumbnail decoder: {}", err);
+            }
+        });
+
+        let frames = Self { pipeline, sink };
+        frames.pipeline.set_state(gst::State::Paused)?;
+        frames.pipeline.state(PREROLL_TIMEOUT).0?;
+        Ok(frames)
+    }
+
+    /// Seek to `position` and copy out the prerolled frame.
+    fn frame_at(
+        &self,
+        position: Position,
+        flags: gst::SeekFlags,
+    ) -> Result<image::RgbaImage, Error> {
+        self.pipeline.seek_simple(
+            gst::SeekFlags::FLUSH | flags,
+            gst::GenericFormattedValue::from(position),
+        )?;
+        self.pipeline.state(PREROLL_TIMEOUT).0?;
+
+        let sample = self.sink.pull_preroll()?;
+        sample_to_rgba(&sample)
+    }
+}
+
+impl Drop for FramePipeline {
+    fn drop(&mut self) {
+        if let Err(err) = self.pipeline.set_state(gst::State::Null) {
+            log::error!("failed to shut down thumbnail pipeline: {}", err);
+        }
+    }
+}
+
+/// Decode a single RGBA frame at `position` from `uri` using a short-lived paused pipeline.
+///
+/// When `size` is given the frame is scaled to exactly that size; otherwise the
+/// natural size is kept.
+pub(crate) fn extract_frame(
+    uri: &url::Url,
+    position: Position,
+    size: Option<(u32, u32)>,
+) -> Result<image::RgbaImage, Error> {
+    FramePipeline::new(uri, "RGBA", size)?.frame_at(position, gst::SeekFlags::ACCURATE)
+}
+
+/// Generates still and animated thumbnails on short-lived secondary pipelines,
+/// leaving playback untouched.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Thumbnailer;
+
+impl Thumbnailer {
+    /// Decode the frame at `position`, scaled to `size` if given.
+    pub fn frame(
+        uri: &url::Url,
+        position: impl Into<Position>,
+        size: Option<(u32, u32)>,
+    ) -> Result<image::RgbaImage, Error> {
+        extract_frame(uri, position.into(), size)
+    }
+
+    /// A short low-resolution frame sequence of `duration` from `start` at `fps`,
+    /// scaled to `size`, e.g. for a hover preview. Frames are BGRA, ready to paint.
+    ///
+    /// Results are cached on disk (see [`cache_dir`](crate::cache_dir)), so only the
+    /// first call per clip decodes. Blocks, so call it from a background thread.
+    pub fn preview_clip(
+        uri: &url::Url,
+        start: Duration,
+        duration: Duration,
+        fps: u32,
+        size: (u32, u32),
+    ) -> Result<Vec<Arc<gpui::RenderImage>>, Error> {
+        let fps = fps.max(1);
+        let dir = cache::cache_dir().join("previews").join(format!(
+            "{}_{}_{}_{fps}_{}x{}",
+            cache::key(uri),
+            start.as_millis(),
+            duration.as_millis(),
+            size.0,
+            size.1
+        ));
+        let frames = match load_preview_frames(&dir) {
+            Some(frames) => frames,
+            None => {
+                let frames = decode_preview_frames(uri, start, duration, fps, size)?;
+                if let Err(err) = save_preview_frames(&dir, &frames) {
+                    log::warn!("failed to cache preview clip in {}: {}", dir.display(), err);
+                }
+                frames
+            }
+        };
+        Ok(frames.into_iter().map(render_image).collect())
+    }
+}
+
+/// Decode `duration` from `start`, resampled to `fps` and scaled to `size`, as BGRA.
+fn decode_preview_frames(
+    uri: &url::Url,
+    start: Duration,
+    duration: Duration,
+    fps: u32,
+    size: (u32, u32),
+) -> Result<Vec<image::RgbaImage>, Error> {
+    runtime::ensure_init()?;
+
+    let pipeline = gst::Pipeline::new();
+    let source = gst::ElementFactory::make("uridecodebin")
+        .property("uri", uri.as_str())
+        .build()?;
+    let convert = gst::ElementFactory::make("videoconvert").build()?;
+    let scale = gst::ElementFactory::make("videoscale").build()?;
+    let rate = gst::ElementFactory::make("videorate").build()?;
+    let caps = gst::Caps::builder("video/x-raw")
+        .field("format", "BGRA")
+        .field("width", size.0 as i32)
+        .field("height", size.1 as i32)
+        .field("pixel-aspect-ratio", gst::Fraction::new(1, 1))
+        .field("framerate", gst::Fraction::new(fps as i32, 1))
+        .build();
+    let sink = gst_app::AppSink::builder().caps(&caps).sync(false).build();
+
+    pipeline.add_many([&source, &convert, &scale, &rate, sink.upcast_ref()])?;
+    gst::Element::link_many([&convert, &scale, &rate, sink.upcast_ref()])?;
+    link_decoded_pads(&source, &convert, "video/");
+
+    let result = (|| -> Result<Vec<image::RgbaImage>, Error> {
+        let bus = pipeline.bus().ok_or(Error::Bus)?;
+        pipeline.set_state(gst::State::Paused)?;
+        pipeline.state(PREROLL_TIMEOUT).0?;
+        pipeline.seek(
+            1.0,
+            gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+            gst::SeekType::Set,
+            gst::ClockTime::from_nseconds(start.as_nanos() as u64),
+            gst::SeekType::Set,
+            gst::ClockTime::from_nseconds((start + duration).as_nanos() as u64),
+        )?;
+        pipeline.set_state(gst::State::Playing)?;
+
+        let deadline = Instant::now() + PREVIEW_CLIP_TIMEOUT;
+        let mut frames = Vec::new();
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let timeout = gst::ClockTime::from_nseconds(remaining.as_nanos() as u64);
+            let Some(sample) = sink.try_pull_sample(timeout) else {
+                // A failed source stops pushing without an EOS, so check the bus
+                // before calling it a timeout.
+                if let Some(msg) = bus.pop_filtered(&[gst::MessageType::Error])
+                    && let gst::MessageView::Error(err) = msg.view()
+                {
+                    return Err(Error::from_bus(err, Some(uri.as_str())));
+                }
+                if sink.is_eos() {
+                    return Ok(frames);
+                }
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    format!(
+                        "decoding a preview clip of {uri} took over {}s",
+                        PREVIEW_CLIP_TIMEOUT.as_secs()
+                    ),
+                )
+                .into());
+            };
+            frames.push(sample_to_rgba(&sample)?);
+        }
+    })();
+
+    if let Err(err) = pipeline.set_state(gst::State::Null) {
+        log::error!("failed to shut down preview clip pipeline: {}", err);
+    }
+    result
+}
+
+/// Frames are stored as PNGs holding BGRA data as-is; only this crate reads them back.
+fn save_preview_frames(dir: &std::path::Path, frames: &[image::RgbaImage]) -> Result<(), Error> {
+    // Write to a scratch directory first so a partial clip is never picked up.
+    let scratch = dir.with_extension("partial");
+    std::fs::create_dir_all(&scratch)?;
+    for (i, frame) in frames.iter().enumerate() {
+        frame.save_with_format(
+            scratch.join(format!("frame_{i:04}.png")),
+            image::ImageFormat::Png,
+        )?;
+    }
+    std::fs::rename(&scratch, dir)?;
+    Ok(())
+}
+
+fn load_preview_frames(dir: &std::path::Path) -> Option<Vec<image::RgbaImage>> {
+    if !dir.is_dir() {
+        return None;
+    }
+    let mut frames = Vec::new();
+    for i in 0.. {
+        let path = dir.join(format!("frame_{i:04}.png"));
+        if !path.exists() {
+            break;
+        }
+        match image::open(&path) {
+            Ok(frame) => frames.push(frame.to_rgba8()),
+            Err(err) => {
+                log::warn!(
+                    "failed to read cached preview frame {}: {}",
+                    path.display(),
+                    err
+                );
+                return None;
+            }
+        }
+    }
+    (!frames.is_empty()).then_some(frames)
+}
+
+#[derive(Default)]
+struct PreviewState {
+    requested: Option<Duration>,
+    latest: Option<(Duration, Arc<gpui::RenderImage>)>,
+    alive: bool,
+}
+
+/// Generates low-resolution preview frames on a background thread from a
+/// secondary pipeline, so scrubbing never disturbs the main playback pipeline.
+///
+/// Only the most recent request is served; older pending ones are dropped.
+pub(crate) struct PreviewGenerator {
+    state: Arc<(Mutex<PreviewState>, Condvar)>,
+    worker: Option<std::thread::JoinHandle<()>>,
+}
+
+impl PreviewGenerator {
+    pub(crate) fn new(uri: url::Url, size: (u32, u32)) -> Self {
+        let state = Arc::new((
+            Mutex::new(PreviewState {
+                alive: true,
+                ..Default::default()
+            }),
+            Condvar::new(),
+        ));
+        let state_ref = Arc::clone(&state);
+        let worker = std::thread::spawn(move || {
+            // GPUI's sprite atlas expects BGRA, so decode straight to it.
+            let frames = match FramePipeline::new(&uri, "BGRA", Some(size)) {
+                Ok(frames) => frames,
+                Err(err) => {
+                    log::error!("failed to start preview thumbnail pipeline: {}", err);
+                    return;
+                }
+            };
+            let (lock, condvar) = &*state_ref;
+            loop {
+                let position = {
+                    let mut state = lock.lock();
+                    while state.alive && state.requested.is_none() {
+                        condvar.wait(&mut state);
+                    }
+                    if !state.alive {
+                        return;
+                    }
+                    state.requested.take()
+                };
+                let Some(position) = position else {
+                    continue;
+                };
+                // Keyframe seeks are much faster and precise enough for a hover preview.
+                match frames.frame_at(position.into(), gst::SeekFlags::KEY_UNIT) {
+                    Ok(frame) => lock.lock().latest = Some((position, render_image(frame))),
+                    Err(err) => log::debug!("failed to generate preview at {position:?}: {err}"),
+                }
+            }
+        });
+        Self {
+            state,
+            worker: Some(worker),
+        }
+    }
+
+    /// Ask for a preview at `position`, replacing any request not yet started.
+    pub(crate) fn request(&self, position: Duration) {
+        let (lock, condvar) = &*self.state;
+        let mut state = lock.lock();
+        if state.latest.as_ref().is_some_and(|(at, _)| *at == position) {
+            return;
+        }
+        state.requested = Some(position);
+        condvar.notify_one();
+    }
+
+    /// Whether a requested preview has not been generated yet.
+    pub(crate) fn is_pending(&self) -> bool {
+        self.state.0.lock().requested.is_some()
+    }
+
+    /// The most recently generated preview and its position.
+    pub(crate) fn latest(&self) -> Option<(Duration, Arc<gpui::RenderImage>)> {
+        self.state.0.lock().latest.clone()
+    }
+}
+
+impl Drop for PreviewGenerator {
+    fn drop(&mut self) {
+        let (lock, condvar) = &*self.state;
+        lock.lock().alive = false;
+        condvar.notify_one();
+        if let Some(worker) = self.worker.take()
+            && worker.join().is_err()
+        {
+            log::error!("preview thumbnail thread panicked");
+        }
+    }
+}
+
+/// Wrap a BGRA frame for painting with GPUI.
+pub(crate) fn render_image(bgra: image::RgbaImage) -> Arc<gpui::RenderImage> {
+    let frames = SmallVec::from_elem(image::Frame::new(bgra), 1);
+    Arc::new(gpui::RenderImage::new(frames))
+}
+
+/// Copy a 4-channel sample into a tightly packed image, honoring the row stride.
+/// The channel order is whatever the sample's caps negotiated.
+pub(crate) fn sample_to_rgba(sample: &gst::Sample) -> Result<image::RgbaImage, Error> {
+    let caps = sample.caps().ok_or(Error::Caps)?;
+    let info = gst_video::VideoInfo::from_caps(caps).map_err(|_| Error::Caps)?;
+    let buffer = sample.buffer().ok_or(Error::Caps)?;
+    let frame = gst_video::VideoFrameRef::from_buffer_ref_readable(buffer, &info)
+        .map_err(|_| Error::Caps)?;
+
+    let width = info.width() as usize;
+    let height = info.height() as usize;
+    let row_len = width * 4;
+    let stride = frame.plane_stride().first().copied().ok_or(Error::Caps)? as usize;
+    let data = frame.plane_data(0).map_err(|_| Error::Caps)?;
+
+    let mut pixels = Vec::with_capacity(row_len * height);
+    for row in 0..height {
+        let start = row * stride;
+        let line = data.get(start..start + row_len).ok_or(Error::Caps)?;
+        pixels.extend_from_slice(line);
+    }
+    image::RgbaImage::from_raw(info.width(), info.height(), pixels).ok_or(Error::Caps)
+}
@@ -0,0 +1,268 @@
+use crate::cache;
+use crate::theme::player_theme;
+use crate::thumbnail::{Thumbnailer, render_image};
+use gpui::{
+    Element, ElementId, GlobalElementId, InspectorElementId, IntoElement, LayoutId, MouseMoveEvent,
+    Pixels, RenderImage, Window,
+};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Default)]
+enum PreviewClip {
+    #[default]
+    Idle,
+    Loading,
+    Ready(Vec<Arc<RenderImage>>),
+    Failed,
+}
+
+/// State kept across frames for one tile.
+#[derive(Default)]
+struct TileState {
+    hovered_since: Option<Instant>,
+    poster: Option<Arc<RenderImage>>,
+    poster_checked: bool,
+    /// Filled by a background thread, hence shared.
+    clip: Arc<Mutex<PreviewClip>>,
+}
+
+/// A gallery thumbnail for a media file that shows its poster frame and plays a
+/// short low-resolution preview clip while hovered.
+///
+/// Neither needs a playback pipeline: the poster comes from the disk cache (see
+/// [`Video::set_poster_time`](crate::Video::set_poster_time)) and the clip from
+/// [`Thumbnailer::preview_clip`], generated on first hover and cached on disk.
+pub struct VideoTile {
+    uri: url::Url,
+    width: Pixels,
+    height: Pixels,
+    preview_start: Duration,
+    preview_duration: Duration,
+    fps: u32,
+    element_id: Option<ElementId>,
+    source_location: &'static core::panic::Location<'static>,
+}
+
+impl VideoTile {
+    #[track_caller]
+    pub fn new(uri: url::Url) -> Self {
+        Self {
+            uri,
+            width: gpui::px(160.0),
+            height: gpui::px(90.0),
+            preview_start: Duration::ZERO,
+            preview_duration: Duration::from_secs(3),
+            fps: 8,
+            element_id: None,
+            source_location: core::panic::Location::caller(),
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<ElementId>) -> Self {
+        self.element_id = Some(id.into());
+        self
+    }
+
+    pub fn size(mut self, width: Pixels, height: Pixels) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Part of the media played on hover. Defaults to the first 3 seconds.
+    pub fn preview(mut self, start: Duration, duration: Duration) -> Self {
+        self.preview_start = start;
+        self.preview_duration = duration;
+        self
+    }
+
+    /// Frame rate of the hover preview. Defaults to 8.
+    pub fn fps(mut self, fps: u32) -> Self {
+        self.fps = fps.max(1);
+        self
+    }
+
+    /// Start generating the preview clip on a background thread.
+    fn load_clip(&self, clip: Arc<Mutex<PreviewClip>>) {
+        *clip.lock() = PreviewClip::Loading;
+        let uri = self.uri.clone();
+        let (start, duration, fps) = (self.preview_start, self.preview_duration, self.fps);
+        // Encoders and scalers prefer even dimensions.
+        let size = (
+            (f32::from(self.width).round() as u32).max(2) & !1,
+            (f32::from(self.height).round() as u32).max(2) & !1,
+        );
+        std::thread::spawn(move || {
+            let result = match Thumbnailer::preview_clip(&uri, start, duration, fps, size) {
+                Ok(frames) if !frames.is_empty() => PreviewClip::Ready(frames),
+                Ok(_) => PreviewClip::Failed,
+                Err(err) => {
+                    log::warn!("failed to generate preview clip for {uri}: {err}");
+                    PreviewClip::Failed
+                }
+            };
+            *clip.lock() = result;
+        });
+    }
+}
+
+impl Element for VideoTile {
+    type RequestLayoutState = ();
+    type PrepaintState = ();
+
+    /// Falls back to an id derived from the media and where the tile was created,
+    /// so each tile of a gallery keeps its own poster, clip, and hover state.
+    fn id(&self) -> Option<ElementId> {
+        Some(self.element_id.clone().unwrap_or_else(|| {
+            ElementId::NamedChild(
+                Box::new(ElementId::CodeLocation(*self.source_location)),
+                cache::key(&self.uri).into(),
+            )
+        }))
+    }
+
+    fn source_location(&self) -> Option<&'static core::panic::Location<'static>> {
+        Some(self.source_location)
+    }
+
+    fn request_layout(
+        &mut self,
+        _global_id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        window: &mut Window,
+        cx: &mut gpui::App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let style = gpui::Style {
+            size: gpui::Size {
+                width: gpui::Length::Definite(gpui::DefiniteLength::Absolute(
+                    gpui::AbsoluteLength::Pixels(self.width),
+                )),
+                height: gpui::Length::Definite(gpui::DefiniteLength::Absolute(
+                    gpui::AbsoluteLength::Pixels(self.height),
+                )),
+            },
+            ..Default::default()
+        };
+
+        let layout_id = window.request_layout(style, [], cx);
+        (layout_id, ())
+    }
+
+    fn prepaint(
+        &mut self,
+        _global_id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        _bounds: gpui::Bounds<Pixels>,
+        _request_layout_state: &mut Self::RequestLayoutState,
+        _window: &mut Window,
+        _cx: &mut gpui::App,
+    ) -> Self::PrepaintState {
+    }
+
+    fn paint(
+        &mut self,
+        _global_id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        bounds: gpui::Bounds<Pixels>,
+        _request_layout_state: &mut Self::RequestLayoutState,
+        _prepaint_state: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut gpui::App,
+    ) {
+        let theme = player_theme(cx);
+        let state: gpui::Entity<TileState> = window.use_state(cx, |_, _| TileState::default());
+
+        if !state.read(cx).poster_checked {
+            let poster = cache::cached_poster(&self.uri).map(|mut poster| {
+                // GPUI's sprite atlas expects BGRA.
+                for pixel in poster.pixels_mut() {
+                    pixel.0.swap(0, 2);
+                }
+                render_image(poster)
+            });
+            state.update(cx, |state, _| {
+                state.poster = poster;
+                state.poster_checked = true;
+            });
+        }
+
+        let (hovered_since, poster, clip) = {
+            let state = state.read(cx);
+            (
+                state.hovered_since,
+                state.poster.clone(),
+                state.clip.clone(),
+            )
+        };
+
+        let mut image = None;
+        if let Some(since) = hovered_since {
+            let mut loading = false;
+            match &*clip.lock() {
+                PreviewClip::Ready(frames) => {
+                    let index = (since.elapsed().as_secs_f32() * self.fps as f32) as usize;
+                    image = frames.get(index % frames.len()).cloned();
+                }
+                PreviewClip::Idle => loading = true,
+                PreviewClip::Loading | PreviewClip::Failed => {}
+            }
+            if loading {
+                self.load_clip(clip.clone());
+            }
+            if !matches!(&*clip.lock(), PreviewClip::Failed) {
+                window.request_animation_frame();
+            }
+        }
+        let image = image.or(poster).or_else(|| match &*clip.lock() {
+            PreviewClip::Ready(frames) => frames.first().cloned(),
+            _ => None,
+        });
+
+        match image {
+            Some(image) => {
+                window
+                    .paint_image(bounds, gpui::Corners::all(theme.radius), image, 0, false)
+                    .ok();
+            }
+            None => window.paint_quad(gpui::fill(bounds, theme.track).corner_radii(theme.radius)),
+        }
+
+        let hover_state = state.clone();
+        window.on_mouse_event(move |event: &MouseMoveEvent, phase, window, cx| {
+            if !phase.bubble() {
+                return;
+            }
+            let hovered = bounds.contains(&event.position);
+            hover_state.update(cx, |state, cx| {
+                if hovered == state.hovered_since.is_some() {
+                    return;
+                }
+                state.hovered_since = hovered.then(Instant::now);
+                // Evict the clip's frames from the sprite atlas once the pointer leaves,
+                // so a gallery of tiles does not grow it without bound.
+                if !hovered && let PreviewClip::Ready(frames) = &*state.clip.lock() {
+                    for frame in frames {
+                        cx.drop_image(frame.clone(), Some(window));
+                    }
+                }
+                cx.notify();
+            });
+        });
+    }
+}
+
+impl IntoElement for VideoTile {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+/// Helper function to create a video tile
+#[track_caller]
+pub fn video_tile(uri: url::Url) -> VideoTile {
+    VideoTile::new(uri)
+}
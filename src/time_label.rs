@@ -0,0 +1,188 @@
+use crate::locale::localizer;
+use crate::repaint;
+use crate::theme::player_theme;
+use crate::video::Video;
+use gpui::{
+    Element, ElementId, GlobalElementId, InspectorElementId, IntoElement, LayoutId, MouseButton,
+    MouseDownEvent, Pixels, SharedString, Window,
+};
+use std::time::Duration;
+
+/// What a [`TimeLabelElement`] shows. Clicking the label cycles through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TimeDisplay {
+    /// The playback position.
+    #[default]
+    Elapsed,
+    /// Time left in the playing direction, from [`Video::remaining`], shown as `-1:05`.
+    Remaining,
+    /// The media duration.
+    Total,
+}
+
+impl TimeDisplay {
+    /// The mode a click switches to.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Elapsed => Self::Remaining,
+            Self::Remaining => Self::Total,
+            Self::Total => Self::Elapsed,
+        }
+    }
+}
+
+/// A playback time label for a [`Video`] that toggles between elapsed, remaining,
+/// and total time when clicked.
+///
+/// Times are formatted by the registered [`Localizer`](crate::Localizer). The label is
+/// as wide as its longest text, so it does not shift neighbors when the mode changes.
+pub struct TimeLabelElement {
+    video: Video,
+    display: TimeDisplay,
+    element_id: Option<ElementId>,
+    source_location: &'static core::panic::Location<'static>,
+}
+
+impl TimeLabelElement {
+    #[track_caller]
+    pub fn new(video: Video) -> Self {
+        Self {
+            video,
+            display: TimeDisplay::default(),
+            element_id: None,
+            source_location: core::panic::Location::caller(),
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<ElementId>) -> Self {
+        self.element_id = Some(id.into());
+        self
+    }
+
+    /// Mode shown until the label is first clicked. Defaults to [`TimeDisplay::Elapsed`].
+    pub fn display(mut self, display: TimeDisplay) -> Self {
+        self.display = display;
+        self
+    }
+
+    fn text(&self, display: TimeDisplay, cx: &gpui::App) -> SharedString {
+        let localizer = localizer(cx);
+        match display {
+            TimeDisplay::Elapsed => localizer.duration(self.video.position()),
+            TimeDisplay::Remaining => {
+                format!("-{}", localizer.duration(self.video.remaining())).into()
+            }
+            TimeDisplay::Total => localizer.duration(self.video.duration()),
+        }
+    }
+}
+
+fn shape(text: SharedString, window: &Window, cx: &gpui::App) -> gpui::ShapedLine {
+    let theme = player_theme(cx);
+    let run = gpui::TextRun {
+        len: text.len(),
+        font: window.text_style().font(),
+        color: theme.foreground,
+        background_color: None,
+        underline: None,
+        strikethrough: None,
+    };
+    window
+        .text_system()
+        .shape_line(text, theme.font_size, &[run], None)
+}
+
+impl Element for TimeLabelElement {
+    type RequestLayoutState = ();
+    type PrepaintState = ();
+
+    /// Falls back to an id derived from the video and where the label was created,
+    /// so labels for different videos keep their own display mode and repaint timer.
+    fn id(&self) -> Option<ElementId> {
+        Some(self.element_id.clone().unwrap_or_else(|| {
+            ElementId::NamedChild(
+                Box::new(ElementId::CodeLocation(*self.source_location)),
+                format!("time-label-{}", self.video.read().id).into(),
+            )
+        }))
+    }
+
+    fn source_location(&self) -> Option<&'static core::panic::Location<'static>> {
+        Some(self.source_location)
+    }
+
+    fn request_layout(
+        &mut self,
+        _global_id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        window: &mut Window,
+        cx: &mut gpui::App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        // The remaining time at the start is the longest text in any mode.
+        let widest = format!("-{}", localizer(cx).duration(self.video.duration()));
+        let width = shape(widest.into(), window, cx).width;
+        let height: Pixels = player_theme(cx).font_size * 1.4;
+        let style = gpui::Style {
+            size: gpui::size(width.into(), height.into()),
+            ..Default::default()
+        };
+        (window.request_layout(style, [], cx), ())
+    }
+
+    fn prepaint(
+        &mut self,
+        _global_id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        _bounds: gpui::Bounds<Pixels>,
+        _request_layout_state: &mut Self::RequestLayoutState,
+        window: &mut Window,
+        cx: &mut gpui::App,
+    ) -> Self::PrepaintState {
+        // Labels show whole seconds.
+        repaint::repaint_on_change(window, cx, &self.video, Some(Duration::from_secs(1)));
+    }
+
+    fn paint(
+        &mut self,
+        _global_id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        bounds: gpui::Bounds<Pixels>,
+        _request_layout_state: &mut Self::RequestLayoutState,
+        _prepaint_state: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut gpui::App,
+    ) {
+        let initial = self.display;
+        let display: gpui::Entity<TimeDisplay> = window.use_state(cx, |_, _| initial);
+        let line = shape(self.text(*display.read(cx), cx), window, cx);
+        if let Err(err) = line.paint(bounds.origin, bounds.size.height, window, cx) {
+            log::debug!("failed to paint time label: {err}");
+        }
+
+        window.on_mouse_event(move |event: &MouseDownEvent, phase, _window, cx| {
+            if phase.bubble()
+                && event.button == MouseButton::Left
+                && bounds.contains(&event.position)
+            {
+                display.update(cx, |display, cx| {
+                    *display = display.next();
+                    cx.notify();
+                });
+            }
+        });
+    }
+}
+
+impl IntoElement for TimeLabelElement {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+/// Helper function to create a time label element
+#[track_caller]
+pub fn time_label(video: Video) -> TimeLabelElement {
+    TimeLabelElement::new(video)
+}
@@ -0,0 +1,382 @@
+use crate::repaint;
+use crate::theme::player_theme;
+use crate::thumbnail::PreviewGenerator;
+use crate::video::Video;
+use gpui::{
+    Element, ElementId, GlobalElementId, InspectorElementId, IntoElement, LayoutId, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, Window,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Height of the hover preview thumbnails; width follows the video's aspect ratio.
+const PREVIEW_HEIGHT: u32 = 90;
+
+/// State kept across frames for one timeline.
+#[derive(Default)]
+struct TimelineState {
+    /// Horizontal pointer offset from the left edge while hovering.
+    hover_x: Option<Pixels>,
+    preview: Option<Arc<PreviewGenerator>>,
+    /// Last thumbnail uploaded to the sprite atlas, dropped once replaced.
+    painted: Option<Arc<gpui::RenderImage>>,
+}
+
+/// A scrubber bar for a [`Video`] showing buffered ranges, the playback position,
+/// chapter markers, and preview thumbnails while hovering. Clicking or dragging seeks;
+/// drags scrub through keyframes (see [`Video::begin_scrub`]) and land exactly on release.
+///
+/// Colors come from the [`PlayerTheme`](crate::PlayerTheme). Thumbnails are decoded by
+/// a separate low-resolution pipeline, so hovering never disturbs playback.
+pub struct TimelineElement {
+    video: Video,
+    chapters: Vec<Duration>,
+    heatmap: Vec<f32>,
+    thumbnails: bool,
+    track_height: Pixels,
+    display_width: Option<Pixels>,
+    display_height: Option<Pixels>,
+    element_id: Option<ElementId>,
+    source_location: &'static core::panic::Location<'static>,
+}
+
+impl TimelineElement {
+    #[track_caller]
+    pub fn new(video: Video) -> Self {
+        Self {
+            video,
+            chapters: Vec::new(),
+            heatmap: Vec::new(),
+            thumbnails: true,
+            track_height: gpui::px(4.0),
+            display_width: None,
+            display_height: None,
+            element_id: None,
+            source_location: core::panic::Location::caller(),
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<ElementId>) -> Self {
+        self.element_id = Some(id.into());
+        self
+    }
+
+    /// Start times of chapters, drawn as markers on the track.
+    pub fn chapters(mut self, chapters: impl IntoIterator<Item = Duration>) -> Self {
+        self.chapters = chapters.into_iter().collect();
+        self
+    }
+
+    /// Per-second activity scores, such as those from
+    /// [`Video::analyze_motion`], drawn as a heat strip above the track. Scores are
+    /// shown relative to the largest one.
+    pub fn heatmap(mut self, scores: impl IntoIterator<Item = f32>) -> Self {
+        self.heatmap = scores.into_iter().collect();
+        self
+    }
+
+    /// Show preview thumbnails while hovering. Defaults to true.
+    pub fn thumbnails(mut self, enabled: bool) -> Self {
+        self.thumbnails = enabled;
+        self
+    }
+
+    /// Thickness of the drawn track; the hit area spans the element's full height.
+    pub fn track_height(mut self, height: Pixels) -> Self {
+        self.track_height = height;
+        self
+    }
+
+    pub fn size(mut self, width: Pixels, height: Pixels) -> Self {
+        self.display_width = Some(width);
+        self.display_height = Some(height);
+        self
+    }
+
+    fn preview_size(&self) -> (u32, u32) {
+        let width = (PREVIEW_HEIGHT as f32 * self.video.aspect_ratio()).round() as u32;
+        // Encoders and scalers prefer even dimensions.
+        (width.max(2) & !1, PREVIEW_HEIGHT)
+    }
+}
+
+/// Map a horizontal offset within `width` to a media time.
+fn time_at(x: Pixels, width: Pixels, duration: Duration) -> Duration {
+    let fraction = (f32::from(x) / f32::from(width).max(1.0)).clamp(0.0, 1.0);
+    duration.mul_f32(fraction)
+}
+
+/// Map a media time to a horizontal offset within `width`.
+fn offset_of(time: Duration, width: Pixels, duration: Duration) -> Pixels {
+    if duration.is_zero() {
+        return gpui::px(0.0);
+    }
+    let fraction = (time.as_secs_f32() / duration.as_secs_f32()).clamp(0.0, 1.0);
+    width * fraction
+}
+
+impl Element for TimelineElement {
+    type RequestLayoutState = ();
+    type PrepaintState = ();
+
+    /// Falls back to an id derived from the video and where the timeline was
+    /// created, so timelines for different videos keep their own hover and drag
+    /// state and repaint timer.
+    fn id(&self) -> Option<ElementId> {
+        Some(self.element_id.clone().unwrap_or_else(|| {
+            ElementId::NamedChild(
+                Box::new(ElementId::CodeLocation(*self.source_location)),
+                format!("timeline-{}", self.video.read().id).into(),
+            )
+        }))
+    }
+
+    fn source_location(&self) -> Option<&'static core::panic::Location<'static>> {
+        Some(self.source_location)
+    }
+
+    fn request_layout(
+        &mut self,
+        _global_id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        window: &mut Window,
+        cx: &mut gpui::App,
+    ) -> (LayoutId, Self::RequestLayoutState) {
+        let width = match self.display_width {
+            Some(px) => gpui::Length::Definite(gpui::DefiniteLength::Absolute(
+                gpui::AbsoluteLength::Pixels(px),
+            )),
+            None => gpui::relative(1.0).into(),
+        };
+        let height = self.display_height.unwrap_or(gpui::px(16.0));
+        let style = gpui::Style {
+            size: gpui::Size {
+                width,
+                height: gpui::Length::Definite(gpui::DefiniteLength::Absolute(
+                    gpui::AbsoluteLength::Pixels(height),
+                )),
+            },
+            ..Default::default()
+        };
+
+        let layout_id = window.request_layout(style, [], cx);
+        (layout_id, ())
+    }
+
+    fn prepaint(
+        &mut self,
+        _global_id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        bounds: gpui::Bounds<Pixels>,
+        _request_layout_state: &mut Self::RequestLayoutState,
+        window: &mut Window,
+        cx: &mut gpui::App,
+    ) -> Self::PrepaintState {
+        // The playhead only needs to move once it would land on another pixel.
+        let width = f32::from(bounds.size.width).max(1.0);
+        let step = self.video.duration().div_f32(width);
+        repaint::repaint_on_change(window, cx, &self.video, Some(step));
+    }
+
+    fn paint(
+        &mut self,
+        _global_id: Option<&GlobalElementId>,
+        _inspector_id: Option<&InspectorElementId>,
+        bounds: gpui::Bounds<Pixels>,
+        _request_layout_state: &mut Self::RequestLayoutState,
+        _prepaint_state: &mut Self::PrepaintState,
+        window: &mut Window,
+        cx: &mut gpui::App,
+    ) {
+        let theme = player_theme(cx);
+        let state: gpui::Entity<TimelineState> =
+            window.use_state(cx, |_, _| TimelineState::default());
+        let duration = self.video.duration();
+        let width = bounds.size.width;
+
+        // Track, buffered ranges, and played portion.
+        let track = gpui::Bounds::new(
+            gpui::point(
+                bounds.origin.x,
+                bounds.origin.y + (bounds.size.height - self.track_height) * 0.5,
+            ),
+            gpui::size(width, self.track_height),
+        );
+        let radius = self.track_height * 0.5;
+        window.paint_quad(gpui::fill(track, theme.track).corner_radii(radius));
+        for range in self.video.buffered_ranges() {
+            let start = offset_of(range.start, width, duration);
+            let end = offset_of(range.end, width, duration);
+            let mut buffered = track;
+            buffered.origin.x += start;
+            buffered.size.width = end - start;
+            window.paint_quad(gpui::fill(buffered, theme.buffered).corner_radii(radius));
+        }
+        let played = offset_of(self.video.position(), width, duration);
+        let mut played_bounds = track;
+        played_bounds.size.width = played;
+        window.paint_quad(gpui::fill(played_bounds, theme.accent).corner_radii(radius));
+
+        for chapter in &self.chapters {
+            let x = offset_of(*chapter, width, duration);
+            let marker = gpui::Bounds::new(
+                gpui::point(track.origin.x + x, track.origin.y),
+                gpui::size(gpui::px(2.0), self.track_height),
+            );
+            window.paint_quad(gpui::fill(marker, theme.foreground));
+        }
+
+        let peak = self.heatmap.iter().copied().fold(0.0_f32, f32::max);
+        if peak > 0.0 && !duration.is_zero() {
+            let strip_top = track.origin.y - self.track_height - gpui::px(1.0);
+            for (second, score) in self.heatmap.iter().enumerate() {
+                if *score <= 0.0 {
+                    continue;
+                }
+                let start = Duration::from_secs(second as u64);
+                let left = offset_of(start, width, duration);
+                let right = offset_of(start + Duration::from_secs(1), width, duration);
+                // Keep every active second visible on long media.
+                let cell = gpui::Bounds::new(
+                    gpui::point(track.origin.x + left, strip_top),
+                    gpui::size((right - left).max(gpui::px(1.0)), self.track_height),
+                );
+                window.paint_quad(gpui::fill(cell, theme.accent.opacity(score / peak)));
+            }
+        }
+
+        let handle_size = self.track_height * 3.0;
+        let handle = gpui::Bounds::new(
+            gpui::point(
+                track.origin.x + played - handle_size * 0.5,
+                track.center().y - handle_size * 0.5,
+            ),
+            gpui::size(handle_size, handle_size),
+        );
+        window.paint_quad(gpui::fill(handle, theme.accent).corner_radii(handle_size * 0.5));
+
+        // Hover preview thumbnail, centered above the pointer and kept within the track.
+        let (hover_x, preview) = {
+            let state = state.read(cx);
+            (state.hover_x, state.preview.clone())
+        };
+        if self.thumbnails
+            && !duration.is_zero()
+            && let Some(x) = hover_x
+        {
+            let preview = match preview {
+                Some(preview) => Some(preview),
+                None => self.video.read().uri().map(|uri| {
+                    let preview = Arc::new(PreviewGenerator::new(uri, self.preview_size()));
+                    state.update(cx, |state, _| state.preview = Some(preview.clone()));
+                    preview
+                }),
+            };
+            if let Some(preview) = preview {
+                preview.request(time_at(x, width, duration));
+                if preview.is_pending() {
+                    window.request_animation_frame();
+                }
+                if let Some((_, image)) = preview.latest() {
+                    let size = image.size(0);
+                    let thumb_size = gpui::size(
+                        gpui::px(size.width.0 as f32),
+                        gpui::px(size.height.0 as f32),
+                    );
+                    let left = (bounds.origin.x + x - thumb_size.width * 0.5)
+                        .max(bounds.origin.x)
+                        .min(bounds.origin.x + width - thumb_size.width);
+                    let thumb = gpui::Bounds::new(
+                        gpui::point(left, bounds.origin.y - thumb_size.height - gpui::px(8.0)),
+                        thumb_size,
+                    );
+                    window.paint_quad(
+                        gpui::fill(thumb.dilate(gpui::px(2.0)), theme.overlay)
+                            .corner_radii(theme.radius),
+                    );
+                    window
+                        .paint_image(
+                            thumb,
+                            gpui::Corners::all(theme.radius),
+                            image.clone(),
+                            0,
+                            false,
+                        )
+                        .ok();
+
+                    let previous =
+                        state.update(cx, |state, _| state.painted.replace(image.clone()));
+                    if let Some(previous) = previous
+                        && !Arc::ptr_eq(&previous, &image)
+                    {
+                        cx.drop_image(previous, Some(window));
+                    }
+                }
+            }
+        }
+
+        let video = self.video.clone();
+        let hover_state = state.clone();
+        window.on_mouse_event(move |event: &MouseMoveEvent, phase, _window, cx| {
+            if !phase.bubble() {
+                return;
+            }
+            let hover_x = bounds
+                .contains(&event.position)
+                .then(|| event.position.x - bounds.origin.x);
+            hover_state.update(cx, |state, cx| {
+                if state.hover_x != hover_x {
+                    state.hover_x = hover_x;
+                    cx.notify();
+                }
+            });
+            if event.pressed_button == Some(MouseButton::Left)
+                && let Some(x) = hover_x
+                && let Err(err) = video.seek(time_at(x, bounds.size.width, video.duration()), false)
+            {
+                log::debug!("timeline scrub failed: {err}");
+            }
+        });
+
+        let video = self.video.clone();
+        window.on_mouse_event(move |event: &MouseDownEvent, phase, _window, _cx| {
+            if !phase.bubble()
+                || event.button != MouseButton::Left
+                || !bounds.contains(&event.position)
+            {
+                return;
+            }
+            // Drags preview keyframes; the exact frame is sought on release.
+            video.begin_scrub();
+            let x = event.position.x - bounds.origin.x;
+            if let Err(err) = video.seek(time_at(x, bounds.size.width, video.duration()), true) {
+                log::debug!("timeline seek failed: {err}");
+            }
+        });
+
+        let video = self.video.clone();
+        window.on_mouse_event(move |event: &MouseUpEvent, phase, _window, _cx| {
+            if phase.bubble()
+                && event.button == MouseButton::Left
+                && video.is_scrubbing()
+                && let Err(err) = video.end_scrub()
+            {
+                log::debug!("timeline seek failed: {err}");
+            }
+        });
+    }
+}
+
+impl IntoElement for TimelineElement {
+    type Element = Self;
+
+    fn into_element(self) -> Self::Element {
+        self
+    }
+}
+
+/// Helper function to create a timeline element
+#[track_caller]
+pub fn timeline(video: Video) -> TimelineElement {
+    TimelineElement::new(video)
+}
@@ -0,0 +1,240 @@
+//! Listing and switching audio and subtitle tracks.
+//!
+//! playbin numbers each kind of stream and switches with its `current-*`
+//! properties. playbin3 and other decodebin3 pipelines post the streams as a
+//! collection instead, and switch with a `select-streams` event naming every
+//! stream to keep playing.
+
+use crate::Error;
+use crate::playbin::{self, PlayFlags};
+use gstreamer as gst;
+use gstreamer::prelude::*;
+
+/// An audio or subtitle track, from [`Video::audio_tracks`](crate::Video::audio_tracks)
+/// or [`Video::subtitle_tracks`](crate::Video::subtitle_tracks).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Track {
+    /// Position among the media's tracks of this kind, to pass to
+    /// [`Video::set_audio_track`](crate::Video::set_audio_track) or
+    /// [`Video::set_subtitle_track`](crate::Video::set_subtitle_track).
+    pub index: usize,
+    /// ISO 639 language code, e.g. `en`, if the container names one.
+    pub language: Option<String>,
+    /// Codec description, e.g. `MPEG-4 AAC` or `SubRip`, if known.
+    pub codec: Option<String>,
+    /// Whether the track is playing.
+    pub selected: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TrackKind {
+    Audio,
+    Subtitle,
+}
+
+impl TrackKind {
+    fn stream_type(self) -> gst::StreamType {
+        match self {
+            Self::Audio => gst::StreamType::AUDIO,
+            Self::Subtitle => gst::StreamType::TEXT,
+        }
+    }
+
+    /// playbin's stream count and current stream properties, and its tag signal.
+    fn playbin_names(self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            Self::Audio => ("n-audio", "current-audio", "get-audio-tags"),
+            Self::Subtitle => ("n-text", "current-text", "get-text-tags"),
+        }
+    }
+
+    fn missing(self, index: usize) -> Error {
+        match self {
+            Self::Audio => Error::AudioStream(index),
+            Self::Subtitle => Error::SubtitleStream(index),
+        }
+    }
+}
+
+/// The streams a decodebin3 pipeline reported, kept up to date by the worker from
+/// `stream-collection` and `streams-selected` messages.
+#[derive(Debug, Default)]
+pub(crate) struct StreamState {
+    pub(crate) collection: Option<gst::StreamCollection>,
+    /// Ids of the streams playing, empty until the first selection is reported.
+    pub(crate) selected: Vec<String>,
+}
+
+fn track(index: usize, tags: Option<&gst::TagListRef>, kind: TrackKind, selected: bool) -> Track {
+    let language = tags
+        .and_then(|tags| tags.get::<gst::tags::LanguageCode>())
+        .map(|code| code.get().to_string());
+    let codec = tags.and_then(|tags| match kind {
+        TrackKind::Audio => tags
+            .get::<gst::tags::AudioCodec>()
+            .map(|codec| codec.get().to_string()),
+        TrackKind::Subtitle => tags
+            .get::<gst::tags::SubtitleCodec>()
+            .map(|codec| codec.get().to_string()),
+    });
+    Track {
+        index,
+        language,
+        codec,
+        selected,
+    }
+}
+
+/// The streams of `kind` in the collection, in order.
+fn collection_streams(
+    collection: &gst::StreamCollection,
+    kind: TrackKind,
+) -> impl Iterator<Item = gst::Stream> + '_ {
+    collection
+        .iter()
+        .filter(move |stream| stream.stream_type().contains(kind.stream_type()))
+}
+
+/// The collection to list and select streams from. Demuxers post collections under
+/// playbin too, but it does not act on `select-streams`, so its properties are used
+/// instead.
+fn collection<'a>(
+    pipeline: &gst::Pipeline,
+    streams: &'a StreamState,
+) -> Option<&'a gst::StreamCollection> {
+    let playbin = pipeline
+        .factory()
+        .is_some_and(|factory| factory.name() == "playbin");
+    streams.collection.as_ref().filter(|_| !playbin)
+}
+
+/// The media's tracks of `kind`, from the stream collection if the pipeline posted
+/// one, else from playbin's properties.
+pub(crate) fn tracks(
+    pipeline: &gst::Pipeline,
+    streams: &StreamState,
+    kind: TrackKind,
+) -> Vec<Track> {
+    if let Some(collection) = collection(pipeline, streams) {
+        return collection_streams(collection, kind)
+            .enumerate()
+            .map(|(index, stream)| {
+                let selected = stream.stream_id().is_some_and(|id| {
+                    streams
+                        .selected
+                        .iter()
+                        .any(|selected| selected == id.as_str())
+                });
+                track(index, stream.tags().as_deref(), kind, selected)
+            })
+            .collect();
+    }
+
+    let (count, current, tags_signal) = kind.playbin_names();
+    if pipeline.find_property(count).is_none() {
+        return Vec::new();
+    }
+    let count = pipeline.property::<i32>(count).max(0);
+    let current = pipeline.property::<i32>(current);
+    // playbin keeps its current subtitle stream while subtitles are turned off.
+    let shown = kind == TrackKind::Audio
+        || playbin::flags(pipeline).is_some_and(|flags| flags.contains(PlayFlags::TEXT));
+    (0..count)
+        .map(|index| {
+            let tags = pipeline.emit_by_name::<Option<gst::TagList>>(tags_signal, &[&index]);
+            track(
+                index as usize,
+                tags.as_deref(),
+                kind,
+                shown && index == current,
+            )
+        })
+        .collect()
+}
+
+/// Play track `index` of `kind`, or for subtitles, none if `index` is None.
+pub(crate) fn select(
+    pipeline: &gst::Pipeline,
+    streams: &StreamState,
+    kind: TrackKind,
+    index: Option<usize>,
+) -> Result<(), Error> {
+    if let Some(collection) = collection(pipeline, streams) {
+        return select_streams(pipeline, streams, collection, kind, index);
+    }
+
+    let (count, current, _) = kind.playbin_names();
+    if pipeline.find_property(count).is_none() {
+        return Err(kind.missing(index.unwrap_or_default()));
+    }
+    if let Some(index) = index {
+        if index >= pipeline.property::<i32>(count).max(0) as usize {
+            return Err(kind.missing(index));
+        }
+        pipeline.set_property(current, index as i32);
+    }
+    if kind == TrackKind::Subtitle
+        && let Some(flags) = playbin::flags(pipeline)
+    {
+        let flags = match index {
+            Some(_) => flags | PlayFlags::TEXT,
+            None => flags.without(PlayFlags::TEXT),
+        };
+        playbin::set_flags(pipeline, flags);
+    }
+    Ok(())
+}
+
+/// Send a `select-streams` event keeping every selected stream of other kinds and
+/// replacing those of `kind` with stream `index`.
+fn select_streams(
+    pipeline: &gst::Pipeline,
+    streams: &StreamState,
+    collection: &gst::StreamCollection,
+    kind: TrackKind,
+    index: Option<usize>,
+) -> Result<(), Error> {
+    let chosen = match index {
+        Some(index) => Some(
+            collection_streams(collection, kind)
+                .nth(index)
+                .and_then(|stream| stream.stream_id())
+                .ok_or_else(|| kind.missing(index))?,
+        ),
+        None => None,
+    };
+
+    let mut ids: Vec<String> = Vec::new();
+    let mut first_of_type = gst::StreamType::empty();
+    for stream in collection.iter() {
+        let stream_type = stream.stream_type();
+        let Some(id) = stream.stream_id() else {
+            continue;
+        };
+        if stream_type.contains(kind.stream_type()) {
+            continue;
+        }
+        // Until decodebin3 reports its selection, assume its default: the first
+        // stream of each type.
+        let keep = if streams.selected.is_empty() {
+            !first_of_type.contains(stream_type)
+        } else {
+            streams
+                .selected
+                .iter()
+                .any(|selected| selected == id.as_str())
+        };
+        first_of_type |= stream_type;
+        if keep {
+            ids.push(id.to_string());
+        }
+    }
+    ids.extend(chosen.map(|id| id.to_string()));
+
+    let event = gst::event::SelectStreams::new(ids.iter().map(String::as_str));
+    if pipeline.send_event(event) {
+        Ok(())
+    } else {
+        Err(Error::StreamSelection)
+    }
+}
@@ -1,14 +1,58 @@
 use crate::Error;
+use crate::animation::{self, AnimatedSource};
+use crate::cache;
+use crate::capabilities;
+use crate::capture::{self, CaptureOptions, DeviceId};
+use crate::chapter::{Chapter, chapters_from_toc};
+use crate::convert::{
+    self, ColorMatrix, ColorOverrides, ColorRange, ColorSpace, FrameLayout, PixelFormat, PixelOrder,
+};
+use crate::debug::{PipelineGuard, WorkerGuard};
+use crate::decoder::{self, DecoderInfo, HwPreference};
+#[cfg(all(target_os = "linux", feature = "dmabuf"))]
+use crate::dmabuf;
+use crate::error::{self, FrameError};
+use crate::event::{EventHub, EventSubscription, PositionWatchers, StreamHealth, VideoEvent};
+use crate::export::{self, AudioFormat, ClipOptions, ImageFormat, SubtitleFormat};
+use crate::filter::{
+    self, AudioFilters, BALANCE_NAME, ColorBalance, EQUALIZER_NAME, KARAOKE_NAME, PITCH_NAME,
+};
+use crate::frames::{FrameExtractor, FrameIter, FrameRef, VideoFrame};
+use crate::governor::{self, DecodeGovernor, DecodePriority};
+use crate::level::{ChannelLevel, LevelState};
+use crate::memory::{self, MemoryTracker, MemoryUsage, SparePixels};
+use crate::metadata::{Metadata, Orientation};
+use crate::motion;
+use crate::network::NetworkTuning;
+use crate::pipeline::{BuiltPipeline, PipelineBuilder, validate_uri, video_sink_pad};
+use crate::playbin::{self, Backend, PlayFlags, PlaybinSettings};
+use crate::power;
+use crate::record::{RecordOptions, Recorder};
+use crate::recovery::{self, RetryPolicy};
+use crate::runtime;
+use crate::silence::{self, DEFAULT_MIN_SILENCE, DEFAULT_SILENCE_THRESHOLD_DB};
+use crate::spectrum::SpectrumState;
+use crate::stats::{PlaybackStats, VideoStats};
+use crate::subtitles;
+use crate::thread::{self, WorkerPriority};
+use crate::thumbnail;
+use crate::tracks::{self, StreamState, Track, TrackKind};
+use crate::watched::{DEFAULT_MILESTONES, WatchTracker};
+use crate::waveform::{self, AudioWindow};
+use futures::StreamExt;
+use futures::channel::mpsc::UnboundedReceiver;
 use gstreamer as gst;
 use gstreamer_app as gst_app;
 use gstreamer_app::prelude::*;
 use gstreamer_video as gst_video;
 // Note: GPUI imports removed since we're using simple Vec<u8> for RGBA data
 use gst::message::MessageView;
-use parking_lot::{Mutex, RwLock};
-use std::collections::VecDeque;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use parking_lot::{Condvar, Mutex, RwLock};
+use std::collections::{HashMap, VecDeque};
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock, Weak};
 use std::time::{Duration, Instant};
 
 /// Position in the media.
@@ -41,16 +85,411 @@ impl From<u64> for Position {
     }
 }
 
+/// A span of media time, e.g. a downloaded portion of a progressive stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeRange {
+    pub start: Duration,
+    pub end: Duration,
+}
+
+/// Frames with at least this many pixels are converted for painting on the worker
+/// thread instead of the render thread.
+const PRECONVERT_MIN_PIXELS: i32 = 2560 * 1440;
+
+/// Buffered frames due within this much of the playback clock are presented now
+/// rather than a display refresh late (half a refresh at 60 Hz).
+const PRESENTATION_SLACK: Duration = Duration::from_millis(8);
+
+/// Jitter buffer latency used for RTSP sources unless `VideoOptions::latency_ms` is set.
+const RTSP_DEFAULT_LATENCY_MS: u32 = 200;
+
+/// Buffering query ranges expressed in `GST_FORMAT_PERCENT` are scaled to this value.
+const PERCENT_MAX: i64 = 1_000_000;
+
+/// Wakes threads blocked in [`Video::wait_for_frame`] when a frame arrives or the
+/// stream ends.
+#[derive(Debug, Default)]
+pub(crate) struct FrameSignal {
+    lock: Mutex<()>,
+    condvar: Condvar,
+}
+
+impl FrameSignal {
+    fn notify(&self) {
+        // Taking the lock orders this after a waiter's last check, so the wakeup
+        // cannot slip in between the check and the wait.
+        let _guard = self.lock.lock();
+        self.condvar.notify_all();
+    }
+}
+
+/// Wait for the next event, then take any others already queued with it. None once
+/// the video is dropped.
+async fn next_events(events: &mut UnboundedReceiver<VideoEvent>) -> Option<Vec<VideoEvent>> {
+    let mut batch = vec![events.next().await?];
+    while let Ok(event) = events.try_recv() {
+        batch.push(event);
+    }
+    Some(batch)
+}
+
+/// Longest wait for a coalesced seek to complete before the next one is issued
+/// anyway, in case its ASYNC_DONE never arrives (e.g. because the seek failed).
+const SEEK_SETTLE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Coalesces [`Video::seek_smooth`] calls: one flushing seek runs at a time, and
+/// only the newest position requested meanwhile is sought once it completes.
+#[derive(Debug, Default)]
+pub(crate) struct SeekCoalescer {
+    state: Mutex<CoalescerState>,
+    // Set once the video is built, so the worker can issue pending seeks.
+    owner: OnceLock<Weak<RwLock<Internal>>>,
+}
+
+#[derive(Debug, Default)]
+struct CoalescerState {
+    in_flight_since: Option<Instant>,
+    pending: Option<Position>,
+}
+
+impl SeekCoalescer {
+    fn bind(&self, owner: Weak<RwLock<Internal>>) {
+        if self.owner.set(owner).is_err() {
+            log::warn!("seek coalescer was bound twice");
+        }
+    }
+
+    /// Whether a seek to `position` may be issued now. Otherwise it replaces the
+    /// pending position and is sought once the running seek completes.
+    fn request(&self, position: Position) -> bool {
+        let mut state = self.state.lock();
+        if state
+            .in_flight_since
+            .is_some_and(|since| since.elapsed() < SEEK_SETTLE_TIMEOUT)
+        {
+            state.pending = Some(position);
+            false
+        } else {
+            state.in_flight_since = Some(Instant::now());
+            state.pending = None;
+            true
+        }
+    }
+
+    /// Forget pending seeks, e.g. because a direct seek supersedes them.
+    fn cancel(&self) {
+        *self.state.lock() = CoalescerState::default();
+    }
+
+    /// The running seek completed; issue the pending one, if any.
+    fn settle(&self) {
+        let pending = {
+            let mut state = self.state.lock();
+            let pending = state.pending.take();
+            state.in_flight_since = pending.is_some().then(Instant::now);
+            pending
+        };
+        let Some(position) = pending else {
+            return;
+        };
+        let Some(owner) = self.owner.get().and_then(Weak::upgrade) else {
+            return;
+        };
+        if let Err(err) = owner.read().seek(position, true) {
+            log::warn!("coalesced seek to {position:?} failed: {err}");
+        }
+    }
+
+    /// Issue the pending seek if the running one has not completed in time.
+    fn settle_stalled(&self) {
+        let stalled = {
+            let state = self.state.lock();
+            state.pending.is_some()
+                && state
+                    .in_flight_since
+                    .is_some_and(|since| since.elapsed() >= SEEK_SETTLE_TIMEOUT)
+        };
+        if stalled {
+            self.settle();
+        }
+    }
+}
+
+/// Minimum time between seeks issued while scrubbing.
+const SCRUB_SEEK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// An interactive scrub started by [`Video::begin_scrub`].
+#[derive(Debug)]
+pub(crate) struct Scrub {
+    /// Whether playback resumes once the scrub ends.
+    resume: bool,
+    /// Latest position asked for, reached exactly when the scrub ends.
+    target: Option<Duration>,
+    /// Whether `target` has not been sought to yet.
+    pending: bool,
+    last_seek: Option<Instant>,
+}
+
+impl Scrub {
+    fn new(resume: bool) -> Self {
+        Self {
+            resume,
+            target: None,
+            pending: false,
+            last_seek: None,
+        }
+    }
+
+    fn set_target(&mut self, target: Duration) {
+        self.target = Some(target);
+        self.pending = true;
+    }
+
+    /// Take the target if it moved and the rate limit allows another seek.
+    fn due(&mut self) -> Option<Duration> {
+        if !self.pending
+            || self
+                .last_seek
+                .is_some_and(|at| at.elapsed() < SCRUB_SEEK_INTERVAL)
+        {
+            return None;
+        }
+        self.pending = false;
+        self.last_seek = Some(Instant::now());
+        self.target
+    }
+}
+
+/// Flush to the keyframe nearest `position` with decoders in keyframe-only trick
+/// mode, which is cheap enough to repeat while a seek bar is dragged.
+fn scrub_seek(pipeline: &gst::Pipeline, position: Duration) -> Result<(), glib::BoolError> {
+    pipeline.seek(
+        1.0,
+        gst::SeekFlags::FLUSH
+            | gst::SeekFlags::KEY_UNIT
+            | gst::SeekFlags::SNAP_BEFORE
+            | gst::SeekFlags::SNAP_AFTER
+            | gst::SeekFlags::TRICKMODE
+            | gst::SeekFlags::TRICKMODE_KEY_UNITS
+            | gst::SeekFlags::TRICKMODE_NO_AUDIO,
+        gst::SeekType::Set,
+        gst::ClockTime::from_nseconds(position.as_nanos() as u64),
+        gst::SeekType::Set,
+        gst::ClockTime::NONE,
+    )
+}
+
+/// Panics in a row after which the worker gives up and stops the pipeline.
+const MAX_CONSECUTIVE_PANICS: u32 = 3;
+
+/// Text of a panic payload, which is a `&str` or `String` for `panic!` with a message.
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown reason".to_string()
+    }
+}
+
+/// Frames held back during reverse playback to restore their order.
+const REVERSE_REORDER_WINDOW: usize = 4;
+
+/// Reverse playback decodes each GOP forwards and pushes it out reversed, so
+/// frames at GOP edges can reach the sink out of order. Holds a few samples and
+/// releases the latest first; anything later than a frame already shown would
+/// step forwards and is dropped.
+#[derive(Default)]
+struct ReverseReorder {
+    pending: Vec<gst::Sample>,
+    last_released: Option<gst::ClockTime>,
+    segment: Option<gst::Segment>,
+}
+
+impl ReverseReorder {
+    fn pts(sample: &gst::Sample) -> Option<gst::ClockTime> {
+        sample.buffer().and_then(|buffer| buffer.pts())
+    }
+
+    /// Add a pulled sample (or `None` on timeout) and return the next one to show.
+    fn push(&mut self, sample: Option<gst::Sample>) -> Option<gst::Sample> {
+        let pulled = sample.is_some();
+        if let Some(sample) = sample {
+            // A new segment means a seek; order restarts from its frames.
+            let segment = sample.segment().cloned();
+            if segment != self.segment {
+                self.clear();
+                self.segment = segment;
+            }
+            let pts = Self::pts(&sample);
+            if let (Some(last), Some(pts)) = (self.last_released, pts)
+                && pts > last
+            {
+                return None;
+            }
+            self.pending.push(sample);
+        }
+        // Release early when nothing new arrives, so the last frames still show.
+        if pulled && self.pending.len() < REVERSE_REORDER_WINDOW {
+            return None;
+        }
+        let (index, _) = self
+            .pending
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, sample)| Self::pts(sample))?;
+        let sample = self.pending.swap_remove(index);
+        self.last_released = Self::pts(&sample);
+        Some(sample)
+    }
+
+    fn clear(&mut self) {
+        self.pending.clear();
+        self.last_released = None;
+    }
+}
+
+/// App-provided timeline that frame presentation follows instead of the pipeline clock.
+#[derive(Clone)]
+pub(crate) struct ExternalClock(Arc<dyn Fn() -> Duration + Send + Sync>);
+
+impl std::fmt::Debug for ExternalClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ExternalClock")
+    }
+}
+
+/// Callback that returns a fresh URL for a source whose signed URL expired, set
+/// with [`VideoOptions::uri_refresher`].
+#[derive(Clone)]
+pub struct UriRefresher(Arc<dyn Fn() -> url::Url + Send + Sync>);
+
+impl UriRefresher {
+    /// Wrap `refresh`, which is called on the video's worker thread and may block
+    /// while it asks a server to sign a new URL.
+    pub fn new(refresh: impl Fn() -> url::Url + Send + Sync + 'static) -> Self {
+        Self(Arc::new(refresh))
+    }
+}
+
+impl std::fmt::Debug for UriRefresher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("UriRefresher")
+    }
+}
+
+/// Poster frame converted for GPUI, which has no `Debug` impl.
+#[derive(Clone)]
+pub(crate) struct PosterImage(Arc<gpui::RenderImage>);
+
+impl std::fmt::Debug for PosterImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PosterImage")
+    }
+}
+
+impl PosterImage {
+    fn from_rgba(mut image: image::RgbaImage) -> Self {
+        // GPUI's sprite atlas expects BGRA.
+        for pixel in image.pixels_mut() {
+            pixel.0.swap(0, 2);
+        }
+        Self(thumbnail::render_image(image))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Frame {
+    pub(crate) sample: gst::Sample,
+    /// When the sample was taken from the video sink, for paint latency.
+    pulled_at: Instant,
+    /// BGRA pixels converted ahead of painting, shared by clones of this frame and
+    /// taken by whichever view paints it first.
+    converted: Arc<Mutex<Option<Converted>>>,
+}
+
+/// Pixels converted ahead of painting, handed to the video's spares if the frame
+/// is dropped before a view takes them.
 #[derive(Debug)]
-pub(crate) struct Frame(gst::Sample);
+struct Converted {
+    pixels: Vec<u8>,
+    spare: Arc<SparePixels>,
+}
+
+impl Drop for Converted {
+    fn drop(&mut self) {
+        self.spare.put(std::mem::take(&mut self.pixels));
+    }
+}
 
 impl Frame {
+    pub(crate) fn new(sample: gst::Sample) -> Self {
+        Self {
+            sample,
+            pulled_at: Instant::now(),
+            converted: Arc::default(),
+        }
+    }
+
     pub fn empty() -> Self {
-        Self(gst::Sample::builder().build())
+        Self::new(gst::Sample::builder().build())
+    }
+
+    pub(crate) fn pulled_at(&self) -> Instant {
+        self.pulled_at
     }
 
     pub fn readable(&'_ self) -> Option<gst::BufferMap<'_, gst::buffer::Readable>> {
-        self.0.buffer().and_then(|x| x.map_readable().ok())
+        self.sample.buffer().and_then(|x| x.map_readable().ok())
+    }
+
+    /// Bytes held by the frame, including pixels converted ahead of painting.
+    pub(crate) fn size(&self) -> usize {
+        let converted = self
+            .converted
+            .lock()
+            .as_ref()
+            .map_or(0, |converted| converted.pixels.len());
+        self.sample.buffer().map_or(0, |buffer| buffer.size()) + converted
+    }
+
+    pub(crate) fn pts(&self) -> Option<gst::ClockTime> {
+        self.sample.buffer().and_then(|buffer| buffer.pts())
+    }
+
+    /// Position of the frame in the media, as reported by [`Video::position`].
+    pub(crate) fn stream_time(&self) -> Option<Duration> {
+        let stream_time = self.sample.segment()?.to_stream_time(self.pts()?).value();
+        (stream_time >= 0).then(|| Duration::from_nanos(stream_time as u64))
+    }
+
+    /// Run `f` on the frame's bytes where they are mapped, without copying them.
+    /// `Ok(None)` means there is no frame data yet.
+    pub(crate) fn with_data<R>(&self, f: impl FnOnce(&[u8]) -> R) -> Result<Option<R>, FrameError> {
+        let Some(buffer) = self.sample.buffer() else {
+            return Ok(None);
+        };
+        let readable = buffer.map_readable().map_err(|_| FrameError::Map)?;
+        let data = readable.as_slice();
+        Ok((!data.is_empty()).then(|| f(data)))
+    }
+
+    pub(crate) fn set_converted(&self, pixels: Vec<u8>, spare: Arc<SparePixels>) {
+        *self.converted.lock() = Some(Converted { pixels, spare });
+    }
+
+    pub(crate) fn take_converted(&self) -> Option<Vec<u8>> {
+        let mut converted = self.converted.lock().take()?;
+        Some(std::mem::take(&mut converted.pixels))
+    }
+
+    /// Whether both frames reference the same underlying buffer memory.
+    pub(crate) fn shares_buffer(&self, other: &Frame) -> bool {
+        match (self.sample.buffer(), other.sample.buffer()) {
+            (Some(a), Some(b)) => a.as_ptr() == b.as_ptr(),
+            _ => false,
+        }
     }
 }
 
@@ -63,6 +502,93 @@ pub struct VideoOptions {
     pub looping: Option<bool>,
     /// Optional initial playback speed. Defaults to 1.0.
     pub speed: Option<f64>,
+    /// Optional number of audio spectrum bands. When set, a `spectrum` element is
+    /// inserted into the audio branch and [`Video::spectrum`] reports magnitudes.
+    /// Defaults to None.
+    pub spectrum_bands: Option<u32>,
+    /// Optional number of times an HTTP(S) or RTSP source is reconnected after a
+    /// read failure before the error is treated as fatal. Waits between reconnects
+    /// grow like [`recovery`](Self::recovery) restarts. Defaults to 3.
+    pub network_retries: Option<u32>,
+    /// Optional flag to decode HDR (PQ/HLG) sources at 10 bits and tone-map them
+    /// for SDR displays. Defaults to true.
+    pub hdr_tone_mapping: Option<bool>,
+    /// Optional scheduling priority of the frame worker thread. Defaults to None
+    /// (inherit the creating thread's priority).
+    pub worker_priority: Option<WorkerPriority>,
+    /// Optional CPU cores to pin the frame worker thread to. Only supported on
+    /// Linux; ignored with a warning elsewhere, as are cores past the 1024 the
+    /// kernel's CPU set holds. Defaults to None.
+    pub worker_affinity: Option<Vec<usize>>,
+    /// Optional pixel format requested from the video sink. Defaults to None
+    /// (NV12, plus P010 for HDR sources when `hdr_tone_mapping` is enabled).
+    pub pixel_format: Option<PixelFormat>,
+    /// Optional jitter buffer latency for RTSP sources in milliseconds. Lower values
+    /// reduce delay but tolerate less network jitter. Defaults to None (200 ms).
+    pub latency_ms: Option<u32>,
+    /// Optional flag to accept media without a video stream (e.g. mp3 or flac).
+    /// Such a video reports a size of 0x0 and never produces frames, but plays,
+    /// seeks, and reports position as usual. Defaults to false.
+    pub allow_audio_only: Option<bool>,
+    /// Optional flag to insert a `level` element into the audio branch so
+    /// [`Video::audio_levels`] and [`VideoEvent::AudioLevels`] report per-channel
+    /// RMS and peak loudness. Defaults to false.
+    pub audio_levels: Option<bool>,
+    /// Optional audio effects (equalizer, karaoke, pitch) to build into the audio
+    /// branch. Defaults to None.
+    pub audio_filters: Option<AudioFilters>,
+    /// Optional length of recent audio kept for [`Video::recent_audio_window`], e.g.
+    /// to plot the waveform against frames when checking lip sync. Defaults to None.
+    pub audio_window: Option<Duration>,
+    /// Optional position to start playback at, e.g. where the viewer left off. The
+    /// pipeline prerolls there, so frame 0 is never shown. Defaults to None (the start).
+    pub start_at: Option<Position>,
+    /// Optional position to end playback at, e.g. to preview one scene. Playback
+    /// ends there with [`VideoEvent::Ended`] as if the media ended, and seeks keep
+    /// the end in place. Defaults to None (the end of the media).
+    pub stop_at: Option<Position>,
+    /// Optional flag to start in pull-on-paint mode; see [`Video::set_pull_on_paint`].
+    /// Defaults to false.
+    pub pull_on_paint: Option<bool>,
+    /// Optional limits on network buffering, for memory-constrained devices.
+    /// Defaults to None (GStreamer's defaults).
+    pub network_tuning: Option<NetworkTuning>,
+    /// Optional choice between hardware and software video decoders; check the
+    /// result with [`Video::decoder_info`]. Defaults to None
+    /// ([`RuntimeConfig::hardware_decoding`](crate::RuntimeConfig::hardware_decoding),
+    /// Auto unless set).
+    pub hardware_decoding: Option<HwPreference>,
+    /// Optional flag to take frames from VA-API decoders as DMA-BUF, skipping the
+    /// download and conversion copies. Only used on Linux with the `dmabuf` feature,
+    /// and only when `pixel_format` is None; other decoders fall back to system
+    /// memory. Defaults to true.
+    pub dmabuf: Option<bool>,
+    /// Optional playbin properties (flags, subtitle font, audio sink, muted start)
+    /// for videos created from a URI. Defaults to None.
+    pub playbin: Option<PlaybinSettings>,
+    /// Optional element that plays the URI. Defaults to None (playbin).
+    pub backend: Option<Backend>,
+    /// Optional watched percentages reported by [`VideoEvent::ProgressMilestone`].
+    /// Defaults to 25, 50, 75, and 95.
+    pub progress_milestones: Option<Vec<u8>>,
+    /// Optional rank among videos competing for a [`DecodeGovernor`] slot, which is
+    /// taken as soon as the video is created. Defaults to Normal.
+    pub decode_priority: Option<DecodePriority>,
+    /// Optional picture adjustments (brightness, contrast, saturation, hue). When
+    /// set, a `videobalance` element is inserted for videos created from a URI, and
+    /// [`Video::set_color_balance`] changes the settings later. Decoders then hand
+    /// over frames in system memory rather than as DMA-BUF. Defaults to None.
+    pub color_balance: Option<ColorBalance>,
+    /// Optional callback for HTTP(S) sources with expiring signed URLs. When the
+    /// server answers 403 or 410, the source is reopened with the URL it returns
+    /// and playback resumes where it stopped, with [`VideoEvent::UriRefreshed`].
+    /// Defaults to None (the error ends playback).
+    pub uri_refresher: Option<UriRefresher>,
+    /// Optional policy for restarting the pipeline after a fatal error, resuming at
+    /// the last shown frame, with [`VideoEvent::Recovering`] and
+    /// [`VideoEvent::Recovered`] or [`VideoEvent::Failed`]. Network failures are
+    /// first retried per `network_retries`. Defaults to None (the error ends playback).
+    pub recovery: Option<RetryPolicy>,
 }
 
 impl Default for VideoOptions {
@@ -71,6 +597,30 @@ impl Default for VideoOptions {
             frame_buffer_capacity: Some(3),
             looping: Some(false),
             speed: Some(1.0),
+            spectrum_bands: None,
+            network_retries: Some(3),
+            hdr_tone_mapping: Some(true),
+            worker_priority: None,
+            worker_affinity: None,
+            pixel_format: None,
+            latency_ms: None,
+            allow_audio_only: Some(false),
+            audio_levels: Some(false),
+            audio_filters: None,
+            audio_window: None,
+            start_at: None,
+            stop_at: None,
+            pull_on_paint: Some(false),
+            network_tuning: None,
+            hardware_decoding: None,
+            dmabuf: Some(true),
+            playbin: None,
+            backend: None,
+            progress_milestones: Some(DEFAULT_MILESTONES.to_vec()),
+            decode_priority: Some(DecodePriority::Normal),
+            color_balance: None,
+            uri_refresher: None,
+            recovery: None,
         }
     }
 }
@@ -81,36 +631,154 @@ pub(crate) struct Internal {
     pub(crate) id: u64,
     pub(crate) bus: gst::Bus,
     pub(crate) source: gst::Pipeline,
+    pub(crate) video_sink: gst_app::AppSink,
     pub(crate) alive: Arc<AtomicBool>,
+    // Cleared when the worker stops after repeated panics.
+    pub(crate) healthy: Arc<AtomicBool>,
+    pub(crate) scrub: Arc<Mutex<Option<Scrub>>>,
+    pub(crate) seeks: Arc<SeekCoalescer>,
+    pub(crate) position_watchers: Arc<PositionWatchers>,
     pub(crate) worker: Option<std::thread::JoinHandle<()>>,
+    pub(crate) pipeline_guard: PipelineGuard,
 
     pub(crate) width: i32,
     pub(crate) height: i32,
     pub(crate) framerate: f64,
     pub(crate) duration: Duration,
+    pub(crate) live: bool,
+    pub(crate) color_space: ColorSpace,
+    pub(crate) color_overrides: Arc<Mutex<ColorOverrides>>,
+    pub(crate) layout: FrameLayout,
+    pub(crate) audio_only: bool,
+    // Set while a `FrameIter` pulls samples itself; the worker then leaves the sink alone.
+    pub(crate) external_consumer: Arc<AtomicBool>,
+    // Set in pull-on-paint mode, where views pull samples themselves when painting.
+    pub(crate) pull_on_paint: Arc<AtomicBool>,
     pub(crate) speed: Arc<AtomicU64>,
 
     pub(crate) frame: Arc<Mutex<Frame>>,
     pub(crate) upload_frame: Arc<AtomicBool>,
+    /// Incremented for every decoded frame, so each view can track which frame
+    /// it last saw instead of racing on `upload_frame`.
+    pub(crate) frame_generation: Arc<AtomicU64>,
+    pub(crate) frame_signal: Arc<FrameSignal>,
     pub(crate) frame_buffer: Arc<Mutex<VecDeque<Frame>>>,
     pub(crate) frame_buffer_capacity: Arc<AtomicUsize>,
+    pub(crate) memory: Arc<MemoryTracker>,
     pub(crate) last_frame_time: Arc<Mutex<Instant>>,
     pub(crate) looping: Arc<AtomicBool>,
     pub(crate) is_eos: Arc<AtomicBool>,
     pub(crate) restart_stream: bool,
+    pub(crate) buffering_percent: Arc<AtomicU8>,
+    // Set while the worker holds playback paused to refill the buffer, so it
+    // knows whether to resume once buffering reaches 100%.
+    pub(crate) resume_after_buffering: Arc<AtomicBool>,
+    pub(crate) error: Arc<Mutex<Option<String>>>,
+    pub(crate) missing_plugins: Arc<Mutex<Vec<String>>>,
+    pub(crate) streams: Arc<Mutex<StreamState>>,
+    pub(crate) chapters: Arc<Mutex<Vec<Chapter>>>,
+    pub(crate) health: Arc<Mutex<StreamHealth>>,
+    pub(crate) stats: Arc<Mutex<PlaybackStats>>,
+    pub(crate) metadata: Arc<Mutex<Metadata>>,
+    // Transform from the stream's image-orientation tag, applied when painting.
+    pub(crate) orientation: Arc<Mutex<Orientation>>,
+    pub(crate) external_clock: Arc<Mutex<Option<ExternalClock>>>,
+    // Sink queue depth from before `set_external_clock`, restored when it is cleared.
+    pub(crate) external_clock_max_buffers: Mutex<Option<u32>>,
+    pub(crate) poster: Mutex<Option<PosterImage>>,
+    // Set once the disk cache was checked, so painting does not hit the disk every frame.
+    pub(crate) poster_loaded: AtomicBool,
+    // A `DecodePriority`, read by the governor.
+    pub(crate) decode_priority: AtomicU8,
 
     pub(crate) subtitle_text: Arc<Mutex<Option<String>>>,
     pub(crate) upload_text: Arc<AtomicBool>,
 
+    pub(crate) spectrum: Arc<Mutex<SpectrumState>>,
+    pub(crate) levels: Arc<Mutex<LevelState>>,
+    pub(crate) watched: Arc<Mutex<WatchTracker>>,
+    pub(crate) audio_window: Arc<Mutex<AudioWindow>>,
+    pub(crate) events: Arc<EventHub>,
+    // Active recording started by `record_to`, fed each frame by the worker.
+    pub(crate) recorder: Arc<Mutex<Option<Recorder>>>,
+    // Position to return to once a boundary preview segment finishes.
+    pub(crate) preview_return: Arc<Mutex<Option<Duration>>>,
+    // A/B loop bounds; playback runs as a segment that restarts at `start` on SEGMENT_DONE.
+    pub(crate) loop_range: Arc<Mutex<Option<TimeRange>>>,
+    // End set by `VideoOptions::stop_at`, kept as the stop of every seek.
+    pub(crate) stop_at: Option<Duration>,
+    // Spans the worker jumps over whenever playback enters them.
+    pub(crate) skip_segments: Arc<Mutex<Vec<TimeRange>>>,
+    // Silent spans from the last silence analysis, skipped while `skip_silence` is set.
+    pub(crate) silence: Arc<Mutex<Option<Vec<TimeRange>>>>,
+    pub(crate) skip_silence: Arc<AtomicBool>,
+    pub(crate) silence_analyzing: Arc<AtomicBool>,
+
     // Optional display size overrides. If only one is set, the other is
     // inferred using the natural aspect ratio (width / height).
     pub(crate) display_width_override: Option<u32>,
     pub(crate) display_height_override: Option<u32>,
 }
 
+// Tear down here rather than in `Drop for Video`: this runs exactly once, when the
+// last clone goes away, and needs no lock, so cleanup cannot be skipped because
+// another thread happens to hold it.
+impl Drop for Internal {
+    fn drop(&mut self) {
+        // Keep going on failure: the worker still has to be stopped and joined.
+        if let Err(err) = self.source.set_state(gst::State::Null) {
+            log::error!("failed to shut down video pipeline: {err}");
+        }
+        self.pipeline_guard.release();
+        if let Some(recorder) = self.recorder.lock().take() {
+            recorder.finish(Arc::clone(&self.events));
+        }
+        // Discard queued messages so they do not keep buffers alive.
+        self.bus.set_flushing(true);
+
+        self.alive.store(false, Ordering::SeqCst);
+        // The last handle can be dropped by an event callback on the worker itself,
+        // which cannot join itself; it exits on its own now that `alive` is false.
+        if let Some(worker) = self.worker.take()
+            && worker.thread().id() != std::thread::current().id()
+            && let Err(err) = worker.join()
+        {
+            log::error!("Video thread panicked: {}", panic_message(&*err));
+        }
+        // Hand this video's decode slot to a queued one.
+        governor::video_dropped();
+    }
+}
+
 impl Internal {
     pub(crate) fn seek(&self, position: impl Into<Position>, accurate: bool) -> Result<(), Error> {
-        let position = position.into();
+        let mut position = position.into();
+
+        // While scrubbing, seeks only move the target; the worker follows it with
+        // cheap keyframe seeks at a limited rate.
+        {
+            let mut scrub = self.scrub.lock();
+            if let Some(scrub) = scrub.as_mut() {
+                scrub.set_target(match position {
+                    Position::Time(time) => time,
+                    Position::Frame(frame) if self.framerate > 0.0 => {
+                        Duration::from_secs_f64(frame as f64 / self.framerate)
+                    }
+                    Position::Frame(_) => Duration::ZERO,
+                });
+                return Ok(());
+            }
+        }
+
+        let loop_range = *self.loop_range.lock();
+        // The loop's and `stop_at`'s stop times are in TIME format, and a seek's start
+        // and stop must share one.
+        if (loop_range.is_some() || self.stop_at.is_some())
+            && let Position::Frame(frame) = position
+            && self.framerate > 0.0
+        {
+            position = Position::Time(Duration::from_secs_f64(frame as f64 / self.framerate));
+        }
         let current_speed = f64::from_bits(self.speed.load(Ordering::SeqCst));
 
         // Clear EOS so the worker resumes pulling after a seek.
@@ -130,27 +798,39 @@ impl Internal {
             }
         }
 
+        *self.preview_return.lock() = None;
+        // Reset the stop position explicitly so a previous preview segment
+        // does not cut playback short. An active A/B loop keeps its segment.
+        let stop = match loop_range {
+            Some(range) => {
+                flags |= gst::SeekFlags::SEGMENT;
+                Some(gst::ClockTime::from_nseconds(range.end.as_nanos() as u64))
+            }
+            None => self.stop_clock_time(),
+        };
         match &position {
             Position::Time(_) => self.source.seek(
                 current_speed,
                 flags,
                 gst::SeekType::Set,
                 gst::GenericFormattedValue::from(position),
-                gst::SeekType::None,
-                gst::ClockTime::NONE,
+                gst::SeekType::Set,
+                stop,
             )?,
             Position::Frame(_) => self.source.seek(
                 current_speed,
                 flags,
                 gst::SeekType::Set,
                 gst::GenericFormattedValue::from(position),
-                gst::SeekType::None,
+                gst::SeekType::Set,
                 gst::format::Default::NONE,
             )?,
         };
 
         *self.subtitle_text.lock() = None;
         self.upload_text.store(true, Ordering::SeqCst);
+        self.spectrum.lock().clear();
+        self.levels.lock().clear();
 
         // Clear any buffered frames so old frames do not display after a seek,
         // which can visually appear as a larger-than-intended jump.
@@ -160,84 +840,358 @@ impl Internal {
         Ok(())
     }
 
+    /// Accurately seek to `range.start`, playing forward at `rate` and ending the
+    /// stream with EOS at `range.end`.
+    pub(crate) fn seek_range(&self, range: TimeRange, rate: f64) -> Result<(), Error> {
+        self.is_eos.store(false, Ordering::SeqCst);
+        *self.preview_return.lock() = None;
+        self.source.seek(
+            rate,
+            gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+            gst::SeekType::Set,
+            gst::ClockTime::from_nseconds(range.start.as_nanos() as u64),
+            gst::SeekType::Set,
+            gst::ClockTime::from_nseconds(range.end.as_nanos() as u64),
+        )?;
+        self.speed.store(rate.to_bits(), Ordering::SeqCst);
+        self.frame_buffer.lock().clear();
+        self.upload_frame.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    pub(crate) fn preview_boundary(
+        &self,
+        at: Duration,
+        span: Duration,
+        rate: f64,
+    ) -> Result<(), Error> {
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(Error::Rate(rate));
+        }
+
+        let half = span / 2;
+        let start = at.saturating_sub(half);
+        let mut stop = at.saturating_add(half);
+        if !self.duration.is_zero() {
+            stop = stop.min(self.duration);
+        }
+
+        self.is_eos.store(false, Ordering::SeqCst);
+        *self.preview_return.lock() = Some(at);
+        // A segment seek posts SEGMENT_DONE instead of EOS at `stop`, which lets
+        // the worker pause and rewind to the boundary.
+        self.source.seek(
+            rate,
+            gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE | gst::SeekFlags::SEGMENT,
+            gst::SeekType::Set,
+            gst::ClockTime::from_nseconds(start.as_nanos() as u64),
+            gst::SeekType::Set,
+            gst::ClockTime::from_nseconds(stop.as_nanos() as u64),
+        )?;
+        self.frame_buffer.lock().clear();
+        self.upload_frame.store(false, Ordering::SeqCst);
+        self.source.set_state(gst::State::Playing)?;
+        Ok(())
+    }
+
     pub(crate) fn set_speed(&mut self, speed: f64) -> Result<(), Error> {
         let Some(position) = self.source.query_position::<gst::ClockTime>() else {
-            return Err(Error::Caps);
+            return Err(Error::Duration);
         };
-        if speed > 0.0 {
+        let loop_range = *self.loop_range.lock();
+        if speed > 0.0
+            && let Some(range) = loop_range
+        {
+            // Keep the A/B loop segment so the new speed does not play past its end.
+            self.source.seek(
+                speed,
+                gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE | gst::SeekFlags::SEGMENT,
+                gst::SeekType::Set,
+                position,
+                gst::SeekType::Set,
+                gst::ClockTime::from_nseconds(range.end.as_nanos() as u64),
+            )?;
+        } else if speed > 0.0 {
+            let (stop_type, stop) = match self.stop_clock_time() {
+                Some(stop) => (gst::SeekType::Set, stop),
+                None => (gst::SeekType::End, gst::ClockTime::ZERO),
+            };
             self.source.seek(
                 speed,
                 gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
                 gst::SeekType::Set,
                 position,
-                gst::SeekType::End,
-                gst::ClockTime::from_seconds(0),
+                stop_type,
+                stop,
             )?;
         } else {
+            // Decoders play backwards one GOP at a time. Trick mode lets them skip
+            // work they cannot keep up with, and audio is dropped because most audio
+            // decoders cannot run in reverse.
             self.source.seek(
                 speed,
-                gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                gst::SeekFlags::FLUSH
+                    | gst::SeekFlags::ACCURATE
+                    | gst::SeekFlags::TRICKMODE
+                    | gst::SeekFlags::TRICKMODE_NO_AUDIO,
                 gst::SeekType::Set,
                 gst::ClockTime::from_seconds(0),
                 gst::SeekType::Set,
                 position,
             )?;
+            self.frame_buffer.lock().clear();
         }
         self.speed.store(speed.to_bits(), Ordering::SeqCst);
         Ok(())
     }
 
+    fn stop_clock_time(&self) -> Option<gst::ClockTime> {
+        self.stop_at
+            .map(|stop| gst::ClockTime::from_nseconds(stop.as_nanos() as u64))
+    }
+
     pub(crate) fn restart_stream(&mut self) -> Result<(), Error> {
         self.is_eos.store(false, Ordering::SeqCst);
-        self.set_paused(false);
+        self.set_paused(false)?;
         self.seek(0, false)?;
         Ok(())
     }
 
-    pub(crate) fn set_paused(&mut self, paused: bool) {
-        self.source
-            .set_state(if paused {
-                gst::State::Paused
-            } else {
-                gst::State::Playing
-            })
-            .unwrap(/* state was changed in ctor; state errors caught there */);
+    pub(crate) fn set_paused(&mut self, paused: bool) -> Result<(), Error> {
+        if !self.live && self.buffering_percent.load(Ordering::SeqCst) < 100 {
+            // Playback is held for buffering; the worker resumes it (or not) at 100%.
+            self.resume_after_buffering.store(!paused, Ordering::SeqCst);
+            return Ok(());
+        }
+
+        self.source.set_state(if paused {
+            gst::State::Paused
+        } else {
+            gst::State::Playing
+        })?;
 
         if self.is_eos.load(Ordering::Acquire) && !paused {
             self.restart_stream = true;
         }
+        Ok(())
+    }
+
+    /// Pause or resume on behalf of the [`DecodeGovernor`]. During a scrub the
+    /// decision is held until it ends, like [`set_paused`](Self::set_paused) does
+    /// while buffering, so a slot freeing up does not start playback mid-drag.
+    pub(crate) fn set_paused_by_governor(&mut self, paused: bool) -> Result<(), Error> {
+        if let Some(scrub) = self.scrub.lock().as_mut() {
+            scrub.resume = !paused;
+            return Ok(());
+        }
+        self.set_paused(paused)
     }
 
     pub(crate) fn paused(&self) -> bool {
         self.source.state(gst::ClockTime::ZERO).1 == gst::State::Paused
+            && !self.resume_after_buffering.load(Ordering::SeqCst)
+    }
+
+    /// The media URI, read back from playbin so custom playbin pipelines work too.
+    pub(crate) fn uri(&self) -> Option<url::Url> {
+        self.source.find_property("uri")?;
+        let uri: Option<String> = self.source.property("uri");
+        uri.and_then(|uri| url::Url::parse(&uri).ok())
+    }
+}
+
+/// Plane layout and colorimetry described by the video sink's negotiated caps.
+fn video_info(caps: &gst::CapsRef) -> Result<gst_video::VideoInfo, Error> {
+    #[cfg(all(target_os = "linux", feature = "dmabuf"))]
+    if dmabuf::is_dmabuf(caps) {
+        return dmabuf::video_info(caps);
+    }
+    gst_video::VideoInfo::from_caps(caps).map_err(|_| Error::Caps)
+}
+
+/// Whether a bus error looks like a dropped or refused connection that is worth retrying.
+fn is_connection_error(error: &glib::Error) -> bool {
+    error.matches(gst::ResourceError::Read)
+        || error.matches(gst::ResourceError::OpenRead)
+        || error.matches(gst::ResourceError::Seek)
+        || error.matches(gst::ResourceError::Failed)
+}
+
+/// Whether a bus error is an HTTP source being refused with 403 Forbidden or 410
+/// Gone, as servers answer for signed URLs that expired.
+///
+/// `souphttpsrc` reports 403 as not authorized and other client errors as failing
+/// to open, with the status code in the debug message.
+fn is_expired_url_error(error: &glib::Error, debug: &str) -> bool {
+    error.matches(gst::ResourceError::NotAuthorized)
+        || ((error.matches(gst::ResourceError::OpenRead)
+            || error.matches(gst::ResourceError::Read))
+            && (debug.contains("(403)") || debug.contains("(410)")))
+}
+
+/// Whether `uri` uses a scheme whose source can be reconnected after a network error.
+pub(crate) fn is_network_uri(uri: &str) -> bool {
+    ["http://", "https://", "rtsp://", "rtsps://", "rtspt://"]
+        .iter()
+        .any(|scheme| uri.starts_with(scheme))
+}
+
+/// Rebuild the source after a connection failure and resume at `position`,
+/// first setting the URI property named in `reload` (`uri` or `suburi`) if given.
+///
+/// Going through READY recreates the HTTP connection; the seek afterwards makes
+/// the source resume with a Range request instead of downloading from the start.
+/// `teardown` NULL goes further and also discards decoders and other elements in a
+/// failed state. Live sources cannot seek, so they pass `None` and rejoin the
+/// stream as it is now.
+///
+/// Waits up to 10 s for the pipeline to preroll; the worker uses
+/// [`restart_source`] instead.
+fn resume_source(
+    pipeline: &gst::Pipeline,
+    position: Option<Duration>,
+    reload: Option<(&str, &url::Url)>,
+    teardown: gst::State,
+) -> Result<(), Error> {
+    if let Some(resume) = restart_source(pipeline, position, reload, teardown)? {
+        pipeline.state(gst::ClockTime::from_seconds(10)).0?;
+        resume.apply(pipeline)?;
+    }
+    Ok(())
+}
+
+/// Start what [`resume_source`] does without waiting for the preroll. A seekable
+/// source is left prerolling; apply the returned [`PendingResume`] once it posts
+/// ASYNC_DONE.
+fn restart_source(
+    pipeline: &gst::Pipeline,
+    position: Option<Duration>,
+    reload: Option<(&str, &url::Url)>,
+    teardown: gst::State,
+) -> Result<Option<PendingResume>, Error> {
+    let playing = pipeline.current_state() != gst::State::Paused;
+    pipeline.set_state(teardown)?;
+    if let Some((property, uri)) = reload {
+        pipeline.set_property(property, uri.as_str());
+    }
+    match position {
+        Some(position) => {
+            pipeline.set_state(gst::State::Paused)?;
+            Ok(Some(PendingResume { position, playing }))
+        }
+        None => {
+            if playing {
+                pipeline.set_state(gst::State::Playing)?;
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// The rest of a [`restart_source`], carried out once the pipeline has prerolled.
+struct PendingResume {
+    position: Duration,
+    /// Whether the pipeline was playing before the restart.
+    playing: bool,
+}
+
+impl PendingResume {
+    fn apply(self, pipeline: &gst::Pipeline) -> Result<(), Error> {
+        pipeline.seek_simple(
+            gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+            gst::ClockTime::from_nseconds(self.position.as_nanos() as u64),
+        )?;
+        if self.playing {
+            pipeline.set_state(gst::State::Playing)?;
+        }
+        Ok(())
+    }
+}
+
+/// Time of `position`, counting frames at `framerate` (frame 0 when unknown).
+fn position_time(position: Position, framerate: f64) -> Duration {
+    match position {
+        Position::Time(time) => time,
+        Position::Frame(frame) if framerate > 0.0 => {
+            Duration::from_secs_f64(frame as f64 / framerate)
+        }
+        Position::Frame(_) => Duration::ZERO,
+    }
+}
+
+/// The range in `ranges` containing `position`, if any.
+fn range_at(position: Duration, ranges: &[TimeRange]) -> Option<TimeRange> {
+    ranges
+        .iter()
+        .find(|range| range.start <= position && position < range.end)
+        .copied()
+}
+
+/// Caps for the video appsink: the pixel format `options` ask for, with square
+/// pixels, and DMA-BUF first where enabled.
+pub(crate) fn sink_caps(options: &VideoOptions) -> gst::Caps {
+    format_caps(
+        options,
+        &[options.pixel_format.unwrap_or(PixelFormat::Nv12)],
+    )
+}
+
+/// Caps for the video appsink while the source is PQ or HLG, offering P010 as well
+/// so 10-bit HDR is not truncated to 8 bits before tone mapping. None if `options`
+/// fix the format or turn tone mapping off.
+pub(crate) fn hdr_sink_caps(options: &VideoOptions) -> Option<gst::Caps> {
+    (options.pixel_format.is_none() && options.hdr_tone_mapping.unwrap_or_default())
+        .then(|| format_caps(options, &[PixelFormat::Nv12, PixelFormat::P010]))
+}
+
+fn format_caps(options: &VideoOptions, formats: &[PixelFormat]) -> gst::Caps {
+    let formats = formats.iter().map(|format| format.caps_name());
+    let caps = gst::Caps::builder("video/x-raw")
+        .field("format", gst::List::new(formats))
+        .field("pixel-aspect-ratio", gst::Fraction::new(1, 1))
+        .build();
+    #[cfg(all(target_os = "linux", feature = "dmabuf"))]
+    if options.dmabuf.unwrap_or_default() && options.pixel_format.is_none() && dmabuf::available() {
+        let mut dmabuf_caps = dmabuf::caps();
+        dmabuf_caps.make_mut().append(caps);
+        return dmabuf_caps;
     }
+    caps
 }
 
 /// A multimedia video loaded from a URI (e.g., a local file path or HTTP stream).
+///
+/// # Threading
+///
+/// `Video` is a cheap, `Send + Sync` handle; clones share one player. Decoding
+/// runs on a worker thread, and methods only take short-lived locks, so
+/// controls and queries (`set_paused`, `seek`, `position`, `current_frame_data`,
+/// ...) can be called from any thread, including gpui's render path.
+///
+/// Methods that block until work finishes (`wait_for_frame`, iterating
+/// [`frames_blocking`](Self::frames_blocking) or [`frames`](Self::frames),
+/// `thumbnail`, `set_poster_time`, `analyze_motion`, `detect_silence`, and the
+/// `export_*` methods) belong on a background thread: on the UI thread they stall
+/// rendering, and inside an [`on_event`](Self::on_event) callback they deadlock
+/// the worker that has to make progress. Debug builds assert both.
 #[derive(Debug, Clone)]
 pub struct Video(pub(crate) Arc<RwLock<Internal>>);
 
-impl Drop for Video {
-    fn drop(&mut self) {
-        // Only cleanup if this is the last reference
-        if Arc::strong_count(&self.0) == 1
-            && let Some(mut inner) = self.0.try_write()
-        {
-            inner
-                .source
-                .set_state(gst::State::Null)
-                .expect("failed to set state");
+// The threading contract above relies on this; keep new fields thread-safe.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Video>();
+};
 
-            inner.alive.store(false, Ordering::SeqCst);
-            if let Some(worker) = inner.worker.take()
-                && let Err(err) = worker.join()
-            {
-                match err.downcast_ref::<String>() {
-                    Some(e) => log::error!("Video thread panicked: {e}"),
-                    None => log::error!("Video thread panicked with unknown reason"),
-                }
-            }
-        }
+/// A handle that does not keep its [`Video`] alive, from [`Video::downgrade`], for
+/// event callbacks and other places a clone would keep the video from ever dropping.
+#[derive(Debug, Clone)]
+pub struct WeakVideo(Weak<RwLock<Internal>>);
+
+impl WeakVideo {
+    /// Get the video back, or None once every `Video` handle has been dropped.
+    pub fn upgrade(&self) -> Option<Video> {
+        self.0.upgrade().map(Video)
     }
 }
 
@@ -249,126 +1203,299 @@ impl Video {
 
     /// Create a new video player from a given video which loads from `uri`,
     /// applying initialization options.
+    ///
+    /// Animated GIF and PNG images play too; see [`Video::from_image_sequence`].
+    /// They are read and decoded here, so a remote one is downloaded before this
+    /// returns, failing after 10 s, and one too large to keep decoded in memory
+    /// fails with [`Error::ImageTooLarge`].
     pub fn new_with_options(uri: &url::Url, options: VideoOptions) -> Result<Self, Error> {
-        gst::init()?;
+        runtime::ensure_init()?;
 
-        let pipeline = format!(
-            "playbin uri=\"{}\" video-sink=\"videoscale ! videoconvert ! appsink name=gpui_video drop=true max-buffers=200 enable-last-sample=false caps=video/x-raw,format=NV12,pixel-aspect-ratio=1/1\"",
-            uri.as_str()
-        );
-        let pipeline = gst::parse::launch(pipeline.as_ref())?
-            .downcast::<gst::Pipeline>()
-            .map_err(|_| Error::Cast)?;
-
-        let video_sink: gst::Element = pipeline.property("video-sink");
-        let pad = video_sink.pads().first().cloned().unwrap();
-        let pad = pad.dynamic_cast::<gst::GhostPad>().unwrap();
-        let bin = pad
-            .parent_element()
-            .unwrap()
-            .downcast::<gst::Bin>()
-            .unwrap();
-        let video_sink = bin.by_name("gpui_video").unwrap();
-        let video_sink = video_sink.downcast::<gst_app::AppSink>().unwrap();
+        if let Some(source) = animation::animated_image(uri, &options)? {
+            return Self::from_animated_source(source, options);
+        }
 
-        Self::from_gst_pipeline_with_options(pipeline, video_sink, None, options)
+        Self::from_pipeline_builder(PipelineBuilder::new(uri), options)
     }
 
-    /// Creates a new video based on an existing GStreamer pipeline and appsink.
-    pub fn from_gst_pipeline(
-        pipeline: gst::Pipeline,
-        video_sink: gst_app::AppSink,
-        text_sink: Option<gst_app::AppSink>,
+    /// Create a video from a customized [`PipelineBuilder`], applying initialization
+    /// options on top: their filters follow the builder's, and their sink caps apply
+    /// unless the builder sets its own.
+    pub fn from_pipeline_builder(
+        mut builder: PipelineBuilder,
+        options: VideoOptions,
     ) -> Result<Self, Error> {
-        Self::from_gst_pipeline_with_options(
+        if builder.sink_caps.is_none() {
+            builder.sink_caps = Some(sink_caps(&options));
+            builder.hdr_sink_caps = hdr_sink_caps(&options);
+        }
+        if builder.backend.is_none() {
+            builder.backend = options.backend;
+        }
+        builder
+            .video_filters
+            .extend(filter::video_filters(&options)?);
+        builder
+            .audio_filters
+            .extend(filter::audio_filters(&options)?);
+
+        let rtsp = matches!(builder.uri().scheme(), "rtsp" | "rtsps" | "rtspt");
+        if rtsp {
+            // Queued frames only add delay on a live stream.
+            builder.max_buffers.get_or_insert(2);
+        }
+        let BuiltPipeline {
             pipeline,
             video_sink,
             text_sink,
-            VideoOptions::default(),
-        )
+        } = builder.build()?;
+
+        if rtsp {
+            let latency = options.latency_ms.unwrap_or(RTSP_DEFAULT_LATENCY_MS);
+            pipeline.connect("source-setup", false, move |args| {
+                if let Ok(source) = args[1].get::<gst::Element>()
+                    && source.find_property("latency").is_some()
+                {
+                    source.set_property("latency", latency);
+                    source.set_property("drop-on-latency", true);
+                }
+                None
+            });
+        }
+
+        if let Some(settings) = &options.playbin {
+            settings.apply(&pipeline)?;
+        }
+
+        Self::from_gst_pipeline_with_options(pipeline, video_sink, text_sink, options)
     }
 
-    /// Creates a new video based on an existing GStreamer pipeline and appsink,
+    /// Create a video showing numbered images at `fps` frames per second, e.g.
+    /// `renders/shot_%04d.png` for `shot_0000.png`, `shot_0001.png`, and so on.
+    ///
+    /// `pattern` takes one printf-style index (`%d`, `%4d`, or `%04d`); numbering
+    /// starts at 0 or 1 and ends before the first missing file. PNG, JPEG, and WebP
+    /// images are supported. With animated GIF and PNG images opened through
+    /// [`Video::new`], this lets one [`VideoElement`](crate::VideoElement) show every
+    /// kind of animated media in an app.
+    pub fn from_image_sequence(pattern: impl AsRef<Path>, fps: f64) -> Result<Self, Error> {
+        Self::from_image_sequence_with_options(pattern, fps, VideoOptions::default())
+    }
+
+    /// Create a video showing numbered images at `fps` frames per second,
     /// applying initialization options.
-    pub fn from_gst_pipeline_with_options(
+    pub fn from_image_sequence_with_options(
+        pattern: impl AsRef<Path>,
+        fps: f64,
+        options: VideoOptions,
+    ) -> Result<Self, Error> {
+        let source = animation::image_sequence(pattern.as_ref(), fps, &options)?;
+        Self::from_animated_source(source, options)
+    }
+
+    /// Create a live video from a camera or other capture device, e.g. one from
+    /// [`list_video_devices`](crate::list_video_devices), to preview it with the
+    /// same [`VideoElement`](crate::VideoElement) used for files.
+    pub fn from_capture_device(device: DeviceId, options: CaptureOptions) -> Result<Self, Error> {
+        let (pipeline, video_sink) = capture::capture_pipeline(&device, &options)?;
+        Self::from_gst_pipeline_with_options(pipeline, video_sink, None, options.video)
+    }
+
+    fn from_animated_source(source: AnimatedSource, options: VideoOptions) -> Result<Self, Error> {
+        Self::from_pipeline(
+            source.pipeline,
+            source.video_sink,
+            None,
+            options,
+            Some(source.duration),
+        )
+    }
+
+    /// Creates a new video based on an existing GStreamer pipeline and appsink.
+    ///
+    /// `video_sink` must be in `pipeline`, or, for a `playbin` or `playbin3`, be its
+    /// `video-sink` or sit inside a bin that is; a playbin with no video sink set
+    /// gets `video_sink`. Anything else fails with [`Error::VideoSink`] rather than
+    /// waiting for frames that never come.
+    pub fn from_gst_pipeline(
+        pipeline: gst::Pipeline,
+        video_sink: gst_app::AppSink,
+        text_sink: Option<gst_app::AppSink>,
+    ) -> Result<Self, Error> {
+        Self::from_gst_pipeline_with_options(
+            pipeline,
+            video_sink,
+            text_sink,
+            VideoOptions::default(),
+        )
+    }
+
+    /// Creates a new video based on an existing GStreamer pipeline and appsink,
+    /// applying initialization options.
+    pub fn from_gst_pipeline_with_options(
+        pipeline: gst::Pipeline,
+        video_sink: gst_app::AppSink,
+        text_sink: Option<gst_app::AppSink>,
+        options: VideoOptions,
+    ) -> Result<Self, Error> {
+        Self::from_pipeline(pipeline, video_sink, text_sink, options, None)
+    }
+
+    /// `known_duration` stands in for pipelines whose source cannot report one.
+    fn from_pipeline(
         pipeline: gst::Pipeline,
         video_sink: gst_app::AppSink,
         text_sink: Option<gst_app::AppSink>,
         options: VideoOptions,
+        known_duration: Option<Duration>,
     ) -> Result<Self, Error> {
-        gst::init()?;
+        runtime::ensure_init()?;
         static NEXT_ID: AtomicU64 = AtomicU64::new(0);
         let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
 
         macro_rules! cleanup {
             ($expr:expr) => {
                 $expr.map_err(|e| {
+                    // The bus usually says why, e.g. which plugin or file is missing.
+                    let e = error::pipeline_error(&pipeline).unwrap_or_else(|| Error::from(e));
                     let _ = pipeline.set_state(gst::State::Null);
                     e
                 })
             };
         }
 
-        let pad = video_sink.pads().first().cloned().unwrap();
+        let bus = pipeline.bus().ok_or(Error::Bus)?;
+        let pad = video_sink_pad(&pipeline, &video_sink)?;
+
+        // Buffers are sized when the source opens, so set limits before prerolling.
+        if let Some(tuning) = options.network_tuning {
+            tuning.apply(pipeline.upcast_ref());
+        }
+
+        let hw_preference = options
+            .hardware_decoding
+            .unwrap_or_else(runtime::hardware_decoding);
+        if hw_preference == HwPreference::Require && !decoder::hardware_decoder_installed() {
+            return Err(Error::NoHardwareDecoder);
+        }
+        decoder::restrict_decoders(&pipeline, hw_preference);
+        // Decoders are created while prerolling, so they are timed from the start.
+        let stats = Arc::new(Mutex::new(PlaybackStats::default()));
+        decoder::time_decoders(&pipeline, &stats);
+
+        // Preroll paused: the decode governor decides whether the video may play once
+        // it exists. Live sources produce nothing until playing, so they start now and
+        // are paused again if the governor queues them.
+        let preroll = cleanup!(pipeline.set_state(gst::State::Paused))?;
+        if preroll == gst::StateChangeSuccess::NoPreroll {
+            cleanup!(pipeline.set_state(gst::State::Playing))?;
+        }
 
-        cleanup!(pipeline.set_state(gst::State::Playing))?;
+        // Seeking after construction would show frame 0 and then jump, so move to the
+        // start and end points while still prerolling.
+        let mut stop_at = None;
+        if options.start_at.is_some() || options.stop_at.is_some() {
+            cleanup!(pipeline.state(gst::ClockTime::from_seconds(5)).0)?;
+            let framerate = pad
+                .current_caps()
+                .and_then(|caps| caps.structure(0)?.get::<gst::Fraction>("framerate").ok())
+                .map_or(0.0, |rate| rate.numer() as f64 / rate.denom() as f64);
+            let to_clock_time = |position| {
+                gst::ClockTime::from_nseconds(position_time(position, framerate).as_nanos() as u64)
+            };
+            stop_at = options
+                .stop_at
+                .map(|position| position_time(position, framerate));
+            // Without SEGMENT, reaching the stop posts a regular EOS.
+            cleanup!(pipeline.seek(
+                1.0,
+                gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                gst::SeekType::Set,
+                options.start_at.map_or(gst::ClockTime::ZERO, to_clock_time),
+                gst::SeekType::Set,
+                options.stop_at.map(to_clock_time),
+            ))?;
+            cleanup!(pipeline.state(gst::ClockTime::from_seconds(5)).0)?;
+        }
 
-        // Wait a brief moment for the pipeline to start playing
+        // Wait a brief moment for the pipeline to preroll
         let _ = pipeline.state(gst::ClockTime::from_mseconds(100));
-        cleanup!(pipeline.state(gst::ClockTime::from_seconds(5)).0)?;
-
-        let caps = cleanup!(pad.current_caps().ok_or(Error::Caps))?;
-        let s = cleanup!(caps.structure(0).ok_or(Error::Caps))?;
-        let width = cleanup!(s.get::<i32>("width").map_err(|_| Error::Caps))?;
-        let height = cleanup!(s.get::<i32>("height").map_err(|_| Error::Caps))?;
-        let framerate = cleanup!(s.get::<gst::Fraction>("framerate").map_err(|_| Error::Caps))?;
-        let framerate = framerate.numer() as f64 / framerate.denom() as f64;
-
-        let vinfo = cleanup!(gst_video::VideoInfo::from_caps(&caps).map_err(|_| Error::Caps))?;
-        let expected_y_size = if width > 0 && height > 0 {
-            width as usize * height as usize
+        let started = cleanup!(pipeline.state(gst::ClockTime::from_seconds(5)).0)?;
+
+        let mut latency_query = gst::query::Latency::new();
+        let live = pipeline.query(&mut latency_query) && latency_query.result().0;
+
+        // Once prerolled, an unlinked video sink means the media has no video stream.
+        let video_caps = pad.current_caps();
+        let audio_only = video_caps.is_none() && options.allow_audio_only.unwrap_or_default();
+
+        let (width, height, framerate, color_space, layout) = if audio_only {
+            let layout = FrameLayout {
+                format: None,
+                stride: [0; 3],
+                offset: [0; 3],
+            };
+            (0, 0, 0.0, ColorSpace::default(), layout)
         } else {
-            0
-        };
-        let expected_size = expected_y_size + expected_y_size / 2;
-        debug_assert!(
-            width > 0
-                && height > 0
-                && vinfo.stride().first().copied() == Some(width)
-                && vinfo.stride().get(1).copied() == Some(width)
-                && vinfo.offset().first().copied() == Some(0)
-                && vinfo.offset().get(1).copied() == Some(expected_y_size)
-                && vinfo.size() == expected_size,
-            "non-tight NV12 video layouts are not supported yet: {width}x{height}, stride={:?}, offset={:?}, size={}",
-            vinfo.stride(),
-            vinfo.offset(),
-            vinfo.size(),
-        );
+            let caps = cleanup!(
+                video_caps.ok_or(if started == gst::StateChangeSuccess::Async {
+                    Error::StateChangeTimeout
+                } else {
+                    Error::NoVideoStream
+                })
+            )?;
+            let s = cleanup!(caps.structure(0).ok_or(Error::Caps))?;
+            let width = cleanup!(s.get::<i32>("width").map_err(|_| Error::Caps))?;
+            let height = cleanup!(s.get::<i32>("height").map_err(|_| Error::Caps))?;
+            let framerate = cleanup!(s.get::<gst::Fraction>("framerate").map_err(|_| Error::Caps))?;
+            let framerate = framerate.numer() as f64 / framerate.denom() as f64;
+
+            let vinfo = cleanup!(video_info(&caps))?;
+            let color_space = ColorSpace::from_video_info(&vinfo);
+            let layout = FrameLayout::from_video_info(&vinfo);
+            if layout.format.is_none() {
+                log::warn!(
+                    "unsupported video sink format {:?}; frames will render black",
+                    vinfo.format()
+                );
+            }
 
-        if framerate.is_nan()
-            || framerate.is_infinite()
-            || framerate < 0.0
-            || framerate.abs() < f64::EPSILON
-        {
-            let _ = pipeline.set_state(gst::State::Null);
-            return Err(Error::Framerate(framerate));
-        }
+            if framerate.is_nan()
+                || framerate.is_infinite()
+                || framerate < 0.0
+                || framerate.abs() < f64::EPSILON
+            {
+                let _ = pipeline.set_state(gst::State::Null);
+                return Err(Error::Framerate(framerate));
+            }
+            (width, height, framerate, color_space, layout)
+        };
 
-        let duration = Duration::from_nanos(
-            pipeline
-                .query_duration::<gst::ClockTime>()
-                .map(|duration| duration.nseconds())
-                .unwrap_or(0),
-        );
+        let duration = pipeline
+            .query_duration::<gst::ClockTime>()
+            .map(|duration| Duration::from_nanos(duration.nseconds()))
+            .or(known_duration)
+            .unwrap_or_default();
 
         let frame = Arc::new(Mutex::new(Frame::empty()));
         let upload_frame = Arc::new(AtomicBool::new(false));
+        let frame_generation = Arc::new(AtomicU64::new(0));
         let frame_buffer = Arc::new(Mutex::new(VecDeque::new()));
         // Default to a small buffer so the element can consume buffered frames
         let frame_buffer_capacity = Arc::new(AtomicUsize::new(
             options.frame_buffer_capacity.unwrap_or_default(),
         ));
+        let memory = MemoryTracker::register(Arc::clone(&frame), Arc::clone(&frame_buffer));
+        let spare_pixels_ref = memory.spare_pixels();
         let alive = Arc::new(AtomicBool::new(true));
+        let healthy = Arc::new(AtomicBool::new(true));
+        let healthy_ref = Arc::clone(&healthy);
+        let scrub = Arc::new(Mutex::new(None::<Scrub>));
+        let scrub_ref = Arc::clone(&scrub);
+        let seeks = Arc::new(SeekCoalescer::default());
+        let seeks_ref = Arc::clone(&seeks);
+        let position_watchers = Arc::new(PositionWatchers::default());
+        let position_watchers_ref = Arc::clone(&position_watchers);
         let last_frame_time = Arc::new(Mutex::new(Instant::now()));
         let initial_looping = options.looping.unwrap_or_default();
         let looping_flag = Arc::new(AtomicBool::new(initial_looping));
@@ -378,7 +1505,20 @@ impl Video {
         let speed_ref = Arc::clone(&speed_state);
 
         let frame_ref = Arc::clone(&frame);
+        let color_overrides = Arc::new(Mutex::new(ColorOverrides::default()));
+        let color_overrides_ref = Arc::clone(&color_overrides);
+        // Converting 4K frames at paint time drops frames, so the worker converts
+        // large ones as they arrive. On macOS tight NV12 is painted without conversion.
+        let preconvert = width.saturating_mul(height) >= PRECONVERT_MIN_PIXELS
+            && !(cfg!(target_os = "macos") && layout.is_tight_nv12(width as u32, height as u32));
         let upload_frame_ref = Arc::clone(&upload_frame);
+        let frame_generation_ref = Arc::clone(&frame_generation);
+        let frame_signal = Arc::new(FrameSignal::default());
+        let frame_signal_ref = Arc::clone(&frame_signal);
+        let external_consumer = Arc::new(AtomicBool::new(false));
+        let external_consumer_ref = Arc::clone(&external_consumer);
+        let pull_on_paint = Arc::new(AtomicBool::new(options.pull_on_paint.unwrap_or_default()));
+        let pull_on_paint_ref = Arc::clone(&pull_on_paint);
         let frame_buffer_ref = Arc::clone(&frame_buffer);
         let frame_buffer_capacity_ref = Arc::clone(&frame_buffer_capacity);
         let alive_ref = Arc::clone(&alive);
@@ -388,14 +1528,143 @@ impl Video {
         let upload_text = Arc::new(AtomicBool::new(false));
         let subtitle_text_ref = Arc::clone(&subtitle_text);
         let upload_text_ref = Arc::clone(&upload_text);
+        let spectrum = Arc::new(Mutex::new(SpectrumState::default()));
+        let spectrum_ref = Arc::clone(&spectrum);
+        let levels = Arc::new(Mutex::new(LevelState::default()));
+        let levels_ref = Arc::clone(&levels);
+        let watched = Arc::new(Mutex::new(WatchTracker::new(
+            options
+                .progress_milestones
+                .clone()
+                .unwrap_or_else(|| DEFAULT_MILESTONES.to_vec()),
+        )));
+        let watched_ref = Arc::clone(&watched);
+        let audio_window = Arc::new(Mutex::new(AudioWindow::new(
+            options.audio_window.unwrap_or_default(),
+        )));
+        if options.audio_window.is_some() {
+            waveform::attach_audio_tap(&pipeline, Arc::clone(&audio_window));
+        }
+        let events = Arc::new(EventHub::default());
+        let events_ref = Arc::clone(&events);
+        let recorder = Arc::new(Mutex::new(None::<Recorder>));
+        let recorder_ref = Arc::clone(&recorder);
+        let preview_return = Arc::new(Mutex::new(None));
+        let preview_return_ref = Arc::clone(&preview_return);
+        let loop_range = Arc::new(Mutex::new(None));
+        let loop_range_ref = Arc::clone(&loop_range);
+        let skip_segments = Arc::new(Mutex::new(Vec::new()));
+        let skip_segments_ref = Arc::clone(&skip_segments);
+        let silence = Arc::new(Mutex::new(None::<Vec<TimeRange>>));
+        let silence_ref = Arc::clone(&silence);
+        let skip_silence = Arc::new(AtomicBool::new(false));
+        let skip_silence_ref = Arc::clone(&skip_silence);
 
         let pipeline_ref = pipeline.clone();
-        let bus_ref = pipeline_ref.bus().unwrap();
+        let bus_ref = bus.clone();
         let is_eos = Arc::new(AtomicBool::new(false));
         let is_eos_ref = Arc::clone(&is_eos);
+        let buffering_percent = Arc::new(AtomicU8::new(100));
+        let buffering_percent_ref = Arc::clone(&buffering_percent);
+        let resume_after_buffering = Arc::new(AtomicBool::new(false));
+        let resume_after_buffering_ref = Arc::clone(&resume_after_buffering);
+        let error = Arc::new(Mutex::new(None));
+        let error_ref = Arc::clone(&error);
+        let missing_plugins = Arc::new(Mutex::new(Vec::new()));
+        let missing_plugins_ref = Arc::clone(&missing_plugins);
+        let streams = Arc::new(Mutex::new(StreamState::default()));
+        let streams_ref = Arc::clone(&streams);
+        let chapters = Arc::new(Mutex::new(Vec::new()));
+        let chapters_ref = Arc::clone(&chapters);
+        let health = Arc::new(Mutex::new(StreamHealth::default()));
+        let health_ref = Arc::clone(&health);
+        let stats_ref = Arc::clone(&stats);
+        let metadata = Arc::new(Mutex::new(Metadata::default()));
+        let metadata_ref = Arc::clone(&metadata);
+        let orientation = Arc::new(Mutex::new(Orientation::default()));
+        let orientation_ref = Arc::clone(&orientation);
+        let external_clock = Arc::new(Mutex::new(None::<ExternalClock>));
+        let external_clock_ref = Arc::clone(&external_clock);
+
+        let network_retries = options.network_retries.unwrap_or_default();
+        let recovery = options.recovery;
+        let network_source = pipeline.find_property("uri").is_some()
+            && pipeline
+                .property::<Option<String>>("uri")
+                .is_some_and(|uri| is_network_uri(&uri));
+        let uri_refresher = options.uri_refresher.clone();
+
+        let worker_priority = options.worker_priority.unwrap_or_default();
+        let worker_affinity = options.worker_affinity.clone();
+
+        // Apply initial playback speed if specified (must be after the pipeline prerolled).
+        // Done before the worker exists, so failing here leaves no thread behind.
+        if (initial_speed - 1.0).abs() > f64::EPSILON {
+            let position = cleanup!(
+                pipeline
+                    .query_position::<gst::ClockTime>()
+                    .ok_or(Error::Duration)
+            )?;
+            if initial_speed > 0.0 {
+                let (stop_type, stop) = match stop_at {
+                    Some(stop) => (
+                        gst::SeekType::Set,
+                        gst::ClockTime::from_nseconds(stop.as_nanos() as u64),
+                    ),
+                    None => (gst::SeekType::End, gst::ClockTime::ZERO),
+                };
+                cleanup!(pipeline.seek(
+                    initial_speed,
+                    gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                    gst::SeekType::Set,
+                    position,
+                    stop_type,
+                    stop,
+                ))?;
+            } else {
+                cleanup!(pipeline.seek(
+                    initial_speed,
+                    gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                    gst::SeekType::Set,
+                    gst::ClockTime::from_seconds(0),
+                    gst::SeekType::Set,
+                    position,
+                ))?;
+            }
+        }
 
+        let video_sink_handle = video_sink.clone();
+        let worker_guard = WorkerGuard::new();
         let worker = std::thread::spawn(move || {
+            let _worker_guard = worker_guard;
+            thread::mark_worker_thread();
+            thread::configure_current_thread(worker_priority, worker_affinity.as_deref());
+
             let mut clear_subtitles_at = None;
+            let mut last_position = Duration::ZERO;
+            let mut reconnect_attempts = 0;
+            // Set once the URL was refreshed, until frames flow again, so a
+            // refresher handing out bad URLs cannot loop.
+            let mut uri_refreshed = false;
+            let mut recover_attempts = 0;
+            // When the pipeline is restarted after a fatal error; errors posted
+            // meanwhile come from the same failure.
+            let mut recover_at: Option<Instant> = None;
+            // When the source is reconnected after a network error, backing off
+            // like recovery restarts.
+            let mut reconnect_at: Option<Instant> = None;
+            // Seek and play left for after a restarted source prerolls, so the
+            // worker never waits for it.
+            let mut pending_resume: Option<PendingResume> = None;
+            // Frame pulled ahead of the external clock, shown once the clock reaches it.
+            let mut held_sample: Option<gst::Sample> = None;
+            let mut last_clock_time = Duration::ZERO;
+            // QoS messages carry a running drop count per element.
+            let mut dropped_by_element: HashMap<String, u64> = HashMap::new();
+            let mut reverse_reorder = ReverseReorder::default();
+            // Range jumped over last, so a seek that has not landed yet is not repeated.
+            let mut last_skipped: Option<TimeRange> = None;
+            let mut consecutive_panics = 0;
 
             while alive_ref.load(Ordering::Acquire) {
                 // Drain bus messages to detect EOS/errors
@@ -428,16 +1697,172 @@ impl Video {
                                         upload_frame_ref.store(false, Ordering::SeqCst);
                                         *subtitle_text_ref.lock() = None;
                                         upload_text_ref.store(true, Ordering::SeqCst);
+                                        spectrum_ref.lock().clear();
+                                        levels_ref.lock().clear();
                                         *last_frame_time_ref.lock() = Instant::now();
+                                        events_ref.emit(VideoEvent::Looped);
                                         continue;
                                     }
                                     Err(err) => {
                                         log::error!("failed to restart video for looping: {}", err);
                                         is_eos_ref.store(true, Ordering::SeqCst);
+                                        frame_signal_ref.notify();
+                                        events_ref.emit(VideoEvent::Ended);
                                     }
                                 }
                             } else {
                                 is_eos_ref.store(true, Ordering::SeqCst);
+                                frame_signal_ref.notify();
+                                events_ref.emit(VideoEvent::Ended);
+                            }
+                        }
+                        MessageView::SegmentDone(_) => {
+                            if let Some(at) = preview_return_ref.lock().take() {
+                                if let Err(err) = pipeline_ref.set_state(gst::State::Paused) {
+                                    log::error!("failed to pause after preview: {}", err);
+                                }
+                                let current_speed =
+                                    f64::from_bits(speed_ref.load(Ordering::SeqCst));
+                                if let Err(err) = pipeline_ref.seek(
+                                    current_speed,
+                                    gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                                    gst::SeekType::Set,
+                                    gst::ClockTime::from_nseconds(at.as_nanos() as u64),
+                                    gst::SeekType::Set,
+                                    stop_at.map(|stop| {
+                                        gst::ClockTime::from_nseconds(stop.as_nanos() as u64)
+                                    }),
+                                ) {
+                                    log::error!("failed to return to preview boundary: {}", err);
+                                }
+                                frame_buffer_ref.lock().clear();
+                                events_ref.emit(VideoEvent::PreviewFinished);
+                            } else if let Some(range) = *loop_range_ref.lock() {
+                                // A non-flushing segment seek keeps already queued data
+                                // playing, so the jump back to `start` is seamless.
+                                let current_speed =
+                                    f64::from_bits(speed_ref.load(Ordering::SeqCst));
+                                match pipeline_ref.seek(
+                                    current_speed,
+                                    gst::SeekFlags::SEGMENT | gst::SeekFlags::ACCURATE,
+                                    gst::SeekType::Set,
+                                    gst::ClockTime::from_nseconds(range.start.as_nanos() as u64),
+                                    gst::SeekType::Set,
+                                    gst::ClockTime::from_nseconds(range.end.as_nanos() as u64),
+                                ) {
+                                    Ok(_) => events_ref.emit(VideoEvent::Looped),
+                                    Err(err) => {
+                                        log::error!("failed to restart loop range: {}", err)
+                                    }
+                                }
+                            }
+                        }
+                        MessageView::Warning(warning) => {
+                            log::warn!(
+                                "gstreamer warning from {:?}: {}",
+                                warning.src(),
+                                warning.error()
+                            );
+                            let health = {
+                                let mut health = health_ref.lock();
+                                health.warnings += 1;
+                                health.last_issue_at = Some(last_position);
+                                health.last_warning = Some(warning.error().to_string());
+                                health.clone()
+                            };
+                            events_ref.emit(VideoEvent::StreamHealth(health));
+                        }
+                        MessageView::Qos(qos) => {
+                            let (_processed, dropped) = qos.stats();
+                            let dropped = dropped.value().max(0) as u64;
+                            let element = qos
+                                .src()
+                                .map(|src| src.path_string().to_string())
+                                .unwrap_or_default();
+                            let previous = dropped_by_element.insert(element, dropped);
+                            if previous == Some(dropped) {
+                                continue;
+                            }
+                            let health = {
+                                let mut health = health_ref.lock();
+                                health.dropped_frames = dropped_by_element.values().sum();
+                                stats_ref.lock().set_dropped_total(health.dropped_frames);
+                                health.last_issue_at = Some(last_position);
+                                health.clone()
+                            };
+                            events_ref.emit(VideoEvent::StreamHealth(health));
+                        }
+                        MessageView::Tag(tag) => {
+                            let tags = tag.tags();
+                            if let Some(value) = tags.get::<gst::tags::ImageOrientation>()
+                                && let Some(parsed) = Orientation::parse(value.get())
+                            {
+                                *orientation_ref.lock() = parsed;
+                            }
+                            metadata_ref.lock().merge(&tags);
+                        }
+                        MessageView::Toc(toc) => {
+                            let (toc, _updated) = toc.toc();
+                            *chapters_ref.lock() = chapters_from_toc(&toc, duration);
+                        }
+                        MessageView::Element(element) => {
+                            let Some(s) = element.structure() else {
+                                continue;
+                            };
+                            if let Some(name) = capabilities::missing_plugin(s) {
+                                log::warn!("missing GStreamer plugin: {name}");
+                                let mut missing = missing_plugins_ref.lock();
+                                if !missing.contains(&name) {
+                                    missing.push(name.clone());
+                                    events_ref.emit(VideoEvent::MissingPlugin { name });
+                                }
+                            } else if !spectrum_ref.lock().push_message(s) {
+                                levels_ref.lock().push_message(s);
+                            }
+                        }
+                        MessageView::AsyncDone(_) => {
+                            seeks_ref.settle();
+                            if let Some(resume) = pending_resume.take()
+                                && let Err(err) = resume.apply(&pipeline_ref)
+                            {
+                                log::error!("failed to resume the restarted source: {err}");
+                            }
+                        }
+                        MessageView::StreamCollection(collection) => {
+                            streams_ref.lock().collection = Some(collection.stream_collection());
+                            events_ref.emit(VideoEvent::TracksChanged);
+                        }
+                        MessageView::StreamsSelected(selected) => {
+                            streams_ref.lock().selected = selected
+                                .streams()
+                                .filter_map(|stream| stream.stream_id())
+                                .map(|id| id.to_string())
+                                .collect();
+                            events_ref.emit(VideoEvent::TracksChanged);
+                        }
+                        MessageView::Buffering(buffering) => {
+                            let percent = buffering.percent().clamp(0, 100) as u8;
+                            let previous = buffering_percent_ref.swap(percent, Ordering::SeqCst);
+                            if percent != previous {
+                                events_ref.emit(VideoEvent::Buffering { percent });
+                            }
+                            // Live sources cannot be paused to refill; they just drop late frames.
+                            if live {
+                                continue;
+                            }
+                            if percent < 100 {
+                                if !resume_after_buffering_ref.load(Ordering::SeqCst)
+                                    && pipeline_ref.current_state() == gst::State::Playing
+                                {
+                                    resume_after_buffering_ref.store(true, Ordering::SeqCst);
+                                    if let Err(err) = pipeline_ref.set_state(gst::State::Paused) {
+                                        log::error!("failed to pause for buffering: {err}");
+                                    }
+                                }
+                            } else if resume_after_buffering_ref.swap(false, Ordering::SeqCst)
+                                && let Err(err) = pipeline_ref.set_state(gst::State::Playing)
+                            {
+                                log::error!("failed to resume after buffering: {err}");
                             }
                         }
                         MessageView::Error(err) => {
@@ -447,167 +1872,501 @@ impl Video {
                                 err.src(),
                                 err.error()
                             );
+
+                            if reconnect_at.is_some() || recover_at.is_some() {
+                                // Already restarting; errors posted meanwhile come
+                                // from the same failure.
+                            } else if network_source
+                                && !uri_refreshed
+                                && let Some(refresher) = &uri_refresher
+                                && is_expired_url_error(&err.error(), &debug)
+                            {
+                                uri_refreshed = true;
+                                let uri = (refresher.0)();
+                                log::debug!("source URL expired, reopening with a refreshed one");
+                                frame_buffer_ref.lock().clear();
+                                let resume_at = (!live).then_some(last_position);
+                                match restart_source(
+                                    &pipeline_ref,
+                                    resume_at,
+                                    Some(("uri", &uri)),
+                                    gst::State::Ready,
+                                ) {
+                                    Ok(resume) => pending_resume = resume,
+                                    Err(err) => log::error!("failed to reopen source: {}", err),
+                                }
+                                events_ref.emit(VideoEvent::UriRefreshed);
+                            } else if network_source
+                                && is_connection_error(&err.error())
+                                && reconnect_attempts < network_retries
+                            {
+                                reconnect_attempts += 1;
+                                let delay = recovery.unwrap_or_default().delay(reconnect_attempts);
+                                log::debug!(
+                                    "reconnecting to the source in {delay:?} (attempt {reconnect_attempts})"
+                                );
+                                reconnect_at = Some(Instant::now() + delay);
+                                events_ref.emit(VideoEvent::Reconnecting {
+                                    attempt: reconnect_attempts,
+                                });
+                            } else if let Some(policy) = recovery
+                                && recover_attempts < policy.max_attempts
+                                && recovery::is_recoverable(&err.error())
+                            {
+                                recover_attempts += 1;
+                                let delay = policy.delay(recover_attempts);
+                                log::debug!(
+                                    "restarting the pipeline in {delay:?} (attempt {recover_attempts})"
+                                );
+                                recover_at = Some(Instant::now() + delay);
+                                events_ref.emit(VideoEvent::Recovering {
+                                    attempt: recover_attempts,
+                                });
+                            } else {
+                                let uri = pipeline_ref
+                                    .find_property("uri")
+                                    .and_then(|_| pipeline_ref.property::<Option<String>>("uri"));
+                                let mut error = Error::from_bus(&err, uri.as_deref());
+                                // decodebin only says the stream could not be decoded;
+                                // its missing-plugin message says what to install.
+                                if matches!(
+                                    error,
+                                    Error::UnsupportedCodec { .. } | Error::MissingPlugin { .. }
+                                ) && let Some(name) = missing_plugins_ref.lock().first().cloned()
+                                {
+                                    error = Error::MissingPlugin { name };
+                                }
+                                let message = error.to_string();
+                                *error_ref.lock() = Some(message.clone());
+                                events_ref.emit(VideoEvent::Error(message.clone()));
+                                if recover_attempts > 0 {
+                                    events_ref.emit(VideoEvent::Failed {
+                                        attempts: std::mem::take(&mut recover_attempts),
+                                        error: message,
+                                    });
+                                }
+                            }
                         }
                         _ => {}
                     }
                 }
 
+                if reconnect_at.is_some_and(|at| Instant::now() >= at) {
+                    reconnect_at = None;
+                    frame_buffer_ref.lock().clear();
+                    let resume_at = (!live).then_some(last_position);
+                    match restart_source(&pipeline_ref, resume_at, None, gst::State::Ready) {
+                        Ok(resume) => pending_resume = resume,
+                        Err(err) => log::error!("failed to reconnect to source: {}", err),
+                    }
+                }
+
+                if recover_at.is_some_and(|at| Instant::now() >= at) {
+                    recover_at = None;
+                    frame_buffer_ref.lock().clear();
+                    let resume_at = (!live).then_some(last_position);
+                    match restart_source(&pipeline_ref, resume_at, None, gst::State::Null) {
+                        Ok(resume) => pending_resume = resume,
+                        Err(err) => log::error!("failed to restart the pipeline: {err}"),
+                    }
+                }
+
+                if position_watchers_ref.is_due()
+                    && let Some(position) = pipeline_ref.query_position::<gst::ClockTime>()
+                {
+                    position_watchers_ref.report(Duration::from_nanos(position.nseconds()));
+                }
+
+                if !live
+                    && !duration.is_zero()
+                    && watched_ref.lock().is_due()
+                    && let Some(position) = pipeline_ref.query_position::<gst::ClockTime>()
+                {
+                    let reached = {
+                        let mut watched = watched_ref.lock();
+                        watched.sample(Duration::from_nanos(position.nseconds()));
+                        watched.take_reached(duration)
+                    };
+                    for percent in reached {
+                        events_ref.emit(VideoEvent::ProgressMilestone { percent });
+                    }
+                }
+
+                // Release level intervals as playback reaches them, so subscribers
+                // see meters in step with what is heard. Spectrum frames are released
+                // here too, so they do not pile up while no visualizer paints.
+                let levels_pending = levels_ref.lock().is_pending();
+                let spectrum_pending = spectrum_ref.lock().is_pending();
+                if (levels_pending || spectrum_pending)
+                    && let Some(position) = pipeline_ref.query_position::<gst::ClockTime>()
+                {
+                    let position = Duration::from_nanos(position.nseconds());
+                    spectrum_ref.lock().release(position);
+                    let released = levels_ref.lock().advance(position);
+                    if let Some(levels) = released {
+                        events_ref.emit(VideoEvent::AudioLevels(levels));
+                    }
+                }
+
+                let skipping_silence = skip_silence_ref.load(Ordering::Acquire);
+                let has_skips = !skip_segments_ref.lock().is_empty()
+                    || (skipping_silence && silence_ref.lock().is_some());
+                if has_skips && let Some(position) = pipeline_ref.query_position::<gst::ClockTime>()
+                {
+                    let position = Duration::from_nanos(position.nseconds());
+                    let user_range = range_at(position, &skip_segments_ref.lock());
+                    let range = user_range.or_else(|| {
+                        skipping_silence
+                            .then(|| range_at(position, silence_ref.lock().as_deref()?))
+                            .flatten()
+                    });
+                    match range {
+                        Some(range) if last_skipped != Some(range) => {
+                            last_skipped = Some(range);
+                            match pipeline_ref.seek_simple(
+                                gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+                                gst::ClockTime::from_nseconds(range.end.as_nanos() as u64),
+                            ) {
+                                Ok(()) => {
+                                    frame_buffer_ref.lock().clear();
+                                    spectrum_ref.lock().clear();
+                                    levels_ref.lock().clear();
+                                    events_ref.emit(VideoEvent::Skipped {
+                                        from: position,
+                                        to: range.end,
+                                    });
+                                }
+                                Err(err) => log::warn!("failed to skip {range:?}: {err}"),
+                            }
+                        }
+                        Some(_) => {}
+                        None => last_skipped = None,
+                    }
+                }
+
+                seeks_ref.settle_stalled();
+
+                let scrub_target = scrub_ref.lock().as_mut().and_then(Scrub::due);
+                if let Some(target) = scrub_target {
+                    match scrub_seek(&pipeline_ref, target) {
+                        Ok(()) => {
+                            is_eos_ref.store(false, Ordering::SeqCst);
+                            frame_buffer_ref.lock().clear();
+                        }
+                        Err(err) => log::debug!("scrub seek to {target:?} failed: {err}"),
+                    }
+                }
+
                 if is_eos_ref.load(Ordering::Acquire) {
                     // Stop busy-polling once EOS reached
                     std::thread::sleep(Duration::from_millis(50));
                     continue;
                 }
-                if let Err(err) = (|| -> Result<(), gst::FlowError> {
-                    // Try to pull a new sample; on timeout just continue (no frame this tick)
-                    let maybe_sample =
-                        if pipeline_ref.state(gst::ClockTime::ZERO).1 != gst::State::Playing {
+                if pending_resume.is_some() {
+                    // A restarted source prerolls at its start; leave that frame for
+                    // the resume seek to flush instead of showing it.
+                    std::thread::sleep(Duration::from_millis(16));
+                    continue;
+                }
+                if audio_only
+                    || external_consumer_ref.load(Ordering::Acquire)
+                    || pull_on_paint_ref.load(Ordering::Acquire)
+                {
+                    // Nothing to pull; just keep draining the bus.
+                    std::thread::sleep(Duration::from_millis(16));
+                    continue;
+                }
+                // Frame handling runs app-facing code (clocks, conversion), so a panic
+                // there is caught and reported instead of silently freezing playback.
+                let processed =
+                    std::panic::catch_unwind(AssertUnwindSafe(|| -> Result<(), gst::FlowError> {
+                        let clock = external_clock_ref.lock().clone();
+                        if let Some(ExternalClock(clock)) = &clock {
+                            let now = clock();
+                            // The app's timeline jumped back; the held frame is no longer next.
+                            if now < last_clock_time {
+                                held_sample = None;
+                            }
+                            last_clock_time = now;
+                        }
+
+                        // Try to pull a new sample; on timeout just continue (no frame this tick)
+                        let maybe_sample = if let Some(sample) = held_sample.take() {
+                            Some(sample)
+                        } else if pipeline_ref.state(gst::ClockTime::ZERO).1 != gst::State::Playing
+                        {
                             video_sink.try_pull_preroll(gst::ClockTime::from_mseconds(16))
                         } else {
-                            video_sink.try_pull_sample(gst::ClockTime::from_mseconds(16))
+                            let sample =
+                                video_sink.try_pull_sample(gst::ClockTime::from_mseconds(16));
+                            if f64::from_bits(speed_ref.load(Ordering::SeqCst)) < 0.0 {
+                                reverse_reorder.push(sample)
+                            } else {
+                                reverse_reorder.clear();
+                                sample
+                            }
                         };
 
-                    let Some(sample) = maybe_sample else {
-                        // No sample available yet (timeout). Don't treat as error.
-                        return Ok(());
-                    };
+                        let Some(sample) = maybe_sample else {
+                            // No sample available yet (timeout). Don't treat as error.
+                            return Ok(());
+                        };
 
-                    *last_frame_time_ref.lock() = Instant::now();
+                        // The sink is unsynchronized while an external clock drives
+                        // presentation, so hold early frames until the clock catches up.
+                        if clock.is_some()
+                            && let (Some(segment), Some(pts)) = (
+                                sample.segment(),
+                                sample.buffer().and_then(|buffer| buffer.pts()),
+                            )
+                            && let Some(stream_time) = segment
+                                .downcast_ref::<gst::ClockTime>()
+                                .and_then(|segment| segment.to_stream_time(pts))
+                            && Duration::from_nanos(stream_time.nseconds()) > last_clock_time
+                        {
+                            held_sample = Some(sample);
+                            std::thread::sleep(Duration::from_millis(4));
+                            return Ok(());
+                        }
 
-                    let frame_segment = sample.segment().cloned().ok_or(gst::FlowError::Error)?;
-                    let buffer = sample.buffer().ok_or(gst::FlowError::Error)?;
-                    let frame_pts = buffer.pts().ok_or(gst::FlowError::Error)?;
-                    let frame_duration = buffer.duration().ok_or(gst::FlowError::Error)?;
+                        let now = Instant::now();
+                        let previous = std::mem::replace(&mut *last_frame_time_ref.lock(), now);
+                        stats_ref.lock().record_frame(now.duration_since(previous));
 
-                    // Store the NV12 sample directly for GPU processing
-                    {
-                        let mut frame_guard = frame_ref.lock();
-                        *frame_guard = Frame(sample);
-                    }
+                        let frame_segment =
+                            sample.segment().cloned().ok_or(gst::FlowError::Error)?;
+                        let buffer = sample.buffer().ok_or(gst::FlowError::Error)?;
+                        let frame_pts = buffer.pts().ok_or(gst::FlowError::Error)?;
+                        let frame_duration = buffer.duration().ok_or(gst::FlowError::Error)?;
 
-                    // Push into frame buffer if enabled, trimming to capacity
-                    let capacity = frame_buffer_capacity_ref.load(Ordering::SeqCst);
-                    if capacity > 0 {
-                        let sample_for_buffer = frame_ref.lock().0.clone();
-                        let mut buf = frame_buffer_ref.lock();
-                        buf.push_back(Frame(sample_for_buffer));
-                        while buf.len() > capacity {
-                            buf.pop_front();
+                        if let Some(recorder) = recorder_ref.lock().as_ref() {
+                            recorder.push_video(&sample);
                         }
-                    }
 
-                    // Always mark frame as ready for upload
-                    upload_frame_ref.store(true, Ordering::SeqCst);
-
-                    // Handle subtitles
-                    if let Some(at) = clear_subtitles_at
-                        && frame_pts >= at
-                    {
-                        *subtitle_text_ref.lock() = None;
-                        upload_text_ref.store(true, Ordering::SeqCst);
-                        clear_subtitles_at = None;
-                    }
+                        let frame = Frame::new(sample);
+                        if preconvert {
+                            let conversion_start = Instant::now();
+                            let mut pixels =
+                                spare_pixels_ref.take(width as usize * height as usize * 4);
+                            let converted = frame.with_data(|data| {
+                                convert::frame_into(
+                                    PixelOrder::Bgra,
+                                    &layout,
+                                    color_overrides_ref.lock().apply(color_space),
+                                    data,
+                                    width as u32,
+                                    height as u32,
+                                    &mut pixels,
+                                )
+                            });
+                            if let Ok(Some(())) = converted {
+                                frame.set_converted(pixels, Arc::clone(&spare_pixels_ref));
+                                stats_ref
+                                    .lock()
+                                    .record_conversion(conversion_start.elapsed());
+                            } else {
+                                spare_pixels_ref.put(pixels);
+                            }
+                        }
 
-                    let text = text_sink
-                        .as_ref()
-                        .and_then(|sink| sink.try_pull_sample(gst::ClockTime::from_seconds(0)));
-                    if let Some(text) = text {
-                        let text_segment = text.segment().ok_or(gst::FlowError::Error)?;
-                        let text = text.buffer().ok_or(gst::FlowError::Error)?;
-                        let text_pts = text.pts().ok_or(gst::FlowError::Error)?;
-                        let text_duration = text.duration().ok_or(gst::FlowError::Error)?;
-
-                        let frame_running_time = frame_segment.to_running_time(frame_pts).value();
-                        let frame_running_time_end = frame_segment
-                            .to_running_time(frame_pts + frame_duration)
-                            .value();
-
-                        let text_running_time = text_segment.to_running_time(text_pts).value();
-                        let text_running_time_end = text_segment
-                            .to_running_time(text_pts + text_duration)
-                            .value();
-
-                        if text_running_time_end > frame_running_time
-                            && frame_running_time_end > text_running_time
+                        // Store the NV12 sample directly for GPU processing
                         {
-                            let duration = text.duration().unwrap_or(gst::ClockTime::ZERO);
-                            let map = text.map_readable().map_err(|_| gst::FlowError::Error)?;
+                            let mut frame_guard = frame_ref.lock();
+                            *frame_guard = frame;
+                        }
+
+                        // Push into frame buffer if enabled, trimming to capacity
+                        let capacity = frame_buffer_capacity_ref.load(Ordering::SeqCst);
+                        if capacity > 0 {
+                            let frame_for_buffer = frame_ref.lock().clone();
+                            let mut buf = frame_buffer_ref.lock();
+                            buf.push_back(frame_for_buffer);
+                            while buf.len() > capacity {
+                                buf.pop_front();
+                            }
+                            drop(buf);
+                            memory::frame_buffered();
+                        }
+
+                        let stream_time = frame_segment.to_stream_time(frame_pts).value();
+                        if stream_time >= 0 {
+                            last_position = Duration::from_nanos(stream_time as u64);
+                        }
+                        uri_refreshed = false;
+                        if reconnect_attempts > 0 {
+                            reconnect_attempts = 0;
+                            *error_ref.lock() = None;
+                            events_ref.emit(VideoEvent::Reconnected);
+                        }
+                        if recover_attempts > 0 {
+                            events_ref.emit(VideoEvent::Recovered {
+                                attempts: std::mem::take(&mut recover_attempts),
+                            });
+                        }
+
+                        // Always mark frame as ready for upload
+                        upload_frame_ref.store(true, Ordering::SeqCst);
+                        frame_generation_ref.fetch_add(1, Ordering::SeqCst);
+                        frame_signal_ref.notify();
 
-                            let text = std::str::from_utf8(map.as_slice())
-                                .map_err(|_| gst::FlowError::Error)?
-                                .to_string();
-                            *subtitle_text_ref.lock() = Some(text);
+                        // Handle subtitles
+                        if let Some(at) = clear_subtitles_at
+                            && frame_pts >= at
+                        {
+                            *subtitle_text_ref.lock() = None;
                             upload_text_ref.store(true, Ordering::SeqCst);
+                            clear_subtitles_at = None;
+                        }
 
-                            clear_subtitles_at = Some(text_pts + duration);
+                        let text = text_sink
+                            .as_ref()
+                            .and_then(|sink| sink.try_pull_sample(gst::ClockTime::from_seconds(0)));
+                        if let Some(text) = text {
+                            let text_segment = text.segment().ok_or(gst::FlowError::Error)?;
+                            let text = text.buffer().ok_or(gst::FlowError::Error)?;
+                            let text_pts = text.pts().ok_or(gst::FlowError::Error)?;
+                            let text_duration = text.duration().ok_or(gst::FlowError::Error)?;
+
+                            let frame_running_time =
+                                frame_segment.to_running_time(frame_pts).value();
+                            let frame_running_time_end = frame_segment
+                                .to_running_time(frame_pts + frame_duration)
+                                .value();
+
+                            let text_running_time = text_segment.to_running_time(text_pts).value();
+                            let text_running_time_end = text_segment
+                                .to_running_time(text_pts + text_duration)
+                                .value();
+
+                            if text_running_time_end > frame_running_time
+                                && frame_running_time_end > text_running_time
+                            {
+                                let duration = text.duration().unwrap_or(gst::ClockTime::ZERO);
+                                let map = text.map_readable().map_err(|_| gst::FlowError::Error)?;
+
+                                let text = std::str::from_utf8(map.as_slice())
+                                    .map_err(|_| gst::FlowError::Error)?
+                                    .to_string();
+                                *subtitle_text_ref.lock() = Some(text);
+                                upload_text_ref.store(true, Ordering::SeqCst);
+
+                                clear_subtitles_at = Some(text_pts + duration);
+                            }
+                        }
+                        Ok(())
+                    }));
+                match processed {
+                    Ok(result) => {
+                        consecutive_panics = 0;
+                        // Only log non-EOS errors
+                        if let Err(err) = result
+                            && err != gst::FlowError::Eos
+                        {
+                            log::error!("error processing frame: {:?}", err);
                         }
                     }
-
-                    Ok(())
-                })() {
-                    // Only log non-EOS errors
-                    if err != gst::FlowError::Eos {
-                        log::error!("error processing frame: {:?}", err);
+                    Err(payload) => {
+                        consecutive_panics += 1;
+                        let message =
+                            format!("frame processing panicked: {}", panic_message(&*payload));
+                        log::error!("{message}");
+                        events_ref.emit(VideoEvent::Error(message.clone()));
+                        // The panicking frame may have been half-handled; start clean.
+                        held_sample = None;
+                        reverse_reorder.clear();
+                        if consecutive_panics >= MAX_CONSECUTIVE_PANICS {
+                            // The panic repeats on every frame; stop rather than spin.
+                            healthy_ref.store(false, Ordering::SeqCst);
+                            *error_ref.lock() = Some(message);
+                            if let Err(err) = pipeline_ref.set_state(gst::State::Null) {
+                                log::error!("failed to stop pipeline after worker panics: {err}");
+                            }
+                            break;
+                        }
                     }
                 }
             }
         });
 
-        // Apply initial playback speed if specified (must be after pipeline started)
-        if (initial_speed - 1.0).abs() > f64::EPSILON {
-            let position = cleanup!(
-                pipeline
-                    .query_position::<gst::ClockTime>()
-                    .ok_or(Error::Caps)
-            )?;
-            if initial_speed > 0.0 {
-                cleanup!(pipeline.seek(
-                    initial_speed,
-                    gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
-                    gst::SeekType::Set,
-                    position,
-                    gst::SeekType::End,
-                    gst::ClockTime::from_seconds(0),
-                ))?;
-            } else {
-                cleanup!(pipeline.seek(
-                    initial_speed,
-                    gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
-                    gst::SeekType::Set,
-                    gst::ClockTime::from_seconds(0),
-                    gst::SeekType::Set,
-                    position,
-                ))?;
-            }
-        }
-
-        Ok(Video(Arc::new(RwLock::new(Internal {
+        let video = Video(Arc::new(RwLock::new(Internal {
             id,
-            bus: pipeline.bus().unwrap(),
+            bus,
             source: pipeline,
+            video_sink: video_sink_handle,
             alive,
+            healthy,
+            scrub,
+            seeks,
+            position_watchers,
             worker: Some(worker),
+            pipeline_guard: PipelineGuard::new(),
 
             width,
             height,
             framerate,
             duration,
+            live,
+            color_space,
+            color_overrides,
+            layout,
+            audio_only,
+            external_consumer,
+            pull_on_paint,
             speed: speed_state,
 
             frame,
             upload_frame,
+            frame_generation,
+            frame_signal,
             frame_buffer,
             frame_buffer_capacity,
+            memory,
             last_frame_time,
             looping: looping_flag,
             is_eos,
             restart_stream: false,
+            buffering_percent,
+            resume_after_buffering,
+            error,
+            missing_plugins,
+            streams,
+            chapters,
+            health,
+            stats,
+            metadata,
+            orientation,
+            external_clock,
+            external_clock_max_buffers: Mutex::new(None),
+            poster: Mutex::new(None),
+            poster_loaded: AtomicBool::new(false),
+            decode_priority: AtomicU8::new(options.decode_priority.unwrap_or_default() as u8),
 
             subtitle_text,
             upload_text,
 
+            spectrum,
+            levels,
+            watched,
+            audio_window,
+            events,
+            recorder,
+            preview_return,
+            loop_range,
+            stop_at,
+            skip_segments,
+            silence,
+            skip_silence,
+            silence_analyzing: Arc::new(AtomicBool::new(false)),
+
             display_width_override: None,
             display_height_override: None,
-        }))))
+        })));
+        video.read().seeks.bind(Arc::downgrade(&video.0));
+        power::register(&video);
+        let admitted = governor::request_play(&video);
+        video.write().set_paused(!admitted)?;
+        subtitles::lookup(&video);
+        Ok(video)
     }
 
     pub(crate) fn read(&'_ self) -> parking_lot::RwLockReadGuard<'_, Internal> {
@@ -651,11 +2410,15 @@ impl Video {
 
     /// Get the effective display size honoring overrides. If only one of
     /// width/height is overridden, the other is inferred from the natural
-    /// aspect ratio, rounded to nearest pixel.
+    /// aspect ratio, rounded to nearest pixel. Sideways-rotated videos (see
+    /// [`Metadata::rotation`]) report their upright size.
     pub fn display_size(&self) -> (u32, u32) {
         let inner = self.read();
-        let natural_w = inner.width.max(0) as u32;
-        let natural_h = inner.height.max(0) as u32;
+        let mut natural_w = inner.width.max(0) as u32;
+        let mut natural_h = inner.height.max(0) as u32;
+        if inner.orientation.lock().swaps_axes() {
+            std::mem::swap(&mut natural_w, &mut natural_h);
+        }
         let ar = if natural_h == 0 {
             1.0
         } else {
@@ -680,67 +2443,665 @@ impl Video {
         }
     }
 
-    /// Get the framerate of the video as frames per second.
-    pub fn framerate(&self) -> f64 {
-        self.read().framerate
+    /// Colorimetry used to convert frames: the negotiated one with any overrides.
+    pub(crate) fn color_space(&self) -> ColorSpace {
+        let inner = self.read();
+        let overrides = *inner.color_overrides.lock();
+        overrides.apply(inner.color_space)
     }
 
-    /// Set the volume multiplier of the audio.
-    pub fn set_volume(&self, volume: f64) {
-        {
-            let inner = self.write();
-            inner.source.set_property("volume", volume);
-        }
-        let muted = self.muted();
-        self.set_muted(muted);
+    pub(crate) fn color_overrides(&self) -> ColorOverrides {
+        *self.read().color_overrides.lock()
     }
 
-    /// Get the volume multiplier of the audio.
-    pub fn volume(&self) -> f64 {
-        self.read().source.property("volume")
+    /// Force the YUV range used to convert frames, for files flagged full range but
+    /// encoded limited (washed-out blacks) or the reverse (crushed blacks). None
+    /// goes back to what the file declares.
+    pub fn override_color_range(&self, range: Option<ColorRange>) {
+        self.update_color_overrides(|overrides| overrides.range = range);
     }
 
-    /// Set if the audio is muted or not.
-    pub fn set_muted(&self, muted: bool) {
-        self.write().source.set_property("mute", muted);
+    pub fn color_range_override(&self) -> Option<ColorRange> {
+        self.read().color_overrides.lock().range
     }
 
-    /// Get if the audio is muted or not.
-    pub fn muted(&self) -> bool {
-        self.read().source.property("mute")
+    /// Force the YUV to RGB matrix used to convert frames, for files flagged with the
+    /// wrong one (shifted reds and greens). None goes back to what the file declares,
+    /// or BT.709 for HD and BT.601 for SD when it declares nothing.
+    pub fn override_matrix(&self, matrix: Option<ColorMatrix>) {
+        self.update_color_overrides(|overrides| overrides.matrix = matrix);
     }
 
-    /// Get if the stream ended or not.
-    pub fn eos(&self) -> bool {
-        self.read().is_eos.load(Ordering::Acquire)
+    pub fn matrix_override(&self) -> Option<ColorMatrix> {
+        self.read().color_overrides.lock().matrix
     }
 
-    /// Get if the media will loop or not.
-    pub fn looping(&self) -> bool {
-        self.read().looping.load(Ordering::SeqCst)
+    fn update_color_overrides(&self, update: impl FnOnce(&mut ColorOverrides)) {
+        let inner = self.read();
+        update(&mut inner.color_overrides.lock());
+        // Pixels converted ahead of painting used the previous colors.
+        inner.frame.lock().take_converted();
+        for frame in inner.frame_buffer.lock().iter() {
+            frame.take_converted();
+        }
     }
 
-    /// Set if the media will loop or not.
-    pub fn set_looping(&self, looping: bool) {
-        self.write().looping.store(looping, Ordering::SeqCst);
+    /// Format and plane layout of the raw frame data returned by the frame accessors.
+    pub(crate) fn orientation(&self) -> Orientation {
+        *self.read().orientation.lock()
     }
 
-    /// Set if the media is paused or not.
-    pub fn set_paused(&self, paused: bool) {
-        self.write().set_paused(paused)
+    pub(crate) fn frame_layout(&self) -> FrameLayout {
+        self.read().layout
     }
 
-    /// Get if the media is paused or not.
+    /// Get the pixel format negotiated with the video sink, or None if the sink
+    /// was configured with a format this crate cannot convert.
+    pub fn pixel_format(&self) -> Option<PixelFormat> {
+        self.read().layout.format
+    }
+
+    /// Get how full the network buffer is, from 0 to 100. Playback is held
+    /// paused while this is below 100 and resumes automatically once it refills.
+    pub fn buffering_percent(&self) -> u8 {
+        self.read().buffering_percent.load(Ordering::SeqCst)
+    }
+
+    /// Get the video decoder in use and whether it runs on hardware, or None
+    /// before the stream is set up or for media without video.
+    pub fn decoder_info(&self) -> Option<DecoderInfo> {
+        decoder::video_decoder(&self.read().source)
+    }
+
+    /// Get the network buffering limits in effect. Fields are None when the
+    /// pipeline has no such setting, as custom pipelines may not.
+    pub fn network_tuning(&self) -> NetworkTuning {
+        NetworkTuning::read(self.read().source.upcast_ref())
+    }
+
+    /// Change network buffering limits; None fields are left as they are.
+    ///
+    /// playbin sizes its buffers when it opens a source, so new limits apply from
+    /// the next reconnect or source change. Use [`VideoOptions::network_tuning`] to
+    /// limit the first one.
+    pub fn set_network_tuning(&self, tuning: NetworkTuning) {
+        tuning.apply(self.read().source.upcast_ref());
+    }
+
+    /// Get the message of the fatal pipeline error that stopped playback, if any.
+    pub fn error(&self) -> Option<String> {
+        self.read().error.lock().clone()
+    }
+
+    /// Plugins GStreamer reported missing while opening the media, described as
+    /// e.g. `H.265 (Main Profile) decoder`. A stream they were needed for is not
+    /// played, though the others may be.
+    pub fn missing_plugins(&self) -> Vec<String> {
+        self.read().missing_plugins.lock().clone()
+    }
+
+    /// Whether playback can still make progress: no fatal pipeline error was
+    /// reported and the worker thread is running. A panic while handling a frame
+    /// is reported as [`VideoEvent::Error`] and the worker carries on; after
+    /// several in a row it stops the pipeline and this turns false.
+    pub fn is_healthy(&self) -> bool {
+        let inner = self.read();
+        inner.healthy.load(Ordering::SeqCst) && inner.error.lock().is_none()
+    }
+
+    /// Get the totals of recoverable problems (warnings, dropped frames) reported so far.
+    /// Updates are also emitted as [`VideoEvent::StreamHealth`].
+    pub fn stream_health(&self) -> StreamHealth {
+        self.read().health.lock().clone()
+    }
+
+    /// Get recent frame intervals, conversion times, and dropped frames, e.g. to
+    /// diagnose stutter. [`VideoElement::debug_overlay`](crate::VideoElement::debug_overlay)
+    /// draws them over the video.
+    pub fn playback_stats(&self) -> PlaybackStats {
+        self.read().stats.lock().clone()
+    }
+
+    /// Get decoded and dropped frame counts, decode time, frame rate, queue depth,
+    /// and bitrate at a glance, e.g. for a performance panel or telemetry.
+    /// [`VideoElement::stats_overlay`](crate::VideoElement::stats_overlay) draws
+    /// them over the video.
+    pub fn stats(&self) -> VideoStats {
+        let inner = self.read();
+        let queue_depth = inner.frame_buffer.lock().len();
+        let stats = inner.stats.lock();
+        VideoStats {
+            decoded_frames: stats.decoded_total,
+            dropped_frames: stats.dropped_total,
+            mean_decode_time: stats.mean_decode_time(),
+            fps: stats.fps(),
+            queue_depth,
+            bitrate: stats.bitrate(),
+        }
+    }
+
+    /// Get the title, artist, codecs, bitrate, and other tags read from the media.
+    pub fn metadata(&self) -> Metadata {
+        self.read().metadata.lock().clone()
+    }
+
+    /// Get the chapters from the media's table of contents, sorted by start time.
+    /// Empty if the container has none. Seek to [`Chapter::start`] to jump to one.
+    pub fn chapters(&self) -> Vec<Chapter> {
+        self.read().chapters.lock().clone()
+    }
+
+    /// Get if the video is a live stream (e.g. RTSP) that cannot be seeked and
+    /// has no duration.
+    pub fn is_live(&self) -> bool {
+        self.read().live
+    }
+
+    /// Get if the video uses an HDR transfer function (PQ or HLG) and is being
+    /// tone-mapped for display.
+    pub fn is_hdr(&self) -> bool {
+        self.read().color_space.is_hdr()
+    }
+
+    /// Get the framerate of the video as frames per second.
+    pub fn framerate(&self) -> f64 {
+        self.read().framerate
+    }
+
+    /// Set the volume multiplier of the audio.
+    pub fn set_volume(&self, volume: f64) {
+        {
+            let inner = self.write();
+            inner.source.set_property("volume", volume);
+        }
+        let muted = self.muted();
+        self.set_muted(muted);
+    }
+
+    /// Get the volume multiplier of the audio.
+    pub fn volume(&self) -> f64 {
+        self.read().source.property("volume")
+    }
+
+    /// Set the gain of equalizer band `index` in dB, from -24 to +12; 0 is flat.
+    /// Requires an equalizer in `VideoOptions::audio_filters`.
+    pub fn set_eq_band(&self, index: usize, gain_db: f64) -> Result<(), Error> {
+        let equalizer = self.audio_filter(EQUALIZER_NAME)?;
+        let band = format!("band{index}");
+        if equalizer.find_property(&band).is_none() {
+            return Err(Error::EqBand(index));
+        }
+        equalizer.set_property(&band, gain_db.clamp(-24.0, 12.0));
+        Ok(())
+    }
+
+    /// Get the gain of equalizer band `index` in dB.
+    pub fn eq_band(&self, index: usize) -> Result<f64, Error> {
+        let equalizer = self.audio_filter(EQUALIZER_NAME)?;
+        let band = format!("band{index}");
+        if equalizer.find_property(&band).is_none() {
+            return Err(Error::EqBand(index));
+        }
+        Ok(equalizer.property(&band))
+    }
+
+    /// Set how strongly centered vocals are removed, from 0.0 (off) to 1.0.
+    /// Requires `karaoke` in `VideoOptions::audio_filters`.
+    pub fn set_karaoke_level(&self, level: f32) -> Result<(), Error> {
+        self.audio_filter(KARAOKE_NAME)?
+            .set_property("level", level.clamp(0.0, 1.0));
+        Ok(())
+    }
+
+    /// Shift the audio pitch by `factor` (e.g. 2.0 is an octave up) without
+    /// changing tempo. Requires `pitch` in `VideoOptions::audio_filters`.
+    pub fn set_pitch(&self, factor: f32) -> Result<(), Error> {
+        self.audio_filter(PITCH_NAME)?
+            .set_property("pitch", factor.clamp(0.1, 10.0));
+        Ok(())
+    }
+
+    /// Adjust the picture: `brightness` and `hue` from -1.0 to 1.0 (neutral 0.0),
+    /// `contrast` and `saturation` from 0.0 to 2.0 (neutral 1.0). Values outside
+    /// are clamped. Requires `VideoOptions::color_balance`.
+    pub fn set_color_balance(
+        &self,
+        brightness: f64,
+        contrast: f64,
+        saturation: f64,
+        hue: f64,
+    ) -> Result<(), Error> {
+        let balance = self.color_balance_filter()?;
+        ColorBalance {
+            brightness,
+            contrast,
+            saturation,
+            hue,
+        }
+        .apply(&balance);
+        // The filter only touches frames decoded from now on, so a paused video
+        // decodes the shown frame again.
+        if self.paused() {
+            self.seek(self.position(), true)?;
+        }
+        Ok(())
+    }
+
+    /// Get the picture adjustments in effect. Requires `VideoOptions::color_balance`.
+    pub fn color_balance(&self) -> Result<ColorBalance, Error> {
+        Ok(ColorBalance::read(&self.color_balance_filter()?))
+    }
+
+    fn color_balance_filter(&self) -> Result<gst::Element, Error> {
+        self.read()
+            .source
+            .by_name(BALANCE_NAME)
+            .ok_or(Error::ColorBalance)
+    }
+
+    fn audio_filter(&self, name: &'static str) -> Result<gst::Element, Error> {
+        self.read()
+            .source
+            .by_name(name)
+            .ok_or(Error::AudioFilter(name))
+    }
+
+    /// Set if the audio is muted or not.
+    pub fn set_muted(&self, muted: bool) {
+        self.write().source.set_property("mute", muted);
+    }
+
+    /// Get if the audio is muted or not.
+    pub fn muted(&self) -> bool {
+        self.read().source.property("mute")
+    }
+
+    /// Change which streams playbin plays and which helpers it plugs in, e.g. drop
+    /// [`PlayFlags::TEXT`] to hide subtitles. Ignored by pipelines without play flags.
+    pub fn set_play_flags(&self, flags: PlayFlags) {
+        playbin::set_flags(&self.write().source, flags);
+    }
+
+    /// Get playbin's current play flags, or None for a pipeline without them.
+    pub fn play_flags(&self) -> Option<PlayFlags> {
+        playbin::flags(&self.read().source)
+    }
+
+    /// Load subtitles from `uri`, e.g. a downloaded `.srt` file, in place of any
+    /// loaded before. They are rendered like embedded subtitles. playbin only reads
+    /// subtitle files when it starts, so the pipeline is reloaded and resumes at
+    /// the current position.
+    pub fn set_subtitle_uri(&self, uri: &url::Url) -> Result<(), Error> {
+        thread::debug_assert_may_block("set_subtitle_uri");
+        let (pipeline, live) = {
+            let inner = self.read();
+            inner.frame_buffer.lock().clear();
+            (inner.source.clone(), inner.live)
+        };
+        if pipeline.find_property("suburi").is_none() {
+            return Err(Error::SubtitleUri);
+        }
+        validate_uri(uri)?;
+        let position = pipeline
+            .query_position::<gst::ClockTime>()
+            .filter(|_| !live)
+            .map(|position| Duration::from_nanos(position.nseconds()));
+        resume_source(
+            &pipeline,
+            position,
+            Some(("suburi", uri)),
+            gst::State::Ready,
+        )
+    }
+
+    /// Get the media's audio tracks. Empty until the pipeline has prerolled, and
+    /// for pipelines that neither are a playbin nor post stream collections.
+    pub fn audio_tracks(&self) -> Vec<Track> {
+        let inner = self.read();
+        tracks::tracks(&inner.source, &inner.streams.lock(), TrackKind::Audio)
+    }
+
+    /// Play audio track `index`, counting from 0 in [`Video::audio_tracks`].
+    /// With [`Backend::Playbin3`] the switch keeps the decoders it can, so it is
+    /// seamless where playbin may stall briefly.
+    pub fn set_audio_track(&self, index: usize) -> Result<(), Error> {
+        let inner = self.read();
+        tracks::select(
+            &inner.source,
+            &inner.streams.lock(),
+            TrackKind::Audio,
+            Some(index),
+        )
+    }
+
+    /// Get the media's embedded subtitle tracks, and with playbin one loaded with
+    /// [`Video::set_subtitle_uri`].
+    pub fn subtitle_tracks(&self) -> Vec<Track> {
+        let inner = self.read();
+        tracks::tracks(&inner.source, &inner.streams.lock(), TrackKind::Subtitle)
+    }
+
+    /// Show subtitle track `index`, counting from 0 in [`Video::subtitle_tracks`],
+    /// or none.
+    pub fn set_subtitle_track(&self, index: Option<usize>) -> Result<(), Error> {
+        let inner = self.read();
+        tracks::select(
+            &inner.source,
+            &inner.streams.lock(),
+            TrackKind::Subtitle,
+            index,
+        )
+    }
+
+    /// Get the subtitle file loaded with [`Video::set_subtitle_uri`], if any.
+    pub fn subtitle_uri(&self) -> Option<url::Url> {
+        let inner = self.read();
+        inner.source.find_property("suburi")?;
+        let uri = inner.source.property::<Option<String>>("suburi")?;
+        url::Url::parse(&uri).ok()
+    }
+
+    /// Set the Pango font description used to render subtitles, e.g. `Sans 24`.
+    pub fn set_subtitle_font(&self, font: &str) {
+        let inner = self.write();
+        if inner.source.find_property("subtitle-font-desc").is_some() {
+            inner.source.set_property("subtitle-font-desc", font);
+        }
+    }
+
+    /// Get if the stream ended or not.
+    pub fn eos(&self) -> bool {
+        self.read().is_eos.load(Ordering::Acquire)
+    }
+
+    /// Get if the media will loop or not.
+    pub fn looping(&self) -> bool {
+        self.read().looping.load(Ordering::SeqCst)
+    }
+
+    /// Set if the media will loop or not. Enabling looping on a stream that
+    /// already ended restarts it immediately.
+    pub fn set_looping(&self, looping: bool) {
+        let mut inner = self.write();
+        inner.looping.store(looping, Ordering::SeqCst);
+        if looping
+            && inner.is_eos.load(Ordering::Acquire)
+            && let Err(err) = inner.restart_stream()
+        {
+            log::error!("failed to restart video for looping: {}", err);
+        }
+    }
+
+    /// Set if the media is paused or not.
+    ///
+    /// With a [`DecodeGovernor`] installed, a video asked to play while all slots are
+    /// taken stays paused and queued until one frees up; see [`VideoEvent::DecodeQueued`].
+    pub fn set_paused(&self, paused: bool) {
+        if paused {
+            if let Err(err) = self.write().set_paused(true) {
+                log::warn!("failed to pause video: {err}");
+            }
+            governor::release(self);
+        } else if governor::request_play(self)
+            && let Err(err) = self.write().set_paused(false)
+        {
+            log::warn!("failed to play video: {err}");
+        }
+    }
+
+    /// Set how this video ranks for a [`DecodeGovernor`] slot. Raising it may start
+    /// a queued video in place of a lower-priority one.
+    pub fn set_decode_priority(&self, priority: DecodePriority) {
+        self.read()
+            .decode_priority
+            .store(priority as u8, Ordering::Relaxed);
+        governor::priority_changed(self);
+    }
+
+    pub fn decode_priority(&self) -> DecodePriority {
+        DecodePriority::from_u8(self.read().decode_priority.load(Ordering::Relaxed))
+    }
+
+    /// Get if the media is paused or not.
     pub fn paused(&self) -> bool {
         self.read().paused()
     }
 
     /// Jumps to a specific position in the media.
     pub fn seek(&self, position: impl Into<Position>, accurate: bool) -> Result<(), Error> {
-        self.write().seek(position, accurate)
+        let inner = self.write();
+        inner.seeks.cancel();
+        inner.seek(position, accurate)
+    }
+
+    /// Accurately seek to `position` without piling up seeks, e.g. while a slider is
+    /// dragged. Only one seek runs at a time: calls made while it is in progress
+    /// replace each other, and the newest is issued once the running seek completes.
+    pub fn seek_smooth(&self, position: impl Into<Position>) -> Result<(), Error> {
+        let position = position.into();
+        let inner = self.read();
+        if inner.seeks.request(position)
+            && let Err(err) = inner.seek(position, true)
+        {
+            // No ASYNC_DONE follows a failed seek; let the next call go through.
+            inner.seeks.cancel();
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Start an interactive scrub, e.g. when the user grabs a seek bar. Playback
+    /// pauses, and until [`end_scrub`](Self::end_scrub) each [`seek`](Self::seek)
+    /// only moves the target: the video shows the keyframe nearest to it, decoding
+    /// keyframes only and seeking at most every 50 ms, so dragging stays fast.
+    ///
+    /// A playing video keeps its [`DecodeGovernor`] slot while scrubbing.
+    pub fn begin_scrub(&self) {
+        let mut inner = self.write();
+        if inner.scrub.lock().is_some() {
+            return;
+        }
+        let resume = !inner.paused();
+        if let Err(err) = inner.set_paused(true) {
+            log::warn!("failed to pause video for scrubbing: {err}");
+        }
+        *inner.scrub.lock() = Some(Scrub::new(resume));
+    }
+
+    /// Finish a scrub started with [`begin_scrub`](Self::begin_scrub): accurately
+    /// seek to the last target, leaving trick mode, and resume playback if it was
+    /// playing before.
+    ///
+    /// Resuming goes through the [`DecodeGovernor`] like [`set_paused`](Self::set_paused):
+    /// if the slot was taken meanwhile, the video stays paused and queued.
+    pub fn end_scrub(&self) -> Result<(), Error> {
+        let scrub = {
+            let mut inner = self.write();
+            let scrub = inner.scrub.lock().take();
+            let Some(scrub) = scrub else {
+                return Ok(());
+            };
+            if let Some(target) = scrub.target {
+                inner.seek(target, true)?;
+            }
+            scrub
+        };
+        // The governor locks the video, so ask it only after releasing ours.
+        if scrub.resume && governor::request_play(self) {
+            self.write().set_paused(false)?;
+        }
+        Ok(())
+    }
+
+    /// Whether a scrub started with [`begin_scrub`](Self::begin_scrub) is in progress.
+    pub fn is_scrubbing(&self) -> bool {
+        self.read().scrub.lock().is_some()
+    }
+
+    /// Audibly preview a boundary such as a trim in/out point: play `span` of
+    /// media centered on `at` at the given `rate`, then pause and return to `at`.
+    ///
+    /// A [`VideoEvent::PreviewFinished`] is emitted when the preview ends. Any
+    /// other seek cancels a running preview.
+    pub fn preview_boundary(&self, at: Duration, span: Duration, rate: f64) -> Result<(), Error> {
+        self.read().preview_boundary(at, span, rate)
+    }
+
+    /// Continuously loop playback between `range.0` and `range.1`, or stop looping
+    /// with `None`. Playback jumps to the start if it is outside the range.
+    ///
+    /// Each restart emits [`VideoEvent::Looped`]. Seeks within the range keep the loop.
+    pub fn set_loop_range(&self, range: Option<(Duration, Duration)>) -> Result<(), Error> {
+        let range = match range {
+            Some((start, end)) if start >= end => return Err(Error::LoopRange(start, end)),
+            Some((start, end)) => Some(TimeRange { start, end }),
+            None => None,
+        };
+        let position = self.position();
+        let inner = self.read();
+        *inner.loop_range.lock() = range;
+        let resume_at = match range {
+            Some(range) if position < range.start || position >= range.end => range.start,
+            _ => position,
+        };
+        inner.seek(resume_at, true)
+    }
+
+    /// Get the active A/B loop range, if any.
+    pub fn loop_range(&self) -> Option<(Duration, Duration)> {
+        self.read()
+            .loop_range
+            .lock()
+            .map(|range| (range.start, range.end))
+    }
+
+    /// Jump over each of `ranges` whenever playback enters it, e.g. to skip intros or
+    /// ad breaks. Replaces any previous segments; pass an empty list to stop skipping.
+    ///
+    /// Each jump emits [`VideoEvent::Skipped`].
+    pub fn set_skip_segments(&self, ranges: impl IntoIterator<Item = TimeRange>) {
+        let mut ranges: Vec<TimeRange> = ranges
+            .into_iter()
+            .filter(|range| range.start < range.end)
+            .collect();
+        ranges.sort();
+        *self.read().skip_segments.lock() = ranges;
+    }
+
+    /// Get the segments set with [`Video::set_skip_segments`].
+    pub fn skip_segments(&self) -> Vec<TimeRange> {
+        self.read().skip_segments.lock().clone()
+    }
+
+    /// Jump over silent spans while playing, e.g. pauses in a lecture recording.
+    ///
+    /// Silence is found by analyzing the audio with a separate pipeline. Unless
+    /// [`Video::detect_silence`] already ran, enabling this starts the analysis in
+    /// the background with a -50 dBFS threshold and 800 ms minimum length; skipping
+    /// begins once it finishes.
+    pub fn set_skip_silence(&self, enabled: bool) {
+        let inner = self.read();
+        inner.skip_silence.store(enabled, Ordering::SeqCst);
+        if !enabled
+            || inner.silence.lock().is_some()
+            || inner.silence_analyzing.swap(true, Ordering::SeqCst)
+        {
+            return;
+        }
+        let Some(uri) = inner.uri() else {
+            log::warn!("cannot skip silence: the video has no URI to analyze");
+            inner.silence_analyzing.store(false, Ordering::SeqCst);
+            return;
+        };
+        let silence = Arc::clone(&inner.silence);
+        let analyzing = Arc::clone(&inner.silence_analyzing);
+        std::thread::spawn(move || {
+            match silence::detect_silence(&uri, DEFAULT_SILENCE_THRESHOLD_DB, DEFAULT_MIN_SILENCE) {
+                Ok(ranges) => *silence.lock() = Some(ranges),
+                Err(err) => log::warn!("silence detection failed for {uri}: {err}"),
+            }
+            analyzing.store(false, Ordering::SeqCst);
+        });
+    }
+
+    /// Whether silent spans are skipped.
+    pub fn skip_silence(&self) -> bool {
+        self.read().skip_silence.load(Ordering::SeqCst)
+    }
+
+    /// Find spans where every audio channel stays below `threshold_db` (peak dBFS)
+    /// for at least `min_duration`. The result is also used by
+    /// [`Video::set_skip_silence`].
+    ///
+    /// Audio is decoded by a separate pipeline as fast as possible. Blocks until
+    /// done, so call it from a background thread.
+    pub fn detect_silence(
+        &self,
+        threshold_db: f64,
+        min_duration: Duration,
+    ) -> Result<Vec<TimeRange>, Error> {
+        thread::debug_assert_may_block("detect_silence");
+        let uri = self.read().uri().ok_or(Error::Uri)?;
+        let ranges = silence::detect_silence(&uri, threshold_db, min_duration)?;
+        *self.read().silence.lock() = Some(ranges.clone());
+        Ok(ranges)
+    }
+
+    /// Silent spans found by the latest silence analysis, or None if none ran yet.
+    pub fn silent_ranges(&self) -> Option<Vec<TimeRange>> {
+        self.read().silence.lock().clone()
+    }
+
+    /// Shift when frames are presented relative to the pipeline clock, in
+    /// milliseconds: positive values delay video, negative values show it early.
+    ///
+    /// Meant for aligning video with audio the app renders itself.
+    pub fn set_presentation_offset(&self, offset_ms: i64) {
+        let inner = self.read();
+        let offset = offset_ms.saturating_mul(1_000_000);
+        // Called from element builders on every frame; skip redundant property writes.
+        if inner.video_sink.property::<i64>("ts-offset") != offset {
+            inner.video_sink.set_property("ts-offset", offset);
+        }
+    }
+
+    /// Get the presentation offset in milliseconds.
+    pub fn presentation_offset(&self) -> i64 {
+        self.read().video_sink.property::<i64>("ts-offset") / 1_000_000
+    }
+
+    /// Present frames according to an app-provided timeline (e.g. a game engine or
+    /// an audio engine outside GStreamer) instead of the pipeline clock. A frame is
+    /// shown once `clock` reaches its timestamp; decoding stalls while it runs ahead.
+    ///
+    /// Intended for video-only pipelines. Seek the video when the app's timeline jumps.
+    pub fn set_external_clock(&self, clock: impl Fn() -> Duration + Send + Sync + 'static) {
+        let inner = self.read();
+        *inner.external_clock.lock() = Some(ExternalClock(Arc::new(clock)));
+        // Blocking instead of dropping bounds memory to a few decoded frames.
+        inner.video_sink.set_property("sync", false);
+        inner.video_sink.set_drop(false);
+        inner
+            .external_clock_max_buffers
+            .lock()
+            .get_or_insert_with(|| inner.video_sink.max_buffers());
+        inner.video_sink.set_max_buffers(4);
     }
 
-    /// Set the playback speed of the media.
+    /// Return to presenting frames by the pipeline clock.
+    pub fn clear_external_clock(&self) {
+        let inner = self.read();
+        *inner.external_clock.lock() = None;
+        inner.video_sink.set_property("sync", true);
+        inner.video_sink.set_drop(true);
+        if let Some(max_buffers) = inner.external_clock_max_buffers.lock().take() {
+            inner.video_sink.set_max_buffers(max_buffers);
+        }
+    }
+
+    /// Set the playback speed of the media. Negative speeds play backwards
+    /// without sound; frames are shown in order even where the decoder delivers
+    /// them out of order.
     pub fn set_speed(&self, speed: f64) -> Result<(), Error> {
         self.write().set_speed(speed)
     }
@@ -760,22 +3121,464 @@ impl Video {
         )
     }
 
+    /// Receive the playback position every `interval` while it changes, e.g. to
+    /// drive a position label without querying the pipeline on every render.
+    ///
+    /// Positions are sent from the worker thread; dropping the receiver stops them.
+    pub fn watch_position(&self, interval: Duration) -> std::sync::mpsc::Receiver<Duration> {
+        self.read().position_watchers.watch(interval)
+    }
+
     /// Get the media duration.
     pub fn duration(&self) -> Duration {
         self.read().duration
     }
 
+    /// Media time left to play in the current direction: up to the end (or
+    /// [`VideoOptions::stop_at`]) when playing forward, back to the start when the
+    /// speed is negative. Zero for live streams, which have no end.
+    pub fn remaining(&self) -> Duration {
+        let (end, live) = {
+            let inner = self.read();
+            (inner.stop_at.unwrap_or(inner.duration), inner.live)
+        };
+        if live {
+            return Duration::ZERO;
+        }
+        let position = self.position();
+        if self.speed() < 0.0 {
+            position
+        } else {
+            end.saturating_sub(position)
+        }
+    }
+
+    /// Wall-clock time until playback reaches the end (or the start, when playing
+    /// backwards) if it carries on at the current speed, e.g. 5 minutes of media at
+    /// 2x is 2.5 minutes. None for live streams and at zero speed.
+    pub fn eta_at_current_speed(&self) -> Option<Duration> {
+        let speed = self.speed().abs();
+        if self.read().live || speed < f64::EPSILON {
+            return None;
+        }
+        Some(self.remaining().div_f64(speed))
+    }
+
+    /// Get the time ranges that have been downloaded so far, for rendering the
+    /// "buffered" shading of a seek bar during progressive HTTP playback.
+    ///
+    /// Returns an empty list when the source does not report buffering ranges
+    /// (e.g. local files) or the duration is unknown.
+    pub fn buffered_ranges(&self) -> Vec<TimeRange> {
+        let inner = self.read();
+        let duration = inner.duration.as_nanos() as i64;
+        if duration <= 0 {
+            return Vec::new();
+        }
+
+        let mut query = gst::query::Buffering::new(gst::Format::Percent);
+        if !inner.source.query(&mut query) {
+            return Vec::new();
+        }
+
+        let to_time = |percent: i64| {
+            let percent = percent.clamp(0, PERCENT_MAX) as i128;
+            Duration::from_nanos((duration as i128 * percent / PERCENT_MAX as i128) as u64)
+        };
+        query
+            .ranges()
+            .into_iter()
+            .filter(|(start, stop)| {
+                start.format() == gst::Format::Percent && stop.format() == gst::Format::Percent
+            })
+            .map(|(start, stop)| TimeRange {
+                start: to_time(start.value()),
+                end: to_time(stop.value()),
+            })
+            .filter(|range| range.end > range.start)
+            .collect()
+    }
+
+    /// Subscribe to [`VideoEvent`]s emitted while the video plays.
+    ///
+    /// Events are sent from the worker thread; dropping the receiver unsubscribes.
+    pub fn subscribe(&self) -> std::sync::mpsc::Receiver<VideoEvent> {
+        self.read().events.subscribe()
+    }
+
+    /// Get a handle that does not keep the video alive.
+    pub fn downgrade(&self) -> WeakVideo {
+        WeakVideo(Arc::downgrade(&self.0))
+    }
+
+    /// Call `callback` for every [`VideoEvent`], directly on the worker thread as
+    /// the event happens, until the returned subscription is dropped.
+    ///
+    /// The callback delays frame delivery while it runs, so keep it short and never
+    /// block in it; use [`on_event_foreground`](Self::on_event_foreground) to update
+    /// gpui state. A panic in the callback is logged and does not stop playback.
+    /// A callback that needs the video should capture [`Video::downgrade`]: the
+    /// video owns its callbacks, so a clone in one keeps it alive.
+    pub fn on_event(
+        &self,
+        callback: impl Fn(&VideoEvent) + Send + Sync + 'static,
+    ) -> EventSubscription {
+        self.read().events.add_callback(callback)
+    }
+
+    /// Call `callback` for every [`VideoEvent`] on gpui's foreground thread, where it
+    /// can update entities and windows.
+    ///
+    /// Events are delivered as they arrive, several at once if more are queued by
+    /// the time the foreground thread runs. Delivery stops when the returned task
+    /// is dropped (call `detach` to keep it running) or when the video is dropped.
+    pub fn on_event_foreground(
+        &self,
+        cx: &gpui::App,
+        mut callback: impl FnMut(&VideoEvent, &mut gpui::App) + 'static,
+    ) -> gpui::Task<()> {
+        let mut events = self.read().events.subscribe_async();
+        cx.spawn(async move |cx| {
+            while let Some(batch) = next_events(&mut events).await {
+                let delivered = cx.update(|cx| {
+                    for event in &batch {
+                        callback(event, cx);
+                    }
+                });
+                // The app quit.
+                if delivered.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+
+    /// Forward every [`VideoEvent`] to the entity behind `cx`: `callback` runs on
+    /// gpui's foreground thread with the entity, and the entity is notified after
+    /// each batch so views re-render.
+    ///
+    /// Delivery stops once the entity or the video is dropped.
+    pub fn observe_in<T: 'static>(
+        &self,
+        cx: &mut gpui::Context<T>,
+        callback: impl Fn(&mut T, VideoEvent, &mut gpui::Context<T>) + 'static,
+    ) {
+        let mut events = self.read().events.subscribe_async();
+        cx.spawn(async move |this, cx| {
+            while let Some(batch) = next_events(&mut events).await {
+                let delivered = this.update(cx, |this, cx| {
+                    for event in batch {
+                        callback(this, event, cx);
+                    }
+                    cx.notify();
+                });
+                if delivered.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
     /// Restarts a stream.
     pub fn restart_stream(&self) -> Result<(), Error> {
         self.write().restart_stream()
     }
 
     /// Get the underlying GStreamer pipeline.
+    #[deprecated(
+        note = "a detached handle races the worker thread; use `with_pipeline` or `with_pipeline_mut`"
+    )]
     pub fn pipeline(&self) -> gst::Pipeline {
         self.read().source.clone()
     }
 
-    /// Get the current NV12 frame data if available.
+    /// Run `f` with the underlying GStreamer pipeline, e.g. to query it or read
+    /// element properties. Other calls on this video wait until `f` returns, so `f`
+    /// must not call back into it.
+    pub fn with_pipeline<R>(&self, f: impl FnOnce(&gst::Pipeline) -> R) -> R {
+        f(&self.read().source)
+    }
+
+    /// Run `f` with the underlying GStreamer pipeline to change it, e.g. to set
+    /// element properties or add a probe. No other call on this video, including
+    /// [`Video::with_pipeline`], runs at the same time, so `f` must not call back
+    /// into it.
+    ///
+    /// Change state and position through [`Video::set_paused`] and [`Video::seek`]
+    /// instead: the worker thread tracks them, and debug builds assert that `f`
+    /// left the pipeline's state alone.
+    pub fn with_pipeline_mut<R>(&self, f: impl FnOnce(&gst::Pipeline) -> R) -> R {
+        let inner = self.write();
+        let target_state = |pipeline: &gst::Pipeline| {
+            let (_, current, pending) = pipeline.state(gst::ClockTime::ZERO);
+            if pending == gst::State::VoidPending {
+                current
+            } else {
+                pending
+            }
+        };
+        let before = target_state(&inner.source);
+        let result = f(&inner.source);
+        debug_assert_eq!(
+            before,
+            target_state(&inner.source),
+            "with_pipeline_mut changed the pipeline state; use Video::set_paused"
+        );
+        result
+    }
+
+    /// Decode a thumbnail at `position` using a separate, paused pipeline, leaving
+    /// playback untouched. When `size` is given the frame is scaled to exactly
+    /// `(width, height)`; otherwise the natural video size is used.
+    pub fn thumbnail(
+        &self,
+        position: impl Into<Position>,
+        size: Option<(u32, u32)>,
+    ) -> Result<image::RgbaImage, Error> {
+        thread::debug_assert_may_block("thumbnail");
+        let uri = self.read().uri().ok_or(Error::Uri)?;
+        thumbnail::extract_frame(&uri, position.into(), size)
+    }
+
+    /// Choose the frame at `at` as this media's poster. The frame is saved to the
+    /// disk cache keyed by URI, so it is also available in later sessions through
+    /// [`Video::poster`] and [`cached_poster`](crate::cached_poster).
+    ///
+    /// Decodes on a separate pipeline and blocks until done.
+    pub fn set_poster_time(&self, at: Duration) -> Result<(), Error> {
+        thread::debug_assert_may_block("set_poster_time");
+        let uri = self.read().uri().ok_or(Error::Uri)?;
+        let frame = thumbnail::extract_frame(&uri, at.into(), None)?;
+        cache::save_poster(&uri, &frame)?;
+        *self.read().poster.lock() = Some(PosterImage::from_rgba(frame));
+        Ok(())
+    }
+
+    /// Get the poster frame chosen with [`Video::set_poster_time`], loading it from
+    /// the disk cache the first time. [`VideoElement`](crate::VideoElement) paints it
+    /// until playback produces a frame, unless another poster is given.
+    pub fn poster(&self) -> Option<Arc<gpui::RenderImage>> {
+        let inner = self.read();
+        let mut poster = inner.poster.lock();
+        if !inner.poster_loaded.swap(true, Ordering::SeqCst)
+            && poster.is_none()
+            && let Some(uri) = inner.uri()
+            && let Some(image) = cache::cached_poster(&uri)
+        {
+            *poster = Some(PosterImage::from_rgba(image));
+        }
+        poster.as_ref().map(|poster| poster.0.clone())
+    }
+
+    /// Extract the audio track (or the part within `range`) to a file at `path`.
+    ///
+    /// Runs a separate transcode pipeline and blocks until it finishes, so call it
+    /// from a background thread.
+    pub fn export_audio(
+        &self,
+        range: Option<TimeRange>,
+        format: AudioFormat,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<(), Error> {
+        thread::debug_assert_may_block("export_audio");
+        let uri = self.read().uri().ok_or(Error::Uri)?;
+        export::export_audio(&uri, range, format, path.as_ref())
+    }
+
+    /// Start recording what plays to `path`, e.g. to capture a clip.
+    ///
+    /// Frames and audio are copied as they are played and encoded by a separate
+    /// pipeline, so the file follows playback: seeks, speed changes, and pauses
+    /// show up in it as they happened. A recording already in progress is stopped first.
+    pub fn record_to(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        mut options: RecordOptions,
+    ) -> Result<(), Error> {
+        let inner = self.read();
+        if inner.audio_only {
+            options.video_codec = None;
+        }
+        let recorder = Recorder::start(&inner.source, path.as_ref(), options)?;
+        if let Some(previous) = inner.recorder.lock().replace(recorder) {
+            previous.finish(Arc::clone(&inner.events));
+        }
+        Ok(())
+    }
+
+    /// Pause the recording; playback carries on. The file continues seamlessly
+    /// from where it was paused once [`Video::resume_recording`] is called.
+    pub fn pause_recording(&self) {
+        if let Some(recorder) = self.read().recorder.lock().as_ref() {
+            recorder.set_paused(true);
+        }
+    }
+
+    pub fn resume_recording(&self) {
+        if let Some(recorder) = self.read().recorder.lock().as_ref() {
+            recorder.set_paused(false);
+        }
+    }
+
+    /// Stop recording. The file is finished in the background, after which
+    /// [`VideoEvent::RecordingFinished`] is emitted.
+    pub fn stop_recording(&self) {
+        let inner = self.read();
+        let recorder = inner.recorder.lock().take();
+        if let Some(recorder) = recorder {
+            recorder.finish(Arc::clone(&inner.events));
+        }
+    }
+
+    /// Whether a recording is in progress, including a paused one.
+    pub fn is_recording(&self) -> bool {
+        self.read().recorder.lock().is_some()
+    }
+
+    /// Whether the recording is paused.
+    pub fn is_recording_paused(&self) -> bool {
+        self.read()
+            .recorder
+            .lock()
+            .as_ref()
+            .is_some_and(Recorder::is_paused)
+    }
+
+    /// Save `start..end` to a new file at `path`, e.g. to trim a clip.
+    ///
+    /// Streams are copied without re-encoding unless [`ClipOptions::profile`] is
+    /// set. The export runs on a background thread and reports
+    /// [`VideoEvent::ClipProgress`], then [`VideoEvent::ClipExported`] or
+    /// [`VideoEvent::Error`]; this only fails if the range or URI is invalid.
+    pub fn export_clip(
+        &self,
+        start: Duration,
+        end: Duration,
+        path: impl AsRef<std::path::Path>,
+        options: ClipOptions,
+    ) -> Result<(), Error> {
+        if start >= end {
+            return Err(Error::ClipRange(start, end));
+        }
+        let inner = self.read();
+        let uri = inner.uri().ok_or(Error::Uri)?;
+        let events = Arc::clone(&inner.events);
+        let path = path.as_ref().to_path_buf();
+        std::thread::spawn(move || {
+            let range = TimeRange { start, end };
+            let result = export::export_clip(&uri, range, &path, &options, |progress| {
+                events.emit(VideoEvent::ClipProgress {
+                    path: path.clone(),
+                    progress,
+                });
+            });
+            match result {
+                Ok(()) => events.emit(VideoEvent::ClipExported(path)),
+                Err(err) => {
+                    log::error!("failed to export clip to {}: {}", path.display(), err);
+                    events.emit(VideoEvent::Error(err.to_string()));
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Write an index of every video frame (PTS, DTS, duration, keyframe flag, and
+    /// size in bytes) to `path`, returning the number of frames. The index is JSON
+    /// if `path` ends in `.json` and CSV otherwise; missing timestamps are empty/`null`.
+    ///
+    /// The stream is only parsed, not decoded, so this is much faster than
+    /// [`Video::export_frames`]. Blocks until done, so call it from a background thread.
+    pub fn export_frame_index(&self, path: impl AsRef<std::path::Path>) -> Result<usize, Error> {
+        thread::debug_assert_may_block("export_frame_index");
+        let uri = self.read().uri().ok_or(Error::Uri)?;
+        export::export_frame_index(&uri, path.as_ref())
+    }
+
+    /// Write embedded subtitle stream `stream_index` (0 for the first subtitle
+    /// stream) to `path` as SubRip or WebVTT, returning the number of cues written.
+    ///
+    /// Text and ASS/SSA streams are supported; bitmap subtitles (DVD, PGS) fail
+    /// with [`Error::SubtitleCodec`]. The container is only parsed, not decoded, but
+    /// the whole file is read; this blocks, so call it from a background thread.
+    pub fn export_subtitles(
+        &self,
+        stream_index: usize,
+        format: SubtitleFormat,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<usize, Error> {
+        thread::debug_assert_may_block("export_subtitles");
+        let uri = self.read().uri().ok_or(Error::Uri)?;
+        export::export_subtitles(&uri, stream_index, format, path.as_ref())
+    }
+
+    /// Score how much the picture changes during each second of the media, e.g. to
+    /// let users skip to where something happens in surveillance footage. Entry `i`
+    /// covers second `i` and ranges from 0.0 (static) to 1.0; pass the result to
+    /// [`TimelineElement::heatmap`](crate::TimelineElement::heatmap) to draw it.
+    ///
+    /// Frames are decoded by a separate low-resolution pipeline as fast as possible.
+    /// Blocks until done, so call it from a background thread.
+    pub fn analyze_motion(&self) -> Result<Vec<f32>, Error> {
+        thread::debug_assert_may_block("analyze_motion");
+        let uri = self.read().uri().ok_or(Error::Uri)?;
+        motion::motion_scores(&uri)
+    }
+
+    /// Write every `every_nth` frame (or only those within `range`) to `dir` as a
+    /// numbered image sequence (`frame_000000.png`, ...), returning how many images
+    /// were written. `progress` is called after each image with the count so far
+    /// and the frame's timestamp.
+    ///
+    /// Frames are decoded by a separate pipeline as fast as possible rather than in
+    /// real time. Blocks until done, so call it from a background thread.
+    pub fn export_frames(
+        &self,
+        range: Option<TimeRange>,
+        every_nth: usize,
+        dir: impl AsRef<std::path::Path>,
+        format: ImageFormat,
+        progress: Option<&mut dyn FnMut(usize, Duration)>,
+    ) -> Result<usize, Error> {
+        thread::debug_assert_may_block("export_frames");
+        let uri = self.read().uri().ok_or(Error::Uri)?;
+        export::export_frames(&uri, range, every_nth, dir.as_ref(), format, progress)
+    }
+
+    /// Capture the currently displayed frame as an RGBA image, using the same
+    /// color conversion as the on-screen element.
+    pub fn screenshot(&self) -> Result<image::RgbaImage, Error> {
+        let (width, height) = self.size();
+        let (width, height) = (width as u32, height as u32);
+        let pixels = self
+            .current_frame()
+            .with_data(|data| {
+                convert::frame_to(
+                    PixelOrder::Rgba,
+                    &self.frame_layout(),
+                    self.color_space(),
+                    data,
+                    width,
+                    height,
+                )
+            })
+            .ok()
+            .flatten()
+            .ok_or(Error::NoFrame)?;
+        image::RgbaImage::from_raw(width, height, pixels).ok_or(Error::NoFrame)
+    }
+
+    /// Capture the currently displayed frame and save it to `path`. The image
+    /// format is picked from the file extension (e.g. `.png`).
+    pub fn save_screenshot(&self, path: impl AsRef<std::path::Path>) -> Result<(), Error> {
+        self.screenshot()?.save(path)?;
+        Ok(())
+    }
+
+    /// Get the current frame data if available. Frames are NV12, or P010 for
+    /// HDR sources (see [`Video::is_hdr`]). This copies the frame; see
+    /// [`with_current_frame`](Self::with_current_frame) to borrow it instead.
     pub fn current_frame_data(&self) -> Option<(Vec<u8>, u32, u32)> {
         let inner = self.read();
 
@@ -790,11 +3593,178 @@ impl Video {
         None
     }
 
+    /// Run `f` on the current frame where it is mapped, without copying it as
+    /// [`current_frame_data`](Self::current_frame_data) does. Returns None if there
+    /// is no frame yet.
+    ///
+    /// The frame stays referenced while `f` runs, but no locks are held, so `f` may
+    /// call back into the video.
+    pub fn with_current_frame<R>(&self, f: impl FnOnce(FrameRef<'_>) -> R) -> Option<R> {
+        let frame = self.current_frame();
+        self.lend_frame(&frame, f).ok().flatten()
+    }
+
+    /// Pop the oldest buffered frame and run `f` on it without copying it, as
+    /// [`pop_buffered_frame`](Self::pop_buffered_frame) does. Returns `Ok(None)`
+    /// while the buffer is empty.
+    pub fn with_buffered_frame<R>(
+        &self,
+        f: impl FnOnce(FrameRef<'_>) -> R,
+    ) -> Result<Option<R>, FrameError> {
+        match self.pop_buffered() {
+            Some(frame) => self.lend_frame(&frame, f),
+            None => Ok(None),
+        }
+    }
+
+    fn lend_frame<R>(
+        &self,
+        frame: &Frame,
+        f: impl FnOnce(FrameRef<'_>) -> R,
+    ) -> Result<Option<R>, FrameError> {
+        let (width, height) = self.size();
+        let layout = self.frame_layout();
+        frame.with_data(|data| {
+            f(FrameRef::new(data, layout, width as u32, height as u32).with_pts(frame.pts()))
+        })
+    }
+
+    /// The latest frame, without copying its data.
+    pub(crate) fn current_frame(&self) -> Frame {
+        self.read().frame.lock().clone()
+    }
+
+    /// Pop the oldest buffered frame without copying its data.
+    pub(crate) fn pop_buffered(&self) -> Option<Frame> {
+        self.read().frame_buffer.lock().pop_front()
+    }
+
+    /// Media time buffered frames are presented against, or None to present them
+    /// as they arrive: while paused, when an external clock already paces the
+    /// worker, or when the position is unknown.
+    fn presentation_clock(&self) -> Option<Duration> {
+        let inner = self.read();
+        if inner.external_clock.lock().is_some() || inner.paused() {
+            return None;
+        }
+        let position = inner.source.query_position::<gst::ClockTime>()?.nseconds() as i64;
+        // A positive offset delays frames, so they are due that much later.
+        let offset = inner.video_sink.property::<i64>("ts-offset");
+        Some(Duration::from_nanos(
+            position.saturating_sub(offset).max(0) as u64
+        ))
+    }
+
+    /// Whether a buffered frame's time has come on the presentation clock.
+    fn due_check(&self) -> impl Fn(&Frame) -> bool + use<> {
+        let clock = self.presentation_clock();
+        let reverse = self.speed() < 0.0;
+        move |frame| match (clock, frame.stream_time()) {
+            (Some(clock), Some(time)) if reverse => time + PRESENTATION_SLACK >= clock,
+            (Some(clock), Some(time)) => time <= clock + PRESENTATION_SLACK,
+            _ => true,
+        }
+    }
+
+    /// Pop the buffered frames whose time has come and return the newest of them.
+    /// Frames still ahead of the playback clock stay buffered for later paints.
+    pub(crate) fn pop_due_frame(&self) -> Option<Frame> {
+        let due = self.due_check();
+        let inner = self.read();
+        let mut buffer = inner.frame_buffer.lock();
+        let mut latest = None;
+        while buffer.front().is_some_and(&due) {
+            latest = buffer.pop_front();
+        }
+        latest
+    }
+
+    /// How many buffered frames are due, so views repaint when a held frame's time
+    /// comes even if no new frame arrives.
+    pub(crate) fn due_buffered_len(&self) -> usize {
+        if self.buffered_len() == 0 {
+            return 0;
+        }
+        let due = self.due_check();
+        self.read()
+            .frame_buffer
+            .lock()
+            .iter()
+            .take_while(|frame| due(frame))
+            .count()
+    }
+
+    /// In pull-on-paint mode, make the newest sample waiting in the video sink the
+    /// current frame. Returns whether that is a new frame.
+    pub(crate) fn pull_latest_frame(&self) -> bool {
+        let inner = self.read();
+        if !inner.pull_on_paint.load(Ordering::Acquire) {
+            return false;
+        }
+        let latest = if inner.source.state(gst::ClockTime::ZERO).1 == gst::State::Playing {
+            // Anything older than the newest sample is already stale.
+            std::iter::from_fn(|| inner.video_sink.try_pull_sample(gst::ClockTime::ZERO)).last()
+        } else {
+            inner.video_sink.try_pull_preroll(gst::ClockTime::ZERO)
+        };
+        let Some(latest) = latest.map(Frame::new) else {
+            return false;
+        };
+        {
+            let mut frame = inner.frame.lock();
+            // The preroll sample is handed out again on every pull while paused.
+            if frame.shares_buffer(&latest) {
+                return false;
+            }
+            *frame = latest;
+        }
+
+        let now = Instant::now();
+        let previous = std::mem::replace(&mut *inner.last_frame_time.lock(), now);
+        inner
+            .stats
+            .lock()
+            .record_frame(now.duration_since(previous));
+        inner.upload_frame.store(true, Ordering::SeqCst);
+        inner.frame_generation.fetch_add(1, Ordering::SeqCst);
+        inner.frame_signal.notify();
+        true
+    }
+
     /// Returns true if a new frame arrived since last check and resets the flag.
+    ///
+    /// Only one caller observes each frame; when several views share a video,
+    /// compare [`Video::frame_generation`] instead.
     pub fn take_frame_ready(&self) -> bool {
         self.read().upload_frame.swap(false, Ordering::SeqCst)
     }
 
+    /// Counter that increases with every decoded frame. Unlike
+    /// [`Video::take_frame_ready`], reading it does not consume anything, so any
+    /// number of views can remember the last value they painted.
+    pub fn frame_generation(&self) -> u64 {
+        self.read().frame_generation.load(Ordering::SeqCst)
+    }
+
+    /// Switch pull-on-paint mode, for minimal-latency mirroring of a live source.
+    ///
+    /// Normally the worker thread pulls every frame as it is due and queues it in
+    /// the frame buffer. In pull-on-paint mode elements instead take the newest frame
+    /// straight from the video sink when they paint, skipping any that went stale in
+    /// between. Latency drops to under a display frame, at the cost of uneven frame
+    /// pacing, and the frame buffer, subtitles, and recording receive no frames.
+    pub fn set_pull_on_paint(&self, enabled: bool) {
+        let inner = self.read();
+        inner.pull_on_paint.store(enabled, Ordering::Release);
+        if enabled {
+            inner.frame_buffer.lock().clear();
+        }
+    }
+
+    pub fn pull_on_paint(&self) -> bool {
+        self.read().pull_on_paint.load(Ordering::Acquire)
+    }
+
     /// Configure the frame buffer capacity (0 disables buffering).
     pub fn set_frame_buffer_capacity(&self, capacity: usize) {
         let inner = self.read();
@@ -816,25 +3786,161 @@ impl Video {
         self.read().frame_buffer_capacity.load(Ordering::SeqCst)
     }
 
-    /// Pop the oldest buffered frame, returning raw NV12 bytes with width/height.
-    /// Returns None if the buffer is empty or mapping fails.
-    pub fn pop_buffered_frame(&self) -> Option<(Vec<u8>, u32, u32)> {
+    /// Pop the oldest buffered frame.
+    ///
+    /// Returns `Ok(None)` while the buffer is empty, so callers know to wait, and an
+    /// error if the frame could not be read; that frame is dropped either way.
+    pub fn pop_buffered_frame(&self) -> Result<Option<VideoFrame>, FrameError> {
+        match self.pop_buffered() {
+            Some(frame) => self.video_frame(&frame),
+            None => Ok(None),
+        }
+    }
+
+    /// Map `frame` with this video's layout and size.
+    pub(crate) fn video_frame(&self, frame: &Frame) -> Result<Option<VideoFrame>, FrameError> {
         let (width, height) = self.size();
-        let inner = self.read();
-        let maybe_frame = inner.frame_buffer.lock().pop_front();
-        if let Some(frame) = maybe_frame
-            && let Some(readable) = frame.readable()
-        {
-            let data = readable.as_slice().to_vec();
-            if !data.is_empty() {
-                return Some((data, width as u32, height as u32));
+        VideoFrame::new(frame, self.frame_layout(), width as u32, height as u32)
+    }
+
+    /// Iterate over every decoded frame from the current position to the end of the
+    /// stream, blocking for each one, e.g. for batch processing.
+    ///
+    /// The video sink stops dropping frames while the iterator exists, so none are
+    /// skipped; decoding is paced by the consumer and the pipeline clock. Frames are
+    /// not delivered to elements or the frame buffer meanwhile. Use one iterator at
+    /// a time, and make sure the video is playing.
+    pub fn frames_blocking(&self) -> FrameIter {
+        FrameIter::new(self.clone())
+    }
+
+    /// Decode this video's frames one at a time at the caller's pace, on a separate
+    /// pipeline that leaves playback alone. See [`FrameExtractor`].
+    pub fn frames(&self) -> Result<FrameExtractor, Error> {
+        thread::debug_assert_may_block("frames");
+        let uri = self.read().uri().ok_or(Error::Uri)?;
+        FrameExtractor::new(&uri)
+    }
+
+    /// Block until a frame is available or `timeout` passes, returning `Ok(None)`
+    /// on timeout.
+    ///
+    /// Buffered frames are returned oldest first. With buffering disabled, this
+    /// waits for a frame newer than the one shown when it was called. Fails with
+    /// [`FrameError::Eos`] once the stream has ended and no frames are left.
+    pub fn wait_for_frame(&self, timeout: Duration) -> Result<Option<VideoFrame>, FrameError> {
+        if !timeout.is_zero() {
+            thread::debug_assert_may_block("wait_for_frame");
+        }
+        let deadline = Instant::now() + timeout;
+        let (signal, start_generation) = {
+            let inner = self.read();
+            (
+                Arc::clone(&inner.frame_signal),
+                inner.frame_generation.load(Ordering::SeqCst),
+            )
+        };
+        loop {
+            if let Some(frame) = self.pop_buffered_frame()? {
+                return Ok(Some(frame));
+            }
+            if self.frame_generation() != start_generation
+                && let Some(frame) = self.video_frame(&self.current_frame())?
+            {
+                return Ok(Some(frame));
+            }
+            if self.eos() {
+                return Err(FrameError::Eos);
+            }
+
+            let mut guard = signal.lock.lock();
+            // Re-check under the lock; the worker notifies while holding it.
+            if self.buffered_len() > 0 || self.frame_generation() != start_generation || self.eos()
+            {
+                continue;
+            }
+            if signal.condvar.wait_until(&mut guard, deadline).timed_out() {
+                return Ok(None);
             }
         }
-        None
+    }
+
+    /// Get the audio spectrum magnitudes (in dB, one per band) for the current playback
+    /// position. Empty unless the video was created with `VideoOptions::spectrum_bands`.
+    pub fn spectrum(&self) -> Vec<f32> {
+        let position = self.position();
+        self.read().spectrum.lock().advance(position)
+    }
+
+    /// Spans of the media that have played so far, merged and sorted. Seeks and
+    /// loops start new spans; parts watched twice count once.
+    pub fn watched_ranges(&self) -> Vec<TimeRange> {
+        self.read().watched.lock().ranges()
+    }
+
+    /// Fraction of the duration covered by [`watched_ranges`](Self::watched_ranges),
+    /// from 0.0 to 1.0. Always 0.0 for live streams.
+    pub fn watched_fraction(&self) -> f32 {
+        let inner = self.read();
+        inner.watched.lock().fraction(inner.duration)
+    }
+
+    /// Set the watched percentages (1 to 100) reported by
+    /// [`VideoEvent::ProgressMilestone`]. Milestones already reported are not sent again.
+    pub fn set_progress_milestones(&self, percents: impl IntoIterator<Item = u8>) {
+        self.read().watched.lock().set_milestones(percents);
+    }
+
+    pub fn progress_milestones(&self) -> Vec<u8> {
+        self.read().watched.lock().milestones()
+    }
+
+    /// Get the RMS and peak level of each audio channel at the current playback
+    /// position, e.g. to drive a VU meter. None unless the video was created with
+    /// `VideoOptions::audio_levels` and audio has started playing.
+    pub fn audio_levels(&self) -> Option<Vec<ChannelLevel>> {
+        self.read().levels.lock().current()
+    }
+
+    /// Get the most recent audio up to the current playback position as mono
+    /// samples (nominally `-1.0..=1.0`), oldest first, covering `VideoOptions::audio_window`.
+    /// Empty unless that option was set.
+    pub fn recent_audio_window(&self) -> Vec<f32> {
+        let position = self.position();
+        self.read().audio_window.lock().window(position)
+    }
+
+    /// Whether the media has no video stream and was opened with
+    /// `VideoOptions::allow_audio_only`.
+    pub fn is_audio_only(&self) -> bool {
+        self.read().audio_only
     }
 
     /// Number of frames currently buffered.
     pub fn buffered_len(&self) -> usize {
         self.read().frame_buffer.lock().len()
     }
+
+    /// Estimate the bytes held by this video's decoded and converted frames.
+    pub fn memory_usage(&self) -> MemoryUsage {
+        self.read().memory.usage()
+    }
+
+    /// Record the size of the frame last converted for display.
+    pub(crate) fn set_converted_bytes(&self, bytes: usize) {
+        self.read().memory.set_converted(bytes);
+    }
+
+    /// Record how long the frame last painted took to convert for display.
+    pub(crate) fn record_conversion(&self, time: Duration) {
+        self.read().stats.lock().record_conversion(time);
+    }
+
+    /// Record that a frame pulled from the sink at `pulled_at` was just painted.
+    pub(crate) fn record_paint_latency(&self, pulled_at: Instant) {
+        self.read()
+            .stats
+            .lock()
+            .record_paint_latency(pulled_at.elapsed());
+    }
 }
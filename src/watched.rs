@@ -0,0 +1,115 @@
+use crate::video::TimeRange;
+use std::time::{Duration, Instant};
+
+/// Milestones reported by default, in percent of the media watched.
+pub(crate) const DEFAULT_MILESTONES: [u8; 4] = [25, 50, 75, 95];
+
+/// How often the worker samples the position for watched ranges.
+const WATCH_SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Position changes larger than this between samples are seeks, not playback.
+const MAX_PLAYED_STEP: Duration = Duration::from_secs(2);
+
+/// Spans of the media that have actually played, merged and sorted, and the
+/// watched-percent milestones they have passed.
+#[derive(Debug)]
+pub(crate) struct WatchTracker {
+    ranges: Vec<TimeRange>,
+    last_position: Option<Duration>,
+    next_sample: Instant,
+    /// Sorted, without duplicates.
+    milestones: Vec<u8>,
+    reported: Vec<u8>,
+}
+
+impl WatchTracker {
+    pub(crate) fn new(milestones: impl IntoIterator<Item = u8>) -> Self {
+        let mut tracker = Self {
+            ranges: Vec::new(),
+            last_position: None,
+            next_sample: Instant::now(),
+            milestones: Vec::new(),
+            reported: Vec::new(),
+        };
+        tracker.set_milestones(milestones);
+        tracker
+    }
+
+    /// Replace the milestones. Ones already passed are not reported again.
+    pub(crate) fn set_milestones(&mut self, milestones: impl IntoIterator<Item = u8>) {
+        self.milestones = milestones
+            .into_iter()
+            .filter(|percent| (1..=100).contains(percent))
+            .collect();
+        self.milestones.sort_unstable();
+        self.milestones.dedup();
+    }
+
+    pub(crate) fn milestones(&self) -> Vec<u8> {
+        self.milestones.clone()
+    }
+
+    /// Whether the worker should sample the position again.
+    pub(crate) fn is_due(&self) -> bool {
+        Instant::now() >= self.next_sample
+    }
+
+    /// Record the playback position. Time since the previous sample counts as
+    /// watched unless playback jumped, as it does on seeks.
+    pub(crate) fn sample(&mut self, position: Duration) {
+        self.next_sample = Instant::now() + WATCH_SAMPLE_INTERVAL;
+        if let Some(last) = self.last_position.replace(position)
+            && position > last
+            && position - last <= MAX_PLAYED_STEP
+        {
+            self.insert(TimeRange {
+                start: last,
+                end: position,
+            });
+        }
+    }
+
+    fn insert(&mut self, range: TimeRange) {
+        self.ranges.push(range);
+        self.ranges.sort_unstable();
+        let mut merged: Vec<TimeRange> = Vec::with_capacity(self.ranges.len());
+        for range in self.ranges.drain(..) {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        self.ranges = merged;
+    }
+
+    pub(crate) fn ranges(&self) -> Vec<TimeRange> {
+        self.ranges.clone()
+    }
+
+    /// Fraction of `duration` watched, from 0.0 to 1.0.
+    pub(crate) fn fraction(&self, duration: Duration) -> f32 {
+        if duration.is_zero() {
+            return 0.0;
+        }
+        let watched: Duration = self
+            .ranges
+            .iter()
+            .map(|range| range.end - range.start)
+            .sum();
+        (watched.as_secs_f64() / duration.as_secs_f64()).min(1.0) as f32
+    }
+
+    /// Milestones reached since the last call, lowest first.
+    pub(crate) fn take_reached(&mut self, duration: Duration) -> Vec<u8> {
+        let percent = self.fraction(duration) * 100.0;
+        let reached: Vec<u8> = self
+            .milestones
+            .iter()
+            .copied()
+            .filter(|milestone| f32::from(*milestone) <= percent)
+            .filter(|milestone| !self.reported.contains(milestone))
+            .collect();
+        self.reported.extend(&reached);
+        reached
+    }
+}
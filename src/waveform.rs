@@ -0,0 +1,122 @@
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub(crate) const AUDIO_TAP_NAME: &str = "gpui_audio_tap";
+
+/// Audio decoded ahead of playback is kept this much longer than the window, so
+/// the window can still be cut at the playback position.
+const LOOKAHEAD: Duration = Duration::from_secs(2);
+
+/// Build the elements inserted into playbin's audio filter chain for the tap:
+/// samples are converted to interleaved f32 so the probe can read them directly.
+pub(crate) fn make_audio_tap() -> Result<[gst::Element; 2], glib::BoolError> {
+    let caps = gst::Caps::builder("audio/x-raw")
+        .field("format", "F32LE")
+        .field("layout", "interleaved")
+        .build();
+    Ok([
+        gst::ElementFactory::make("audioconvert").build()?,
+        gst::ElementFactory::make("capsfilter")
+            .name(AUDIO_TAP_NAME)
+            .property("caps", &caps)
+            .build()?,
+    ])
+}
+
+/// Copy every buffer leaving the tap in `pipeline` into `window`. Does nothing if
+/// the pipeline has no tap.
+pub(crate) fn attach_audio_tap(pipeline: &gst::Pipeline, window: Arc<Mutex<AudioWindow>>) {
+    let Some(pad) = pipeline
+        .by_name(AUDIO_TAP_NAME)
+        .and_then(|tap| tap.static_pad("src"))
+    else {
+        return;
+    };
+    pad.add_probe(gst::PadProbeType::BUFFER, move |pad, info| {
+        if let Some(buffer) = info.buffer()
+            && let Some(pts) = buffer.pts()
+            && let Some(caps) = pad.current_caps()
+            && let Some(s) = caps.structure(0)
+            && let (Ok(rate), Ok(channels)) = (s.get::<i32>("rate"), s.get::<i32>("channels"))
+            && let Some(segment) = pad.sticky_event::<gst::event::Segment>(0)
+            && let Some(stream_time) = segment
+                .segment()
+                .downcast_ref::<gst::ClockTime>()
+                .and_then(|segment| segment.to_stream_time(pts))
+            && let Ok(map) = buffer.map_readable()
+        {
+            window.lock().push(
+                Duration::from_nanos(stream_time.nseconds()),
+                rate.max(1) as u32,
+                channels.max(1) as usize,
+                &map,
+            );
+        }
+        gst::PadProbeReturn::Ok
+    });
+}
+
+/// Recent audio as mono samples, kept by stream time so the window can be cut at
+/// the playback position rather than at the decoder, which runs ahead.
+#[derive(Debug)]
+pub(crate) struct AudioWindow {
+    length: Duration,
+    /// Stream time of each chunk's first sample, its sample rate, and the samples.
+    chunks: VecDeque<(Duration, u32, Vec<f32>)>,
+}
+
+impl AudioWindow {
+    pub(crate) fn new(length: Duration) -> Self {
+        Self {
+            length,
+            chunks: VecDeque::new(),
+        }
+    }
+
+    /// Mix a buffer of interleaved f32 samples down to mono and keep it.
+    fn push(&mut self, at: Duration, rate: u32, channels: usize, data: &[u8]) {
+        // Time going backwards means a seek or loop; older audio no longer applies.
+        if self.chunks.back().is_some_and(|(last, _, _)| at < *last) {
+            self.chunks.clear();
+        }
+        let samples = data
+            .chunks_exact(4 * channels)
+            .map(|frame| {
+                let sum: f32 = frame
+                    .chunks_exact(4)
+                    .map(|sample| f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]))
+                    .sum();
+                sum / channels as f32
+            })
+            .collect();
+        self.chunks.push_back((at, rate, samples));
+
+        let keep_from = at.saturating_sub(self.length + LOOKAHEAD);
+        while let Some((start, rate, samples)) = self.chunks.front()
+            && *start + Duration::from_secs_f64(samples.len() as f64 / *rate as f64) < keep_from
+        {
+            self.chunks.pop_front();
+        }
+    }
+
+    /// Samples from `length` before `position` up to `position`, oldest first.
+    pub(crate) fn window(&self, position: Duration) -> Vec<f32> {
+        let start = position.saturating_sub(self.length);
+        let mut window = Vec::new();
+        for (at, rate, samples) in &self.chunks {
+            let index = |time: Duration| {
+                ((time.saturating_sub(*at).as_secs_f64() * *rate as f64) as usize)
+                    .min(samples.len())
+            };
+            let (first, last) = (index(start), index(position));
+            if first < last {
+                window.extend_from_slice(&samples[first..last]);
+            }
+        }
+        window
+    }
+}